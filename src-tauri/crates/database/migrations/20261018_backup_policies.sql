@@ -0,0 +1,8 @@
+-- 单个游戏的存档快照保留策略（无记录时使用 config 中的默认策略）
+CREATE TABLE IF NOT EXISTS "game_backup_policies" (
+    "game_id"      TEXT    PRIMARY KEY,
+    "keep_last"    INTEGER NOT NULL,
+    "keep_daily"   INTEGER NOT NULL,
+    "keep_weekly"  INTEGER NOT NULL,
+    FOREIGN KEY ("game_id") REFERENCES "games" ("id") ON DELETE CASCADE
+);
@@ -0,0 +1,20 @@
+-- 每个游戏最近一次成功备份时的存档指纹，定时备份据此跳过没有变化的存档
+CREATE TABLE IF NOT EXISTS "game_backup_fingerprints" (
+    "game_id"      TEXT     PRIMARY KEY,
+    "fingerprint"  TEXT     NOT NULL,
+    "updated_at"   DATETIME NOT NULL,
+    FOREIGN KEY ("game_id") REFERENCES "games" ("id") ON DELETE CASCADE
+);
+
+-- 定时备份的执行记录
+CREATE TABLE IF NOT EXISTS "backup_runs" (
+    "id"           INTEGER  PRIMARY KEY AUTOINCREMENT,
+    "started_at"   DATETIME NOT NULL,
+    "finished_at"  DATETIME NOT NULL,
+    "backed_up"    INTEGER  NOT NULL DEFAULT 0,
+    "skipped"      INTEGER  NOT NULL DEFAULT 0,
+    "failed"       INTEGER  NOT NULL DEFAULT 0,
+    "errors"       TEXT
+);
+
+CREATE INDEX IF NOT EXISTS "idx_backup_runs_started" ON "backup_runs" ("started_at");
@@ -0,0 +1,2 @@
+-- 游戏的存档位置列表（JSON 数组），非空时取代 save_data_path
+ALTER TABLE "games" ADD COLUMN "save_locations" TEXT NOT NULL DEFAULT '[]';
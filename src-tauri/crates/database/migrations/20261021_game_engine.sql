@@ -0,0 +1,2 @@
+-- 游戏引擎（按游戏目录的特征文件识别），未识别时为 NULL
+ALTER TABLE "games" ADD COLUMN "engine" TEXT;
@@ -0,0 +1,2 @@
+-- 游戏的设置程序（画面、音量等），与主启动程序分开记录
+ALTER TABLE "games" ADD COLUMN "config_path" TEXT;
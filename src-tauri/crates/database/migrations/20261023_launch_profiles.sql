@@ -0,0 +1,19 @@
+-- 游戏的启动配置（可执行文件、参数、工作目录、环境变量、转区 / 兼容层包装命令）
+-- 「主程序」「设置程序」由 games.abs_path / games.config_path 生成，不存放在此表中
+CREATE TABLE IF NOT EXISTS "game_launch_profiles" (
+    "id"           TEXT     PRIMARY KEY,
+    "game_id"      TEXT     NOT NULL REFERENCES "games" ("id") ON DELETE CASCADE,
+    "name"         TEXT     NOT NULL,
+    "exe_path"     TEXT     NOT NULL,
+    "args"         TEXT     NOT NULL DEFAULT '[]',
+    "working_dir"  TEXT,
+    "env"          TEXT     NOT NULL DEFAULT '{}',
+    "wrapper"      TEXT     NOT NULL DEFAULT 'null',
+    "is_default"   BOOLEAN  NOT NULL DEFAULT 0,
+    "sort_order"   INTEGER  NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS "idx_launch_profiles_game_id" ON "game_launch_profiles" ("game_id");
+
+-- 会话使用的启动配置（旧会话为空）
+ALTER TABLE "game_play_sessions" ADD COLUMN "profile_id" TEXT;
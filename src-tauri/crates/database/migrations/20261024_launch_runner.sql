@@ -0,0 +1,4 @@
+-- 启动配置的包装命令改为运行方式（转区工具 / Wine / Proton / 自定义命令）
+ALTER TABLE "game_launch_profiles" RENAME COLUMN "wrapper" TO "runner";
+-- 已有的包装命令转为自定义命令
+UPDATE "game_launch_profiles" SET "runner" = json_set("runner", '$.kind', 'custom') WHERE "runner" <> 'null';
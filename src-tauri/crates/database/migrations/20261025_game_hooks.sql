@@ -0,0 +1,17 @@
+-- 游戏的启动前 / 退出后脚本（挂载镜像、切换补丁、调整分辨率、同步存档等）
+CREATE TABLE IF NOT EXISTS "game_hooks" (
+    "id"            TEXT     PRIMARY KEY,
+    "game_id"       TEXT     NOT NULL REFERENCES "games" ("id") ON DELETE CASCADE,
+    "name"          TEXT     NOT NULL,
+    -- preLaunch / postExit
+    "stage"         TEXT     NOT NULL,
+    "command"       TEXT     NOT NULL,
+    "working_dir"   TEXT,
+    "timeout_secs"  INTEGER  NOT NULL DEFAULT 30,
+    -- abort / warn
+    "on_failure"    TEXT     NOT NULL DEFAULT 'warn',
+    "is_enabled"    BOOLEAN  NOT NULL DEFAULT 1,
+    "sort_order"    INTEGER  NOT NULL DEFAULT 0
+);
+
+CREATE INDEX IF NOT EXISTS "idx_hooks_game_id" ON "game_hooks" ("game_id");
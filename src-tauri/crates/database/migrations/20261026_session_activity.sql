@@ -0,0 +1,3 @@
+-- 会话中有操作（且游戏在前台）与离开的秒数，旧会话为 NULL
+ALTER TABLE "game_play_sessions" ADD COLUMN "active_seconds" INTEGER;
+ALTER TABLE "game_play_sessions" ADD COLUMN "idle_seconds" INTEGER;
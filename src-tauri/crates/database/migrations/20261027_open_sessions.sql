@@ -0,0 +1,4 @@
+-- 进行中的会话：启动游戏时写入，监控期间定期更新心跳，游戏结束时关闭；
+-- 程序意外退出后遗留的会话在下次启动时按最后一次心跳结束
+ALTER TABLE "game_play_sessions" ADD COLUMN "is_open" BOOLEAN NOT NULL DEFAULT 0;
+ALTER TABLE "game_play_sessions" ADD COLUMN "heartbeat_at" TEXT;
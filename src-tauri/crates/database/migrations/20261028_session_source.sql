@@ -0,0 +1,2 @@
+-- 会话来源：hub（从本程序启动）/ external（从外部启动，由后台进程监控识别）
+ALTER TABLE "game_play_sessions" ADD COLUMN "source" TEXT NOT NULL DEFAULT 'hub';
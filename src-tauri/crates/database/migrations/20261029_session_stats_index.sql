@@ -0,0 +1,8 @@
+-- 游玩统计用的覆盖索引，只包含已结束的会话：
+-- 按日期范围聚合走第一个，按游戏聚合走第二个，都不必回表
+CREATE INDEX IF NOT EXISTS "idx_sessions_stats_date"
+    ON "game_play_sessions" ("play_date", "game_id", "duration_minutes")
+    WHERE "is_open" = 0;
+CREATE INDEX IF NOT EXISTS "idx_sessions_stats_game"
+    ON "game_play_sessions" ("game_id", "play_date", "duration_minutes")
+    WHERE "is_open" = 0;
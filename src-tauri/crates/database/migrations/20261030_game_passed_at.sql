@@ -0,0 +1,5 @@
+-- 通关时间：标记通关时写入，取消通关时清空
+ALTER TABLE "games" ADD COLUMN "passed_at" DATETIME;
+
+-- 已通关的旧记录没有通关时间，以最后游玩时间近似
+UPDATE "games" SET "passed_at" = "last_played_at" WHERE "is_passed" = 1;
@@ -0,0 +1,12 @@
+-- 手动修改会话的记录：新建、修改、拆分、合并、删除前后的会话快照（JSON 数组）
+CREATE TABLE IF NOT EXISTS "game_session_edits" (
+    "id"          TEXT      PRIMARY KEY,
+    "game_id"     TEXT      NOT NULL REFERENCES "games" ("id") ON DELETE CASCADE,
+    -- create / update / split / merge / delete
+    "action"      TEXT      NOT NULL,
+    "before"      TEXT      NOT NULL DEFAULT '[]',
+    "after"       TEXT      NOT NULL DEFAULT '[]',
+    "created_at"  DATETIME  NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS "idx_session_edits_game_id" ON "game_session_edits" ("game_id");
@@ -0,0 +1,28 @@
+-- 用户统计改为由 games / game_play_sessions 实时计算，account 中的统计列只保存手动覆盖值（NULL 表示不覆盖）。
+-- 旧的游戏数、时长、通关数是前端同步的副本而非用户设置，直接清空；
+-- 喜爱的游戏是用户填写的，非空时保留为覆盖值。
+CREATE TABLE "account_new" (
+    "id"                        TEXT    PRIMARY KEY,
+    "user_name"                 TEXT    NOT NULL,
+    "avatar"                    TEXT    NOT NULL DEFAULT '',
+    "games_count"               INTEGER,
+    "favorite_game"             TEXT,
+    "total_play_time"           INTEGER,
+    "games_completed_number"    INTEGER,
+    "selected_disk"             TEXT,
+    "last_play_at"              DATETIME,
+    "created_at"                DATETIME
+);
+
+INSERT INTO "account_new"
+SELECT
+    id, user_name, avatar,
+    NULL,
+    NULLIF(TRIM(favorite_game), ''),
+    NULL,
+    NULL,
+    selected_disk, last_play_at, created_at
+FROM "account";
+
+DROP TABLE "account";
+ALTER TABLE "account_new" RENAME TO "account";
@@ -0,0 +1,94 @@
+-- ============================================================
+-- 多用户档案
+--
+-- 每个档案（account 的一行）有各自的游玩记录、游戏进度、收藏夹、快捷键与部分设置，
+-- 安装的游戏（games 表）由所有档案共用。现有数据全部归到当前档案。
+-- ============================================================
+
+PRAGMA foreign_keys = OFF;
+
+-- ────────────────────────────────────────────────────────────
+-- 1. account：当前档案标记与随档案切换的设置
+-- ────────────────────────────────────────────────────────────
+INSERT INTO "account" ("id", "user_name", "created_at")
+SELECT 'default', 'user', DATETIME('now')
+WHERE NOT EXISTS (SELECT 1 FROM "account");
+
+ALTER TABLE "account" ADD COLUMN "is_active" BOOLEAN NOT NULL DEFAULT 0;
+-- 切换走时保存的设置（JSON），切换回来时恢复
+ALTER TABLE "account" ADD COLUMN "config_overrides" TEXT NOT NULL DEFAULT '{}';
+
+UPDATE "account" SET "is_active" = 1
+WHERE "id" = (SELECT "id" FROM "account" ORDER BY "created_at", "id" LIMIT 1);
+
+CREATE UNIQUE INDEX IF NOT EXISTS "idx_account_active" ON "account" ("is_active")
+    WHERE "is_active" = 1;
+
+-- ────────────────────────────────────────────────────────────
+-- 2. game_progress：按档案的通关标记与游玩时长，取代 games 中的对应列
+-- ────────────────────────────────────────────────────────────
+CREATE TABLE IF NOT EXISTS "game_progress" (
+    "account_id"      TEXT     NOT NULL REFERENCES "account" ("id") ON DELETE CASCADE,
+    "game_id"         TEXT     NOT NULL REFERENCES "games" ("id") ON DELETE CASCADE,
+    "is_passed"       BOOLEAN  NOT NULL DEFAULT 0,
+    "passed_at"       DATETIME,
+    "play_time"       INTEGER  NOT NULL DEFAULT 0,
+    "last_played_at"  DATETIME,
+    PRIMARY KEY ("account_id", "game_id")
+);
+
+CREATE INDEX IF NOT EXISTS "idx_game_progress_game_id" ON "game_progress" ("game_id");
+
+INSERT INTO "game_progress" ("account_id", "game_id", "is_passed", "passed_at", "play_time", "last_played_at")
+SELECT (SELECT "id" FROM "account" WHERE "is_active" = 1), "id", "is_passed", "passed_at", "play_time", "last_played_at"
+FROM "games"
+WHERE "is_passed" != 0 OR "play_time" > 0 OR "last_played_at" IS NOT NULL;
+
+ALTER TABLE "games" DROP COLUMN "is_passed";
+ALTER TABLE "games" DROP COLUMN "passed_at";
+ALTER TABLE "games" DROP COLUMN "play_time";
+ALTER TABLE "games" DROP COLUMN "last_played_at";
+
+-- ────────────────────────────────────────────────────────────
+-- 3. 会话、会话修改记录与收藏夹归属档案
+-- ────────────────────────────────────────────────────────────
+ALTER TABLE "game_play_sessions" ADD COLUMN "account_id" TEXT REFERENCES "account" ("id") ON DELETE CASCADE;
+UPDATE "game_play_sessions" SET "account_id" = (SELECT "id" FROM "account" WHERE "is_active" = 1);
+
+ALTER TABLE "game_session_edits" ADD COLUMN "account_id" TEXT REFERENCES "account" ("id") ON DELETE CASCADE;
+UPDATE "game_session_edits" SET "account_id" = (SELECT "id" FROM "account" WHERE "is_active" = 1);
+
+ALTER TABLE "collections" ADD COLUMN "account_id" TEXT REFERENCES "account" ("id") ON DELETE CASCADE;
+UPDATE "collections" SET "account_id" = (SELECT "id" FROM "account" WHERE "is_active" = 1);
+
+CREATE INDEX IF NOT EXISTS "idx_collections_account_id" ON "collections" ("account_id");
+
+-- 游玩统计的覆盖索引改为以档案开头
+DROP INDEX IF EXISTS "idx_sessions_stats_date";
+DROP INDEX IF EXISTS "idx_sessions_stats_game";
+CREATE INDEX IF NOT EXISTS "idx_sessions_stats_date"
+    ON "game_play_sessions" ("account_id", "play_date", "game_id", "duration_minutes")
+    WHERE "is_open" = 0;
+CREATE INDEX IF NOT EXISTS "idx_sessions_stats_game"
+    ON "game_play_sessions" ("account_id", "game_id", "play_date", "duration_minutes")
+    WHERE "is_open" = 0;
+
+-- ────────────────────────────────────────────────────────────
+-- 4. shortcut：主键改为 (档案, 动作)
+-- ────────────────────────────────────────────────────────────
+CREATE TABLE "shortcut_new" (
+    "account_id"  TEXT     NOT NULL REFERENCES "account" ("id") ON DELETE CASCADE,
+    "id"          TEXT     NOT NULL,
+    "key_combo"   TEXT,
+    "is_global"   BOOLEAN  NOT NULL DEFAULT 0,
+    PRIMARY KEY ("account_id", "id")
+);
+
+INSERT INTO "shortcut_new" ("account_id", "id", "key_combo", "is_global")
+SELECT (SELECT "id" FROM "account" WHERE "is_active" = 1), "id", "key_combo", COALESCE("is_global", 0)
+FROM "shortcut";
+
+DROP TABLE "shortcut";
+ALTER TABLE "shortcut_new" RENAME TO "shortcut";
+
+PRAGMA foreign_keys = ON;
@@ -1,136 +1,541 @@
 //! 存档备份与恢复逻辑
 
-use std::path::PathBuf;
+use std::path::Path;
 
-use sqlx::{Row, SqlitePool};
+use chrono::{DateTime, Local};
+use sqlx::{Row, SqlitePool, sqlite::SqliteRow, types::Json};
 use tauri::async_runtime;
 use tauri_plugin_log::log::error;
 
 use crate::{
-        config::read_config,
+        backup::{
+                discovery::{self, DiscoveryTarget},
+                entity::{
+                        BackupRun, BackupSnapshot, FileChange, RestoreReport, SaveCandidate,
+                        SnapshotKind, VerifyReport,
+                },
+                location::SaveSet,
+                snapshot, verify,
+        },
+        config::{CONFIG_PATH, entity::RetentionPolicy, read_config},
+        engine::entity::Engine,
         error::AppError,
-        infra::archive::{extract_zip, zip_dir},
+        game::entity::SaveLocation,
 };
 
-/// 备份单个游戏存档（通过游戏 ID）
-pub async fn backup_by_game_id(
-        pool: SqlitePool,
-        game_id: String,
-) -> Result<(), AppError> {
-        let row = sqlx::query("SELECT save_data_path FROM games WHERE id = ?")
-                .bind(&game_id)
-                .fetch_one(&pool)
-                .await
-                .map_err(AppError::from)?;
+const SAVE_SET_SQL: &str = "SELECT id, abs_path, save_data_path, save_locations FROM games";
 
+/// 从查询结果解析游戏的存档位置，一个都没设置时返回 `None`
+fn save_set_from_row(row: &SqliteRow) -> Result<Option<SaveSet>, AppError> {
+        let abs_path: String = row.get("abs_path");
         let save_path: Option<String> = row.get("save_data_path");
-        let save_path = save_path
-                .ok_or_else(|| AppError::Resolve("none".into(), "该游戏未设置存档路径".into()))?;
+        let Json(locations): Json<Vec<SaveLocation>> = row.try_get("save_locations")?;
 
-        let backup_root = read_config()?.storage.backup_save_path.clone();
-        let zip_dst = backup_root.join(format!("game_{}.zip", game_id));
-        let src = PathBuf::from(save_path);
+        let exe = Path::new(&abs_path);
+        let game_dir = exe.parent().unwrap_or(exe);
+        let set = SaveSet::resolve(&locations, save_path.as_deref(), game_dir)?;
+        Ok((!set.is_empty()).then_some(set))
+}
 
-        async_runtime::spawn_blocking(move || zip_dir(&src, &zip_dst))
+/// 查询游戏的存档位置，未设置时返回错误
+async fn fetch_save_set(
+        pool: &SqlitePool,
+        game_id: &str,
+) -> Result<SaveSet, AppError> {
+        let row = sqlx::query(&format!("{} WHERE id = ?", SAVE_SET_SQL))
+                .bind(game_id)
+                .fetch_one(pool)
                 .await
-                .map_err(|e| AppError::Fs(e.to_string()))??;
+                .map_err(AppError::from)?;
 
-        Ok(())
+        save_set_from_row(&row)?
+                .ok_or_else(|| AppError::Resolve("none".into(), "该游戏未设置存档路径".into()))
 }
 
-/// 备份所有已设置存档路径的游戏
-pub async fn backup_all(pool: &SqlitePool) -> Result<(), AppError> {
-        let games = sqlx::query("SELECT id, save_data_path FROM games")
+/// 查询所有游戏的存档位置；单个游戏的路径模板 / 过滤规则有误时只影响它自己
+async fn fetch_all_save_sets(
+        pool: &SqlitePool
+) -> Result<Vec<(String, Result<Option<SaveSet>, AppError>)>, AppError> {
+        let rows = sqlx::query(SAVE_SET_SQL)
                 .fetch_all(pool)
                 .await
                 .map_err(AppError::from)?;
+        Ok(rows.iter()
+                .map(|row| (row.get("id"), save_set_from_row(row)))
+                .collect())
+}
 
+/// 查询游戏生效的保留策略：有单独设置时用单独设置，否则用全局默认
+pub async fn fetch_policy(
+        pool: &SqlitePool,
+        game_id: &str,
+) -> Result<RetentionPolicy, AppError> {
+        let custom = fetch_custom_policy(pool, game_id).await?;
+        match custom {
+                | Some(policy) => Ok(policy),
+                | None => Ok(read_config()?.storage.backup_retention.clone()),
+        }
+}
+
+/// 查询游戏单独设置的保留策略
+pub async fn fetch_custom_policy(
+        pool: &SqlitePool,
+        game_id: &str,
+) -> Result<Option<RetentionPolicy>, AppError> {
+        sqlx::query_as::<_, RetentionPolicy>(
+                "SELECT keep_last, keep_daily, keep_weekly FROM game_backup_policies WHERE game_id = ?",
+        )
+        .bind(game_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// 设置 / 清除游戏单独的保留策略（`None` 表示回到全局默认）
+pub async fn save_custom_policy(
+        pool: &SqlitePool,
+        game_id: &str,
+        policy: Option<RetentionPolicy>,
+) -> Result<(), AppError> {
+        match policy {
+                | Some(p) => sqlx::query(
+                        "INSERT OR REPLACE INTO game_backup_policies \
+                 (game_id, keep_last, keep_daily, keep_weekly) VALUES (?, ?, ?, ?)",
+                )
+                .bind(game_id)
+                .bind(p.keep_last)
+                .bind(p.keep_daily)
+                .bind(p.keep_weekly)
+                .execute(pool)
+                .await
+                .map_err(AppError::from)?,
+                | None => sqlx::query("DELETE FROM game_backup_policies WHERE game_id = ?")
+                        .bind(game_id)
+                        .execute(pool)
+                        .await
+                        .map_err(AppError::from)?,
+        };
+        Ok(())
+}
+
+/// 备份单个游戏存档（通过游戏 ID）：写入新快照后按保留策略清理旧快照
+pub async fn backup_by_game_id(
+        pool: SqlitePool,
+        game_id: String,
+        force: bool,
+) -> Result<BackupSnapshot, AppError> {
+        let set = fetch_save_set(&pool, &game_id).await?;
+        let policy = fetch_policy(&pool, &game_id).await?;
         let backup_root = read_config()?.storage.backup_save_path.clone();
 
-        for row in games {
-                let game_id: String = row.get("id");
-                let save_path: Option<String> = row.get("save_data_path");
+        let id = game_id.clone();
+        let (created, fingerprint) = async_runtime::spawn_blocking(move || {
+                let fingerprint = snapshot::fingerprint(&set);
+                let created =
+                        snapshot::create(&backup_root, &id, &set, SnapshotKind::Regular, force)?;
+                snapshot::prune(&backup_root, &id, &policy)?;
+                Ok::<_, AppError>((created, fingerprint))
+        })
+        .await
+        .map_err(|e| AppError::Fs(e.to_string()))??;
 
-                let Some(sp) = save_path else {
-                        continue;
+        save_fingerprint(&pool, &game_id, &fingerprint).await?;
+        Ok(created)
+}
+
+/// 备份所有已设置存档路径的游戏
+pub async fn backup_all(pool: &SqlitePool) -> Result<(), AppError> {
+        let games = fetch_all_save_sets(pool).await?;
+        let backup_root = read_config()?.storage.backup_save_path.clone();
+
+        for (game_id, set) in games {
+                let set = match set {
+                        | Ok(Some(set)) => set,
+                        | Ok(None) => continue,
+                        | Err(e) => {
+                                error!("备份游戏 {} 失败: {}", game_id, e);
+                                continue;
+                        },
                 };
 
-                let src = PathBuf::from(sp);
-                let zip_dst = backup_root.join(format!("game_{}.zip", game_id));
-                let gid = game_id.clone();
+                let root = backup_root.clone();
+                let policy = fetch_policy(pool, &game_id).await?;
+                let id = game_id.clone();
 
-                async_runtime::spawn_blocking(move || {
-                        if let Err(e) = zip_dir(&src, &zip_dst) {
-                                error!("备份游戏 {} 失败: {}", gid, e);
-                        }
+                let result = async_runtime::spawn_blocking(move || {
+                        let fingerprint = snapshot::fingerprint(&set);
+                        snapshot::create(&root, &id, &set, SnapshotKind::Regular, false)?;
+                        snapshot::prune(&root, &id, &policy)?;
+                        Ok::<_, AppError>(fingerprint)
                 })
                 .await
                 .map_err(|e| AppError::Fs(e.to_string()))?;
+
+                // 与定时备份共用指纹，避免下一轮定时备份重复备份未变的存档
+                match result {
+                        | Ok(fingerprint) => save_fingerprint(pool, &game_id, &fingerprint).await?,
+                        | Err(e) => error!("备份游戏 {} 失败: {}", game_id, e),
+                }
         }
 
         Ok(())
 }
 
-/// 从备份恢复单个游戏存档
+/// 列出游戏的所有快照（从新到旧）
+pub async fn list_snapshots(game_id: String) -> Result<Vec<BackupSnapshot>, AppError> {
+        let backup_root = read_config()?.storage.backup_save_path.clone();
+
+        async_runtime::spawn_blocking(move || snapshot::list(&backup_root, &game_id))
+                .await
+                .map_err(|e| AppError::Fs(e.to_string()))?
+}
+
+/// 恢复快照（阻塞）：先对比差异，恢复会覆盖或删除当前存档时先为当前存档保存一份快照
+///
+/// `dry_run` 为 true 时只返回差异，不改动任何文件
+fn restore_blocking(
+        root: &Path,
+        game_id: &str,
+        snapshot_id: &str,
+        set: &SaveSet,
+        dry_run: bool,
+) -> Result<RestoreReport, AppError> {
+        let changes = snapshot::diff(root, game_id, snapshot_id, set)?;
+        let mut report = RestoreReport {
+                game_id: game_id.to_string(),
+                snapshot_id: snapshot_id.to_string(),
+                applied: false,
+                safety_snapshot: None,
+                changes,
+        };
+        if dry_run {
+                return Ok(report);
+        }
+
+        // 只新增文件时当前存档不会丢失内容，不需要保护快照
+        let destructive = report.changes.iter().any(|c| c.change != FileChange::Added);
+        if destructive && set.exists() {
+                let safety = snapshot::create(root, game_id, set, SnapshotKind::PreRestore, true)?;
+                report.safety_snapshot = Some(safety.id);
+        }
+
+        if !report.changes.is_empty() {
+                snapshot::restore(root, game_id, snapshot_id, set)?;
+        }
+        report.applied = true;
+        Ok(report)
+}
+
+/// 最新的一份常规快照（恢复前的保护快照不算）
+fn latest_regular(
+        root: &Path,
+        game_id: &str,
+) -> Result<Option<BackupSnapshot>, AppError> {
+        Ok(snapshot::list(root, game_id)?
+                .into_iter()
+                .find(|s| s.kind == SnapshotKind::Regular))
+}
+
+/// 从指定快照恢复单个游戏存档
+pub async fn restore_snapshot(
+        pool: &SqlitePool,
+        game_id: &str,
+        snapshot_id: &str,
+        dry_run: bool,
+) -> Result<RestoreReport, AppError> {
+        let set = fetch_save_set(pool, game_id).await?;
+        let backup_root = read_config()?.storage.backup_save_path.clone();
+        let game_id = game_id.to_string();
+        let snapshot_id = snapshot_id.to_string();
+
+        async_runtime::spawn_blocking(move || {
+                restore_blocking(&backup_root, &game_id, &snapshot_id, &set, dry_run)
+        })
+        .await
+        .map_err(|e| AppError::Fs(e.to_string()))?
+}
+
+/// 从最新快照恢复单个游戏存档
 pub async fn restore_by_game_id(
         pool: &SqlitePool,
         game_id: &str,
-) -> Result<(), AppError> {
-        let row = sqlx::query("SELECT save_data_path FROM games WHERE id = ?")
-                .bind(game_id)
-                .fetch_one(pool)
+        dry_run: bool,
+) -> Result<RestoreReport, AppError> {
+        let backup_root = read_config()?.storage.backup_save_path.clone();
+        let id = game_id.to_string();
+        let latest = async_runtime::spawn_blocking(move || latest_regular(&backup_root, &id))
                 .await
-                .map_err(AppError::from)?;
+                .map_err(|e| AppError::Fs(e.to_string()))??
+                .ok_or_else(|| AppError::Fs(format!("未找到 ID 为 {} 的备份文件", game_id)))?;
 
-        let save_path: Option<String> = row.get("save_data_path");
-        let save_path = save_path
-                .map(PathBuf::from)
-                .ok_or_else(|| AppError::Resolve("none".into(), "该游戏未设置存档路径".into()))?;
+        restore_snapshot(pool, game_id, &latest.id, dry_run).await
+}
 
+/// 用各自最新的快照恢复所有有备份的游戏存档，单个游戏失败时记录日志并继续
+pub async fn restore_all(
+        pool: &SqlitePool,
+        dry_run: bool,
+) -> Result<Vec<RestoreReport>, AppError> {
+        let games = fetch_all_save_sets(pool).await?;
         let backup_root = read_config()?.storage.backup_save_path.clone();
-        let zip_src = backup_root.join(format!("game_{}.zip", game_id));
+        let mut reports = Vec::new();
+
+        for (game_id, set) in games {
+                let set = match set {
+                        | Ok(Some(set)) => set,
+                        | Ok(None) => continue,
+                        | Err(e) => {
+                                error!("恢复游戏 {} 失败: {}", game_id, e);
+                                continue;
+                        },
+                };
+                let root = backup_root.clone();
 
-        if !zip_src.exists() {
-                return Err(AppError::Fs(format!("未找到 ID 为 {} 的备份文件", game_id)));
+                let report = async_runtime::spawn_blocking(move || {
+                        let result = latest_regular(&root, &game_id).and_then(|latest| {
+                                latest.map(|s| {
+                                        restore_blocking(&root, &game_id, &s.id, &set, dry_run)
+                                })
+                                .transpose()
+                        });
+                        result.unwrap_or_else(|e| {
+                                error!("恢复游戏 {} 失败: {}", game_id, e);
+                                None
+                        })
+                })
+                .await
+                .map_err(|e| AppError::Fs(e.to_string()))?;
+                reports.extend(report);
         }
 
-        async_runtime::spawn_blocking(move || extract_zip(&zip_src, &save_path).map(|_| ()))
+        Ok(reports)
+}
+
+/// 校验所有备份的完整性
+pub async fn verify_backups(pool: &SqlitePool) -> Result<VerifyReport, AppError> {
+        // 存档位置解析失败的游戏仍参与快照校验，只是无法判断缺失 / 过期
+        let games: Vec<(String, Option<SaveSet>)> = fetch_all_save_sets(pool)
+                .await?
+                .into_iter()
+                .map(|(id, set)| {
+                        let set = set.unwrap_or_else(|e| {
+                                error!("解析游戏 {} 的存档位置失败: {}", id, e);
+                                None
+                        });
+                        (id, set)
+                })
+                .collect();
+
+        let backup_root = read_config()?.storage.backup_save_path.clone();
+
+        async_runtime::spawn_blocking(move || verify::verify_all(&backup_root, &games))
+                .await
+                .map_err(|e| AppError::Fs(e.to_string()))?
+}
+
+// ── 定时备份 ──────────────────────────────────────────────────────────────────
+
+/// 查询游戏上次成功备份时的存档指纹
+async fn fetch_fingerprint(
+        pool: &SqlitePool,
+        game_id: &str,
+) -> Result<Option<String>, AppError> {
+        sqlx::query_scalar("SELECT fingerprint FROM game_backup_fingerprints WHERE game_id = ?")
+                .bind(game_id)
+                .fetch_optional(pool)
                 .await
-                .map_err(|e| AppError::Fs(e.to_string()))??;
+                .map_err(AppError::from)
+}
 
+async fn save_fingerprint(
+        pool: &SqlitePool,
+        game_id: &str,
+        fingerprint: &str,
+) -> Result<(), AppError> {
+        sqlx::query(
+                "INSERT OR REPLACE INTO game_backup_fingerprints (game_id, fingerprint, updated_at) \
+                 VALUES (?, ?, ?)",
+        )
+        .bind(game_id)
+        .bind(fingerprint)
+        .bind(Local::now())
+        .execute(pool)
+        .await
+        .map_err(AppError::from)?;
         Ok(())
 }
 
-/// 恢复所有有备份文件的游戏存档
-pub async fn restore_all(pool: &SqlitePool) -> Result<(), AppError> {
-        let games = sqlx::query("SELECT id, save_data_path FROM games")
-                .fetch_all(pool)
+/// 最近一次定时备份的开始时间
+pub async fn last_scheduled_run(pool: &SqlitePool) -> Result<Option<DateTime<Local>>, AppError> {
+        sqlx::query_scalar("SELECT MAX(started_at) FROM backup_runs")
+                .fetch_one(pool)
                 .await
-                .map_err(AppError::from)?;
+                .map_err(AppError::from)
+}
 
+/// 执行一轮定时备份：跳过指纹未变的存档，单个游戏失败不影响其他游戏，结果写入执行记录
+pub async fn run_scheduled(
+        pool: &SqlitePool,
+        started_at: DateTime<Local>,
+) -> Result<BackupRun, AppError> {
+        let games = fetch_all_save_sets(pool).await?;
         let backup_root = read_config()?.storage.backup_save_path.clone();
+        let (mut backed_up, mut skipped) = (0u32, 0u32);
+        let mut errors = Vec::new();
 
-        for row in games {
-                let game_id: String = row.get("id");
-                let save_path: Option<String> = row.get("save_data_path");
-                let Some(sp) = save_path else { continue };
-
-                let zip_src = backup_root.join(format!("game_{}.zip", game_id));
-                if !zip_src.exists() {
+        for (game_id, set) in games {
+                let set = match set {
+                        | Ok(Some(set)) => set,
+                        | Ok(None) => continue,
+                        | Err(e) => {
+                                error!("定时备份游戏 {} 失败: {}", game_id, e);
+                                errors.push(format!("{}: {}", game_id, e));
+                                continue;
+                        },
+                };
+                if !set.exists() {
+                        skipped += 1;
                         continue;
                 }
 
-                let dst = PathBuf::from(sp);
-                let gid = game_id.clone();
+                let scan_set = set.clone();
+                let fingerprint =
+                        async_runtime::spawn_blocking(move || snapshot::fingerprint(&scan_set))
+                                .await
+                                .map_err(|e| AppError::Fs(e.to_string()))?;
+                if fetch_fingerprint(pool, &game_id).await?.as_deref() == Some(fingerprint.as_str())
+                {
+                        skipped += 1;
+                        continue;
+                }
 
-                async_runtime::spawn_blocking(move || {
-                        if let Err(e) = extract_zip(&zip_src, &dst) {
-                                error!("恢复游戏 {} 失败: {}", gid, e);
-                        }
+                let root = backup_root.clone();
+                let policy = fetch_policy(pool, &game_id).await?;
+                let id = game_id.clone();
+                let result = async_runtime::spawn_blocking(move || {
+                        snapshot::create(&root, &id, &set, SnapshotKind::Regular, false)?;
+                        snapshot::prune(&root, &id, &policy)
                 })
                 .await
                 .map_err(|e| AppError::Fs(e.to_string()))?;
+
+                match result {
+                        | Ok(_) => {
+                                save_fingerprint(pool, &game_id, &fingerprint).await?;
+                                backed_up += 1;
+                        },
+                        | Err(e) => {
+                                error!("定时备份游戏 {} 失败: {}", game_id, e);
+                                errors.push(format!("{}: {}", game_id, e));
+                        },
+                }
         }
 
-        Ok(())
+        let finished_at = Local::now();
+        let failed = errors.len() as u32;
+        let errors = (!errors.is_empty()).then(|| errors.join("\n"));
+
+        let id = sqlx::query(
+                "INSERT INTO backup_runs (started_at, finished_at, backed_up, skipped, failed, errors) \
+                 VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(started_at)
+        .bind(finished_at)
+        .bind(backed_up)
+        .bind(skipped)
+        .bind(failed)
+        .bind(&errors)
+        .execute(pool)
+        .await
+        .map_err(AppError::from)?
+        .last_insert_rowid();
+
+        Ok(BackupRun {
+                id,
+                started_at,
+                finished_at,
+                backed_up,
+                skipped,
+                failed,
+                errors,
+        })
+}
+
+/// 最近的定时备份记录（从新到旧）
+pub async fn list_runs(
+        pool: &SqlitePool,
+        limit: u32,
+) -> Result<Vec<BackupRun>, AppError> {
+        sqlx::query_as::<_, BackupRun>(
+                "SELECT id, started_at, finished_at, backed_up, skipped, failed, errors \
+                 FROM backup_runs ORDER BY started_at DESC LIMIT ?",
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+// ── 存档目录发现 ──────────────────────────────────────────────────────────────
+
+/// 为游戏搜索存档目录候选，以该用户最近一次已结束的游玩期间的文件修改作为主要依据
+pub async fn discover_save_paths(
+        pool: &SqlitePool,
+        account_id: &str,
+        game_id: &str,
+) -> Result<Vec<SaveCandidate>, AppError> {
+        let row = sqlx::query("SELECT name, abs_path, developer, engine FROM games WHERE id = ?")
+                .bind(game_id)
+                .fetch_one(pool)
+                .await
+                .map_err(AppError::from)?;
+        let name: String = row.get("name");
+        let engine: Option<Engine> = row.get("engine");
+        let developer: Option<String> = row.get("developer");
+        let abs_path: String = row.get("abs_path");
+
+        let session: Option<(DateTime<Local>, DateTime<Local>)> = sqlx::query_as(
+                "SELECT play_date, last_played_at FROM game_play_sessions \
+                 WHERE account_id = ? AND game_id = ? AND is_open = 0 \
+                 ORDER BY last_played_at DESC LIMIT 1",
+        )
+        .bind(account_id)
+        .bind(game_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::from)?;
+
+        let game_dir = Path::new(&abs_path)
+                .parent()
+                .map(Path::to_path_buf)
+                .ok_or_else(|| AppError::Resolve(abs_path.clone(), "无法解析游戏目录".into()))?;
+
+        // 本程序自己的数据也会在游玩期间写入，不能当成存档
+        let mut excluded = {
+                let storage = &read_config()?.storage;
+                vec![
+                        storage.backup_save_path.clone(),
+                        storage.meta_save_path.clone(),
+                        storage.screenshot_path.clone(),
+                ]
+        };
+        if let Some(dir) = CONFIG_PATH.get().and_then(|p| p.parent()) {
+                excluded.push(dir.to_path_buf());
+        }
+        excluded.retain(|p| !p.as_os_str().is_empty());
+
+        async_runtime::spawn_blocking(move || {
+                discovery::discover(&DiscoveryTarget {
+                        name: &name,
+                        developer: developer.as_deref().unwrap_or_default(),
+                        game_dir: &game_dir,
+                        // 会话结束后游戏可能还在落盘，多留一分钟余量
+                        session: session
+                                .map(|(start, end)| (start, end + chrono::Duration::minutes(1))),
+                        excluded,
+                        engine,
+                })
+        })
+        .await
+        .map_err(|e| AppError::Fs(e.to_string()))
 }
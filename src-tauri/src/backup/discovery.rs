@@ -0,0 +1,259 @@
+//! 存档目录自动发现
+//!
+//! 扫描游戏安装目录和常见的存档位置（文档、AppData、`Saved Games` 等），
+//! 以目录为单位汇总证据并打分：
+//! - 上次游玩期间被修改过的文件（最有力的证据）
+//! - 路径里出现游戏名 / 开发商名
+//! - 文件名或扩展名看起来像存档
+//! - 是游戏引擎惯用的存档目录
+//!
+//! 常见存档位置只扫描有限深度，且只收录有游玩期间修改或名称匹配的目录，避免把无关程序的数据列进来。
+
+use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local};
+use directories::{BaseDirs, UserDirs};
+use walkdir::{DirEntry, WalkDir};
+
+use crate::{
+        backup::{entity::SaveCandidate, location},
+        engine::entity::Engine,
+};
+
+/// 常见存档位置的扫描深度
+const WELL_KNOWN_DEPTH: usize = 5;
+/// 游戏目录的扫描深度
+const GAME_DIR_DEPTH: usize = 6;
+/// 最多返回的候选数
+const MAX_CANDIDATES: usize = 20;
+/// 每个候选附带的样例文件数
+const MAX_SAMPLES: usize = 5;
+
+/// 存档常用的扩展名
+const SAVE_EXTS: &[&str] = &["sav", "save", "dat", "sys", "rsd", "ksd", "bin", "json"];
+/// 文件名中常见的存档关键词
+const SAVE_KEYWORDS: &[&str] = &["save", "sav", "slot", "data", "global", "system", "config"];
+/// 明显不是存档的目录（日志、缓存、临时文件等）
+const NOISE_DIRS: &[&str] = &[
+        "log",
+        "logs",
+        "cache",
+        "caches",
+        "temp",
+        "tmp",
+        "crash",
+        "crashes",
+        "crashdumps",
+        "shadercache",
+        "gpucache",
+        "webview",
+        "ebwebview",
+];
+
+/// 游戏的相关信息
+pub struct DiscoveryTarget<'a> {
+        pub name: &'a str,
+        pub developer: &'a str,
+        pub game_dir: &'a Path,
+        /// 上次游玩的起止时间，没有游玩记录时为空
+        pub session: Option<(DateTime<Local>, DateTime<Local>)>,
+        /// 不参与扫描的目录（本程序自己的数据目录等）
+        pub excluded: Vec<PathBuf>,
+        /// 游戏引擎，用于优先推荐引擎惯用的存档目录
+        pub engine: Option<Engine>,
+}
+
+struct Evidence {
+        /// 所在的扫描根目录，名称匹配只看根目录以下的部分
+        root: PathBuf,
+        in_game_dir: bool,
+        modified_in_session: usize,
+        save_like_files: usize,
+        last_modified: Option<DateTime<Local>>,
+        samples: Vec<String>,
+}
+
+/// 搜索存档目录候选，按得分从高到低返回
+pub fn discover(target: &DiscoveryTarget) -> Vec<SaveCandidate> {
+        let keywords: Vec<String> = [target.name, target.developer]
+                .iter()
+                .map(|s| normalize(s))
+                .filter(|s| s.chars().count() >= 3)
+                .collect();
+
+        let engine_dirs: Vec<PathBuf> = target
+                .engine
+                .map(|e| e.save_locations())
+                .unwrap_or_default()
+                .iter()
+                .filter_map(|loc| location::expand(&loc.template, target.game_dir).ok())
+                .collect();
+
+        let mut found: HashMap<PathBuf, Evidence> = HashMap::new();
+        scan(target.game_dir, GAME_DIR_DEPTH, target, &mut found, true);
+        for root in well_known_roots() {
+                if root.is_dir() {
+                        scan(&root, WELL_KNOWN_DEPTH, target, &mut found, false);
+                }
+        }
+
+        let mut candidates: Vec<SaveCandidate> = found
+                .into_iter()
+                .filter_map(|(dir, evidence)| {
+                        let in_game_dir = evidence.in_game_dir;
+                        let name_match = dir.strip_prefix(&evidence.root).is_ok_and(|rel| {
+                                let rel = normalize(&rel.to_string_lossy());
+                                keywords.iter().any(|k| rel.contains(k.as_str()))
+                        });
+                        // 常见位置里的目录需要有实质证据，否则只是碰巧有 .dat 文件的无关程序
+                        if !in_game_dir && evidence.modified_in_session == 0 && !name_match {
+                                return None;
+                        }
+                        if evidence.modified_in_session == 0 && evidence.save_like_files == 0 {
+                                return None;
+                        }
+
+                        let engine_match = engine_dirs.contains(&dir);
+                        let score = evidence.modified_in_session.min(20) as u32 * 10
+                                + evidence.save_like_files.min(20) as u32 * 2
+                                + if name_match { 15 } else { 0 }
+                                + if engine_match { 20 } else { 0 }
+                                + if in_game_dir { 3 } else { 0 };
+
+                        Some(SaveCandidate {
+                                path: dir.to_string_lossy().into_owned(),
+                                score,
+                                modified_in_session: evidence.modified_in_session,
+                                save_like_files: evidence.save_like_files,
+                                name_match,
+                                engine_match,
+                                in_game_dir,
+                                last_modified: evidence.last_modified,
+                                sample_files: evidence.samples,
+                        })
+                })
+                .collect();
+
+        candidates.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+        candidates.truncate(MAX_CANDIDATES);
+        candidates
+}
+
+/// 遍历一个根目录，把文件的证据记到其所在目录上
+fn scan(
+        root: &Path,
+        depth: usize,
+        target: &DiscoveryTarget,
+        found: &mut HashMap<PathBuf, Evidence>,
+        in_game_dir: bool,
+) {
+        let walker = WalkDir::new(root)
+                .max_depth(depth)
+                .into_iter()
+                .filter_entry(|e| !is_skipped(e, target, in_game_dir));
+
+        for entry in walker.filter_map(|e| e.ok()) {
+                if !entry.file_type().is_file() {
+                        continue;
+                }
+                let Some(dir) = entry.path().parent() else {
+                        continue;
+                };
+                let Ok(meta) = entry.metadata() else {
+                        continue;
+                };
+
+                let modified = meta.modified().ok().map(DateTime::<Local>::from);
+                let in_session = match (target.session, modified) {
+                        | (Some((start, end)), Some(m)) => m >= start && m <= end,
+                        | _ => false,
+                };
+                let save_like = is_save_like(entry.path());
+                if !in_session && !save_like {
+                        continue;
+                }
+
+                let evidence = found.entry(dir.to_path_buf()).or_insert_with(|| Evidence {
+                        root: root.to_path_buf(),
+                        in_game_dir,
+                        modified_in_session: 0,
+                        save_like_files: 0,
+                        last_modified: None,
+                        samples: Vec::new(),
+                });
+                if in_session {
+                        evidence.modified_in_session += 1;
+                }
+                if save_like {
+                        evidence.save_like_files += 1;
+                }
+                if modified > evidence.last_modified {
+                        evidence.last_modified = modified;
+                }
+                if evidence.samples.len() < MAX_SAMPLES {
+                        evidence.samples
+                                .push(entry.file_name().to_string_lossy().into_owned());
+                }
+        }
+}
+
+/// 跳过噪声目录、排除目录；扫描常见位置时跳过游戏目录（已单独扫描）
+fn is_skipped(
+        entry: &DirEntry,
+        target: &DiscoveryTarget,
+        in_game_dir: bool,
+) -> bool {
+        if !entry.file_type().is_dir() || entry.depth() == 0 {
+                return false;
+        }
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_lowercase();
+        NOISE_DIRS.contains(&name.as_str())
+                || name.starts_with('.')
+                || target.excluded.iter().any(|ex| path.starts_with(ex))
+                || (!in_game_dir && path.starts_with(target.game_dir))
+}
+
+fn is_save_like(path: &Path) -> bool {
+        let ext = path
+                .extension()
+                .map(|e| e.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+        let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+        SAVE_EXTS.contains(&ext.as_str()) || SAVE_KEYWORDS.iter().any(|k| stem.contains(k))
+}
+
+/// 只保留字母和数字并转小写，用于宽松地匹配游戏名
+fn normalize(s: &str) -> String {
+        s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+}
+
+/// 常见的存档位置
+fn well_known_roots() -> Vec<PathBuf> {
+        let mut roots = Vec::new();
+        if let Some(user) = UserDirs::new()
+                && let Some(docs) = user.document_dir()
+        {
+                roots.push(docs.to_path_buf());
+        }
+        if let Some(base) = BaseDirs::new() {
+                roots.push(base.data_dir().to_path_buf());
+                roots.push(base.data_local_dir().to_path_buf());
+                let home = base.home_dir();
+                // Windows 上 Unity 等引擎使用的 AppData\LocalLow 与 Saved Games
+                roots.push(home.join("AppData").join("LocalLow"));
+                roots.push(home.join("Saved Games"));
+        }
+        roots.sort();
+        roots.dedup();
+        roots
+}
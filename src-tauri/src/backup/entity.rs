@@ -0,0 +1,183 @@
+//! 存档备份相关数据结构
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+/// 单个存档快照
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSnapshot {
+        /// 快照 ID（即时间戳，形如 `20260418-213005123`）
+        pub id: String,
+        pub game_id: String,
+        pub created_at: DateTime<Local>,
+        pub kind: SnapshotKind,
+        pub format: SnapshotFormat,
+        /// 快照占用的存储（字节）：ZIP 为包大小；
+        /// 去重仓库中为其引用内容的总大小，这部分可能与其他快照共享
+        pub size: u64,
+        /// 快照内存档的原始大小（字节）
+        pub original_size: u64,
+        pub file_count: usize,
+}
+
+/// 快照的来源
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SnapshotKind {
+        /// 手动 / 自动备份
+        #[default]
+        Regular,
+        /// 恢复前自动保存的当前存档，用于撤销一次恢复
+        PreRestore,
+}
+
+/// 快照的存储格式
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum SnapshotFormat {
+        /// 整包 ZIP（旧版本生成）
+        Archive,
+        /// 去重仓库中的清单
+        Manifest,
+}
+
+/// 快照清单：描述某一时刻存档目录里的所有文件
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotManifest {
+        pub version: u32,
+        pub game_id: String,
+        pub created_at: DateTime<Local>,
+        #[serde(default)]
+        pub kind: SnapshotKind,
+        pub files: Vec<ManifestEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestEntry {
+        /// 所属存档位置的序号（旧版清单只有一个位置，缺省为 0）
+        #[serde(default)]
+        pub location: u32,
+        /// 相对所属存档位置的路径，统一使用 `/` 分隔
+        pub path: String,
+        /// 内容的 SHA-256
+        pub hash: String,
+        pub size: u64,
+        pub modified: Option<DateTime<Local>>,
+}
+
+/// 恢复快照时单个文件会发生的变化
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum FileChange {
+        /// 只存在于快照中，恢复后会新增
+        Added,
+        /// 两边都有但内容不同，恢复后会被快照版本覆盖
+        Modified,
+        /// 只存在于当前存档中，恢复后会被删除
+        Deleted,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiff {
+        pub location: u32,
+        pub path: String,
+        pub change: FileChange,
+        pub backup_size: Option<u64>,
+        pub live_size: Option<u64>,
+        pub backup_modified: Option<DateTime<Local>>,
+        pub live_modified: Option<DateTime<Local>>,
+}
+
+/// 一次恢复（或演练）的结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreReport {
+        pub game_id: String,
+        pub snapshot_id: String,
+        /// 是否真正写入了存档目录（演练时为 false）
+        pub applied: bool,
+        /// 恢复前为当前存档保存的快照 ID，当前存档不会丢失任何内容时为空
+        pub safety_snapshot: Option<String>,
+        pub changes: Vec<FileDiff>,
+}
+
+/// 一份存档内容的概况，用于备份前的合理性检查
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SaveStats {
+        pub total_size: u64,
+        pub file_count: usize,
+}
+
+/// 备份校验发现的问题类型
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum BackupIssueKind {
+        /// 游戏设置了存档路径，但没有任何快照
+        Missing,
+        /// 快照无法读取、CRC 校验失败或引用的内容缺失 / 被篡改
+        Corrupt,
+        /// 最新快照早于存档目录里最后一次修改，当前进度尚未备份
+        Stale,
+        /// 快照对应的游戏已被删除
+        Orphaned,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupIssue {
+        pub game_id: String,
+        /// 问题所在的快照，针对整个游戏的问题（缺失 / 过期 / 孤立）为空
+        pub snapshot_id: Option<String>,
+        pub kind: BackupIssueKind,
+        pub detail: String,
+}
+
+/// 一次备份校验的结果
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyReport {
+        pub checked_at: Option<DateTime<Local>>,
+        /// 参与校验的游戏数
+        pub game_count: usize,
+        /// 逐个读取校验过的快照数
+        pub snapshot_count: usize,
+        pub issues: Vec<BackupIssue>,
+}
+
+/// 一次定时备份的执行记录
+#[derive(Debug, Serialize, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupRun {
+        pub id: i64,
+        pub started_at: DateTime<Local>,
+        pub finished_at: DateTime<Local>,
+        pub backed_up: u32,
+        /// 存档没有变化或存档目录尚不存在而跳过的游戏数
+        pub skipped: u32,
+        pub failed: u32,
+        /// 失败游戏的错误信息，每行一条
+        pub errors: Option<String>,
+}
+
+/// 自动发现的存档目录候选
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveCandidate {
+        pub path: String,
+        /// 综合得分，越高越可能是存档目录
+        pub score: u32,
+        /// 上次游玩期间被修改过的文件数
+        pub modified_in_session: usize,
+        /// 文件名 / 扩展名看起来像存档的文件数
+        pub save_like_files: usize,
+        /// 路径中包含游戏名或开发商名
+        pub name_match: bool,
+        /// 是游戏引擎惯用的存档目录
+        pub engine_match: bool,
+        /// 位于游戏安装目录内
+        pub in_game_dir: bool,
+        pub last_modified: Option<DateTime<Local>>,
+        /// 作为依据的部分文件（相对候选目录）
+        pub sample_files: Vec<String>,
+}
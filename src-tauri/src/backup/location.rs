@@ -0,0 +1,213 @@
+//! 存档位置解析
+//!
+//! 一个游戏的存档可以分布在多个目录，每个目录由 [`SaveLocation`] 描述：
+//! 路径模板展开占位符后得到实际目录，再用 include / exclude 规则筛选文件。
+//! 快照清单里的文件以「位置序号 + 相对路径」定位，恢复时写回对应目录。
+//!
+//! 未设置存档位置列表的游戏沿用 `save_data_path`，视为一个不带过滤规则的位置。
+
+use std::path::{Path, PathBuf};
+
+use directories::{BaseDirs, UserDirs};
+use glob::{MatchOptions, Pattern};
+use walkdir::WalkDir;
+
+use crate::{backup::store, error::AppError, game::entity::SaveLocation};
+
+/// 过滤规则统一忽略大小写，`*` 可以跨目录匹配
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+        case_sensitive: false,
+        require_literal_separator: false,
+        require_literal_leading_dot: false,
+};
+
+/// 展开占位符后的单个存档位置
+#[derive(Debug, Clone)]
+pub struct SaveRoot {
+        pub dir: PathBuf,
+        include: Vec<Pattern>,
+        exclude: Vec<Pattern>,
+}
+
+/// 存档位置中的一个文件
+#[derive(Debug, Clone)]
+pub struct SaveFile {
+        /// 所属位置在列表中的序号
+        pub location: u32,
+        /// 相对所属位置的路径，统一使用 `/` 分隔
+        pub path: String,
+        pub abs: PathBuf,
+}
+
+/// 一个游戏的全部存档位置
+#[derive(Debug, Clone, Default)]
+pub struct SaveSet {
+        pub roots: Vec<SaveRoot>,
+}
+
+impl SaveRoot {
+        /// 文件是否在备份范围内
+        pub fn accepts(
+                &self,
+                rel: &str,
+        ) -> bool {
+                let included = self.include.is_empty()
+                        || self.include
+                                .iter()
+                                .any(|p| p.matches_with(rel, MATCH_OPTIONS));
+                included && !self
+                        .exclude
+                        .iter()
+                        .any(|p| p.matches_with(rel, MATCH_OPTIONS))
+        }
+}
+
+impl SaveSet {
+        /// 展开游戏的存档位置；`locations` 为空时退回到旧的 `save_data_path`
+        pub fn resolve(
+                locations: &[SaveLocation],
+                legacy_path: Option<&str>,
+                game_dir: &Path,
+        ) -> Result<Self, AppError> {
+                let roots = if locations.is_empty() {
+                        legacy_path
+                                .filter(|p| !p.is_empty())
+                                .map(|p| SaveRoot {
+                                        dir: PathBuf::from(p),
+                                        include: Vec::new(),
+                                        exclude: Vec::new(),
+                                })
+                                .into_iter()
+                                .collect()
+                } else {
+                        locations
+                                .iter()
+                                .map(|loc| {
+                                        Ok(SaveRoot {
+                                                dir: expand(&loc.template, game_dir)?,
+                                                include: compile(&loc.include)?,
+                                                exclude: compile(&loc.exclude)?,
+                                        })
+                                })
+                                .collect::<Result<_, AppError>>()?
+                };
+                Ok(Self { roots })
+        }
+
+        pub fn is_empty(&self) -> bool {
+                self.roots.is_empty()
+        }
+
+        /// 是否至少有一个位置的目录已经存在
+        pub fn exists(&self) -> bool {
+                self.roots.iter().any(|r| r.dir.is_dir())
+        }
+
+        /// 所有位置里在备份范围内的文件
+        pub fn files(&self) -> Vec<SaveFile> {
+                let mut files = Vec::new();
+                for (i, root) in self.roots.iter().enumerate() {
+                        for entry in WalkDir::new(&root.dir).into_iter().filter_map(|e| e.ok()) {
+                                if !entry.file_type().is_file() {
+                                        continue;
+                                }
+                                let Ok(rel) = entry.path().strip_prefix(&root.dir) else {
+                                        continue;
+                                };
+                                let path = store::to_manifest_path(rel);
+                                if root.accepts(&path) {
+                                        files.push(SaveFile {
+                                                location: i as u32,
+                                                path,
+                                                abs: entry.into_path(),
+                                        });
+                                }
+                        }
+                }
+                files
+        }
+
+        /// 快照中的文件 → 恢复时的实际路径
+        pub fn target(
+                &self,
+                location: u32,
+                rel: &str,
+        ) -> Result<PathBuf, AppError> {
+                let root = self.roots.get(location as usize).ok_or_else(|| {
+                        AppError::Fs(format!(
+                                "快照中的文件 {} 属于第 {} 个存档位置，但当前只设置了 {} 个",
+                                rel,
+                                location + 1,
+                                self.roots.len()
+                        ))
+                })?;
+                store::resolve_manifest_path(&root.dir, rel)
+        }
+}
+
+/// 展开路径模板中的占位符
+pub fn expand(
+        template: &str,
+        game_dir: &Path,
+) -> Result<PathBuf, AppError> {
+        let mut out = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+                out.push_str(&rest[..start]);
+                let end = rest[start..]
+                        .find('}')
+                        .ok_or_else(|| invalid(template, "括号未闭合"))?;
+                let name = &rest[start + 1..start + end];
+                let value = placeholder(name, game_dir).ok_or_else(|| {
+                        invalid(template, &format!("未知或无法解析的占位符 {{{}}}", name))
+                })?;
+                out.push_str(&value.to_string_lossy());
+                rest = &rest[start + end + 1..];
+        }
+        out.push_str(rest);
+
+        if out.trim().is_empty() {
+                return Err(invalid(template, "路径为空"));
+        }
+        let path = PathBuf::from(out);
+        Ok(if path.is_absolute() {
+                path
+        } else {
+                game_dir.join(path)
+        })
+}
+
+fn placeholder(
+        name: &str,
+        game_dir: &Path,
+) -> Option<PathBuf> {
+        match name {
+                | "game_dir" => Some(game_dir.to_path_buf()),
+                | "home" => BaseDirs::new().map(|d| d.home_dir().to_path_buf()),
+                | "appdata" => BaseDirs::new().map(|d| d.data_dir().to_path_buf()),
+                | "documents" => {
+                        UserDirs::new().and_then(|d| d.document_dir().map(Path::to_path_buf))
+                },
+                | _ => None,
+        }
+}
+
+fn compile(patterns: &[String]) -> Result<Vec<Pattern>, AppError> {
+        patterns.iter()
+                .map(|p| {
+                        Pattern::new(p).map_err(|e| {
+                                AppError::Resolve(p.clone(), format!("过滤规则不合法: {}", e))
+                        })
+                })
+                .collect()
+}
+
+fn invalid(
+        template: &str,
+        reason: &str,
+) -> AppError {
+        AppError::Resolve(
+                template.to_string(),
+                format!("存档路径模板不合法: {}", reason),
+        )
+}
@@ -1,2 +1,16 @@
 //! 存档备份模块
 pub mod commands;
+pub mod discovery;
+pub mod entity;
+pub mod location;
+pub mod retention;
+pub mod schedule;
+pub mod snapshot;
+pub mod store;
+pub mod verify;
+
+use tauri::AppHandle;
+
+pub fn init(handle: &AppHandle) {
+        schedule::start(handle);
+}
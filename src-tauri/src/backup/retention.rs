@@ -0,0 +1,62 @@
+//! 快照保留策略
+//!
+//! 规则与 restic 的 `--keep-*` 类似：`keep_last` 保留最近 N 份，
+//! `keep_daily` / `keep_weekly` 在最近 N 个有快照的日 / 周里各保留最新一份，三者取并集。
+
+use std::collections::HashSet;
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::{backup::entity::BackupSnapshot, config::entity::RetentionPolicy};
+
+/// 挑出按策略应当删除的快照 ID
+///
+/// `snapshots` 必须按创建时间从新到旧排序；最新的一份无论策略如何都会保留
+pub fn select_expired(
+        snapshots: &[BackupSnapshot],
+        policy: &RetentionPolicy,
+) -> Vec<String> {
+        let mut keep: HashSet<&str> = snapshots
+                .iter()
+                .take(policy.keep_last.max(1) as usize)
+                .map(|s| s.id.as_str())
+                .collect();
+
+        keep_newest_per_bucket(snapshots, policy.keep_daily, &mut keep, |d| {
+                (d.year(), d.ordinal())
+        });
+        keep_newest_per_bucket(snapshots, policy.keep_weekly, &mut keep, |d| {
+                let week = d.iso_week();
+                (week.year(), week.week())
+        });
+
+        snapshots
+                .iter()
+                .filter(|s| !keep.contains(s.id.as_str()))
+                .map(|s| s.id.clone())
+                .collect()
+}
+
+/// 按 `bucket` 分组，在最近 `limit` 个分组里各保留最新的一份
+fn keep_newest_per_bucket<'a>(
+        snapshots: &'a [BackupSnapshot],
+        limit: u32,
+        keep: &mut HashSet<&'a str>,
+        bucket: impl Fn(NaiveDate) -> (i32, u32),
+) {
+        let mut last_bucket = None;
+        let mut count = 0;
+
+        for snapshot in snapshots {
+                if count >= limit {
+                        break;
+                }
+                let key = bucket(snapshot.created_at.date_naive());
+                // 已按从新到旧排序，每个分组遇到的第一份就是最新的
+                if last_bucket != Some(key) {
+                        keep.insert(snapshot.id.as_str());
+                        last_bucket = Some(key);
+                        count += 1;
+                }
+        }
+}
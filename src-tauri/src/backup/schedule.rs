@@ -0,0 +1,228 @@
+//! 后台定时备份
+//!
+//! 每分钟检查一次 `Storage::backup_schedule`，到点时对所有设置了存档路径的游戏执行备份。
+//! 计划随配置实时生效，无需重启；存档指纹没有变化的游戏会被跳过。
+
+use std::{str::FromStr, time::Duration};
+
+use chrono::{DateTime, Datelike, Local, Timelike};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_log::log::{error, info, warn};
+
+use crate::{
+        backup::commands as bc,
+        config::{entity::BackupSchedule, read_config},
+        error::AppError,
+};
+
+pub fn start(handle: &AppHandle) {
+        let Some(pool) = handle.try_state::<SqlitePool>() else {
+                error!("未能获取 SqlitePool，定时备份未启动");
+                return;
+        };
+        let pool = pool.inner().clone();
+
+        tauri::async_runtime::spawn(async move {
+                let mut last_run = bc::last_scheduled_run(&pool).await.unwrap_or_else(|e| {
+                        error!("读取定时备份记录失败: {}", e);
+                        None
+                });
+                // 记住上一次解析失败的表达式，避免每分钟重复告警
+                let mut invalid_expr: Option<String> = None;
+
+                loop {
+                        tokio::time::sleep(until_next_minute()).await;
+
+                        let schedule = match read_config() {
+                                | Ok(cfg) => cfg.storage.backup_schedule.clone(),
+                                | Err(e) => {
+                                        error!("读取定时备份配置失败: {}", e);
+                                        continue;
+                                },
+                        };
+
+                        let now = Local::now();
+                        let due = match is_due(&schedule, now, last_run) {
+                                | Ok(due) => {
+                                        invalid_expr = None;
+                                        due
+                                },
+                                | Err(e) => {
+                                        if let BackupSchedule::Cron { expr } = &schedule
+                                                && invalid_expr.as_ref() != Some(expr)
+                                        {
+                                                warn!("定时备份计划无效: {}", e);
+                                                invalid_expr = Some(expr.clone());
+                                        }
+                                        false
+                                },
+                        };
+                        if !due {
+                                continue;
+                        }
+
+                        last_run = Some(now);
+                        match bc::run_scheduled(&pool, now).await {
+                                | Ok(run) => info!(
+                                        "定时备份完成：备份 {} · 跳过 {} · 失败 {}",
+                                        run.backed_up, run.skipped, run.failed
+                                ),
+                                | Err(e) => error!("定时备份失败: {}", e),
+                        }
+                }
+        });
+
+        info!("定时备份已启动");
+}
+
+/// 距离下一个整分钟的时长（多等一秒，避免唤醒时还停在上一分钟）
+fn until_next_minute() -> Duration {
+        let now = Local::now();
+        Duration::from_secs(60 - now.second() as u64 + 1)
+}
+
+/// 判断此刻是否应执行定时备份
+fn is_due(
+        schedule: &BackupSchedule,
+        now: DateTime<Local>,
+        last_run: Option<DateTime<Local>>,
+) -> Result<bool, AppError> {
+        match schedule {
+                | BackupSchedule::Off => Ok(false),
+                | BackupSchedule::Interval { minutes } => {
+                        let interval = chrono::Duration::minutes(i64::from((*minutes).max(1)));
+                        Ok(last_run.is_none_or(|last| now - last >= interval))
+                },
+                | BackupSchedule::Cron { expr } => {
+                        let cron = CronExpr::from_str(expr)?;
+                        // 同一分钟内只执行一次
+                        let same_minute = |last: DateTime<Local>| {
+                                last.date_naive() == now.date_naive()
+                                        && last.hour() == now.hour()
+                                        && last.minute() == now.minute()
+                        };
+                        Ok(cron.matches(&now) && !last_run.is_some_and(same_minute))
+                },
+        }
+}
+
+// ── cron 表达式 ───────────────────────────────────────────────────────────────
+
+/// 五段式 cron 表达式，每段支持 `*`、`a`、`a-b`、`*/n`、`a-b/n` 及逗号分隔的组合
+///
+/// 与常见 cron 一致：日和周同时受限时，满足其一即可；周的 0 和 7 都表示周日
+#[derive(Debug, Clone)]
+pub struct CronExpr {
+        minute: u64,
+        hour: u64,
+        day: u64,
+        month: u64,
+        weekday: u64,
+        day_any: bool,
+        weekday_any: bool,
+}
+
+impl FromStr for CronExpr {
+        type Err = AppError;
+
+        fn from_str(expr: &str) -> Result<Self, Self::Err> {
+                let fields: Vec<&str> = expr.split_whitespace().collect();
+                let [minute, hour, day, month, weekday] = fields[..] else {
+                        return Err(AppError::Generic(format!(
+                                "cron 表达式需要 5 段（分 时 日 月 周）: {}",
+                                expr
+                        )));
+                };
+
+                let mut weekday_bits = parse_field(weekday, 0, 7)?;
+                // 7 与 0 都是周日
+                if weekday_bits & (1 << 7) != 0 {
+                        weekday_bits |= 1;
+                }
+
+                Ok(Self {
+                        minute: parse_field(minute, 0, 59)?,
+                        hour: parse_field(hour, 0, 23)?,
+                        day: parse_field(day, 1, 31)?,
+                        month: parse_field(month, 1, 12)?,
+                        weekday: weekday_bits,
+                        day_any: day == "*",
+                        weekday_any: weekday == "*",
+                })
+        }
+}
+
+impl CronExpr {
+        pub fn matches(
+                &self,
+                t: &DateTime<Local>,
+        ) -> bool {
+                let hit = |bits: u64, v: u32| bits & (1 << v) != 0;
+
+                let day_hit = hit(self.day, t.day());
+                let weekday_hit = hit(self.weekday, t.weekday().num_days_from_sunday());
+                let date_hit = match (self.day_any, self.weekday_any) {
+                        | (true, true) => true,
+                        | (false, true) => day_hit,
+                        | (true, false) => weekday_hit,
+                        | (false, false) => day_hit || weekday_hit,
+                };
+
+                hit(self.minute, t.minute())
+                        && hit(self.hour, t.hour())
+                        && hit(self.month, t.month())
+                        && date_hit
+        }
+}
+
+/// 把一段 cron 字段解析成位集合
+fn parse_field(
+        field: &str,
+        min: u32,
+        max: u32,
+) -> Result<u64, AppError> {
+        let invalid = || AppError::Generic(format!("cron 字段不合法: {}", field));
+        let num = |s: &str| -> Result<u32, AppError> {
+                let n: u32 = s.parse().map_err(|_| invalid())?;
+                if (min..=max).contains(&n) {
+                        Ok(n)
+                } else {
+                        Err(invalid())
+                }
+        };
+
+        let mut bits = 0u64;
+        for part in field.split(',') {
+                let (range, step) = match part.split_once('/') {
+                        | Some((range, step)) => {
+                                (range, step.parse::<u32>().map_err(|_| invalid())?)
+                        },
+                        | None => (part, 1),
+                };
+                if step == 0 {
+                        return Err(invalid());
+                }
+
+                let (start, end) = match range {
+                        | "*" => (min, max),
+                        | _ => match range.split_once('-') {
+                                | Some((a, b)) => (num(a)?, num(b)?),
+                                // `a/n` 表示从 a 开始到最大值
+                                | None if part.contains('/') => (num(range)?, max),
+                                | None => {
+                                        let n = num(range)?;
+                                        (n, n)
+                                },
+                        },
+                };
+                if start > end {
+                        return Err(invalid());
+                }
+
+                for v in (start..=end).step_by(step as usize) {
+                        bits |= 1 << v;
+                }
+        }
+        Ok(bits)
+}
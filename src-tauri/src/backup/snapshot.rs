@@ -0,0 +1,480 @@
+//! 存档快照的磁盘布局与读写
+//!
+//! ```
+//! backup_save_path/
+//! ├── blobs/                        去重仓库，见 [`store`](crate::backup::store)
+//! └── game_<id>/
+//!     ├── 20260418-213005123.zip    旧版整包快照（只读，仍可恢复）
+//!     └── 20260420-090112004.json   快照清单
+//! ```
+//!
+//! 文件名即快照 ID（本地时间戳，精确到毫秒）。新快照一律写入去重仓库；
+//! 旧版本遗留的 `backup_save_path/game_<id>.zip` 会在首次访问时迁入上述目录。
+
+use std::{
+        cmp::Reverse,
+        collections::{HashMap, HashSet},
+        path::{Path, PathBuf},
+};
+
+use chrono::{DateTime, Local, NaiveDateTime};
+use sha2::{Digest, Sha256};
+use tauri_plugin_log::log::{info, warn};
+
+use crate::{
+        backup::{
+                entity::{
+                        BackupSnapshot, FileChange, FileDiff, ManifestEntry, SaveStats,
+                        SnapshotFormat, SnapshotKind,
+                },
+                location::{SaveFile, SaveSet},
+                retention::select_expired,
+                store::{self, MANIFEST_EXT},
+        },
+        config::entity::RetentionPolicy,
+        error::AppError,
+        infra::archive::{extract_zip, list_zip, read_zip_files},
+};
+
+/// 快照 ID 的时间格式
+const ID_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+/// 存档总大小不超过该值时视为空存档，拒绝备份
+const MIN_SAVE_SIZE: u64 = 1024;
+/// 与上一份快照相比，大小或文件数缩水超过该比例时视为存档异常（被清空 / 损坏）
+const MAX_SHRINK_RATIO: f64 = 0.5;
+/// 恢复前的保护快照不参与保留策略，只保留最近的这么多份
+const KEEP_PRE_RESTORE: usize = 3;
+
+// ── 路径 ──────────────────────────────────────────────────────────────────────
+
+/// 单个游戏的快照目录
+pub fn game_dir(
+        root: &Path,
+        game_id: &str,
+) -> PathBuf {
+        root.join(format!("game_{}", game_id))
+}
+
+/// 查找快照文件及其格式；ID 不合法时返回错误，防止前端传入的 ID 拼出目录外的路径
+pub fn locate(
+        root: &Path,
+        game_id: &str,
+        snapshot_id: &str,
+) -> Result<(PathBuf, SnapshotFormat), AppError> {
+        if parse_id(snapshot_id).is_none() {
+                return Err(AppError::Resolve(
+                        snapshot_id.into(),
+                        "快照 ID 不合法".into(),
+                ));
+        }
+        let dir = game_dir(root, game_id);
+        let manifest = dir.join(format!("{}.{}", snapshot_id, MANIFEST_EXT));
+        if manifest.is_file() {
+                return Ok((manifest, SnapshotFormat::Manifest));
+        }
+        let archive = dir.join(format!("{}.zip", snapshot_id));
+        if archive.is_file() {
+                return Ok((archive, SnapshotFormat::Archive));
+        }
+        Err(AppError::Fs(format!(
+                "未找到游戏 {} 的快照 {}",
+                game_id, snapshot_id
+        )))
+}
+
+fn format_of(path: &Path) -> Option<SnapshotFormat> {
+        match path.extension()?.to_str()? {
+                | "zip" => Some(SnapshotFormat::Archive),
+                | MANIFEST_EXT => Some(SnapshotFormat::Manifest),
+                | _ => None,
+        }
+}
+
+fn parse_id(id: &str) -> Option<DateTime<Local>> {
+        NaiveDateTime::parse_from_str(id, ID_FORMAT)
+                .ok()?
+                .and_local_timezone(Local)
+                .earliest()
+}
+
+// ── 查询 ──────────────────────────────────────────────────────────────────────
+
+/// 列出某个游戏的所有快照（从新到旧）
+pub fn list(
+        root: &Path,
+        game_id: &str,
+) -> Result<Vec<BackupSnapshot>, AppError> {
+        migrate_legacy(root, game_id)?;
+
+        let dir = game_dir(root, game_id);
+        if !dir.is_dir() {
+                return Ok(Vec::new());
+        }
+
+        let mut snapshots = Vec::new();
+        for entry in std::fs::read_dir(&dir)?.flatten() {
+                let path = entry.path();
+                let Some(format) = format_of(&path) else {
+                        continue;
+                };
+                let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+                        continue;
+                };
+                let Some(created_at) = parse_id(id) else {
+                        continue;
+                };
+
+                // 损坏的快照也列出来，交给校验 / 清理处理
+                let (kind, size, original_size, file_count) = match format {
+                        | SnapshotFormat::Archive => {
+                                let entries =
+                                        list_zip(&path.to_string_lossy()).unwrap_or_else(|e| {
+                                                warn!("读取快照 {:?} 失败: {}", path, e);
+                                                Vec::new()
+                                        });
+                                let files = entries.iter().filter(|e| !e.is_dir);
+                                (
+                                        SnapshotKind::Regular,
+                                        entry.metadata().map(|m| m.len()).unwrap_or(0),
+                                        files.clone().map(|e| e.size).sum(),
+                                        files.count(),
+                                )
+                        },
+                        | SnapshotFormat::Manifest => match store::read_manifest(&path) {
+                                | Ok(m) => {
+                                        let total = m.files.iter().map(|f| f.size).sum();
+                                        (m.kind, total, total, m.files.len())
+                                },
+                                | Err(e) => {
+                                        warn!("读取快照 {:?} 失败: {}", path, e);
+                                        (SnapshotKind::Regular, 0, 0, 0)
+                                },
+                        },
+                };
+
+                snapshots.push(BackupSnapshot {
+                        id: id.to_string(),
+                        game_id: game_id.to_string(),
+                        created_at,
+                        kind,
+                        format,
+                        size,
+                        original_size,
+                        file_count,
+                });
+        }
+
+        snapshots.sort_by_key(|s| Reverse(s.created_at));
+        Ok(snapshots)
+}
+
+/// 旧版单文件备份 `game_<id>.zip` → 以其修改时间为 ID 迁入快照目录
+fn migrate_legacy(
+        root: &Path,
+        game_id: &str,
+) -> Result<(), AppError> {
+        let legacy = root.join(format!("game_{}.zip", game_id));
+        if !legacy.is_file() {
+                return Ok(());
+        }
+
+        let modified: DateTime<Local> = std::fs::metadata(&legacy)?.modified()?.into();
+        let dir = game_dir(root, game_id);
+        std::fs::create_dir_all(&dir)?;
+        std::fs::rename(
+                &legacy,
+                dir.join(format!("{}.zip", modified.format(ID_FORMAT))),
+        )?;
+
+        info!("已迁移旧版备份: {:?}", legacy);
+        Ok(())
+}
+
+// ── 写入 ──────────────────────────────────────────────────────────────────────
+
+/// 统计存档文件的总大小与文件数
+pub fn scan_save(files: &[SaveFile]) -> SaveStats {
+        files.iter()
+                .filter_map(|f| std::fs::metadata(&f.abs).ok())
+                .fold(SaveStats::default(), |acc, m| SaveStats {
+                        total_size: acc.total_size + m.len(),
+                        file_count: acc.file_count + 1,
+                })
+}
+
+/// 存档的指纹：由所有文件的位置、相对路径、大小和修改时间计算，不读取文件内容
+///
+/// 指纹不变即视为存档没有变化，定时备份据此跳过
+pub fn fingerprint(set: &SaveSet) -> String {
+        let mut files: Vec<(u32, String, u64, i64)> = set
+                .files()
+                .into_iter()
+                .filter_map(|f| {
+                        let meta = std::fs::metadata(&f.abs).ok()?;
+                        let modified = meta
+                                .modified()
+                                .ok()
+                                .map(|t| DateTime::<Local>::from(t).timestamp_millis())
+                                .unwrap_or(0);
+                        Some((f.location, f.path, meta.len(), modified))
+                })
+                .collect();
+        files.sort();
+
+        let mut hasher = Sha256::new();
+        for (location, path, size, modified) in files {
+                hasher.update(format!("{}\0{}\0{}\0{}\n", location, path, size, modified));
+        }
+        format!("{:x}", hasher.finalize())
+}
+
+/// 备份前的合理性检查：拒绝空存档，以及相比上一份快照明显缩水的存档，
+/// 避免一次损坏的自动备份把好的快照挤出保留窗口
+pub fn check_sanity(
+        current: &SaveStats,
+        previous: Option<&BackupSnapshot>,
+) -> Result<(), AppError> {
+        if current.total_size <= MIN_SAVE_SIZE {
+                return Err(AppError::Fs(format!(
+                        "存档内容过小 ({:.2} KB)，已拦截备份以保护旧数据",
+                        current.total_size as f64 / 1024.0
+                )));
+        }
+
+        let Some(prev) = previous else {
+                return Ok(());
+        };
+
+        let shrunk = |now: f64, before: f64| before > 0.0 && now < before * MAX_SHRINK_RATIO;
+        if shrunk(current.total_size as f64, prev.original_size as f64)
+                || shrunk(current.file_count as f64, prev.file_count as f64)
+        {
+                return Err(AppError::Fs(format!(
+                        "存档相比上一份快照 {} 明显缩水 ({} 个文件 / {:.2} KB → {} 个文件 / {:.2} KB)，\
+                         已拦截备份以保护旧数据",
+                        prev.id,
+                        prev.file_count,
+                        prev.original_size as f64 / 1024.0,
+                        current.file_count,
+                        current.total_size as f64 / 1024.0
+                )));
+        }
+
+        Ok(())
+}
+
+/// 为游戏的存档创建一份新快照
+///
+/// 常规快照写入前会做合理性检查，`force` 为 true 时跳过与上一份快照的对比
+/// （空存档检查仍然生效）；恢复前的保护快照原样保存，不做任何检查
+pub fn create(
+        root: &Path,
+        game_id: &str,
+        set: &SaveSet,
+        kind: SnapshotKind,
+        force: bool,
+) -> Result<BackupSnapshot, AppError> {
+        if !set.exists() {
+                let dirs: Vec<_> = set.roots.iter().map(|r| r.dir.to_string_lossy()).collect();
+                return Err(AppError::Resolve(dirs.join(", "), "存档目录不存在".into()));
+        }
+
+        let files = set.files();
+        let stats = scan_save(&files);
+        if kind == SnapshotKind::Regular {
+                let existing = list(root, game_id)?;
+                let previous = if force {
+                        None
+                } else {
+                        existing.iter().find(|s| s.kind == SnapshotKind::Regular)
+                };
+                check_sanity(&stats, previous)?;
+        }
+
+        let now = Local::now();
+        let id = now.format(ID_FORMAT).to_string();
+        let created_at = parse_id(&id).unwrap_or(now);
+        let dir = game_dir(root, game_id);
+        std::fs::create_dir_all(&dir)?;
+
+        let _guard = store::lock()?;
+        let (manifest, added_bytes) =
+                store::snapshot_files(root, game_id, &files, created_at, kind)?;
+        store::write_manifest(&dir.join(format!("{}.{}", id, MANIFEST_EXT)), &manifest)?;
+        info!(
+                "游戏 {} 新快照 {}：{} 个文件，新增内容 {:.2} KB",
+                game_id,
+                id,
+                manifest.files.len(),
+                added_bytes as f64 / 1024.0
+        );
+
+        Ok(BackupSnapshot {
+                id,
+                game_id: game_id.to_string(),
+                created_at,
+                kind,
+                format: SnapshotFormat::Manifest,
+                size: stats.total_size,
+                original_size: stats.total_size,
+                file_count: stats.file_count,
+        })
+}
+
+/// 按保留策略删除过期快照，并回收不再被引用的内容，返回被删除的快照 ID
+pub fn prune(
+        root: &Path,
+        game_id: &str,
+        policy: &RetentionPolicy,
+) -> Result<Vec<String>, AppError> {
+        let (regular, pre_restore): (Vec<_>, Vec<_>) = list(root, game_id)?
+                .into_iter()
+                .partition(|s| s.kind == SnapshotKind::Regular);
+        let mut expired = select_expired(&regular, policy);
+        expired.extend(pre_restore.into_iter().skip(KEEP_PRE_RESTORE).map(|s| s.id));
+
+        for id in &expired {
+                let (path, _) = locate(root, game_id, id)?;
+                std::fs::remove_file(path)?;
+        }
+
+        if !expired.is_empty() {
+                info!("游戏 {} 清理过期快照 {} 份", game_id, expired.len());
+                let _guard = store::lock()?;
+                store::gc(root)?;
+        }
+        Ok(expired)
+}
+
+// ── 恢复 ──────────────────────────────────────────────────────────────────────
+
+/// 读取快照包含的文件列表（ZIP 快照会现场计算内容哈希）
+pub fn entries(
+        root: &Path,
+        game_id: &str,
+        snapshot_id: &str,
+) -> Result<Vec<ManifestEntry>, AppError> {
+        match locate(root, game_id, snapshot_id)? {
+                | (path, SnapshotFormat::Manifest) => Ok(store::read_manifest(&path)?.files),
+                | (path, SnapshotFormat::Archive) => {
+                        let mut files = Vec::new();
+                        read_zip_files(&path, |entry, reader| {
+                                files.push(ManifestEntry {
+                                        location: 0,
+                                        path: entry.name.replace('\\', "/"),
+                                        hash: store::hash_reader(reader)?,
+                                        size: entry.size,
+                                        modified: None,
+                                });
+                                Ok(())
+                        })?;
+                        Ok(files)
+                },
+        }
+}
+
+/// 对比快照与当前存档，列出恢复该快照会带来的改动（内容相同的文件不列出）
+pub fn diff(
+        root: &Path,
+        game_id: &str,
+        snapshot_id: &str,
+        set: &SaveSet,
+) -> Result<Vec<FileDiff>, AppError> {
+        let backup = entries(root, game_id, snapshot_id)?;
+
+        let mut live_files: HashMap<(u32, String), (PathBuf, std::fs::Metadata)> = set
+                .files()
+                .into_iter()
+                .filter_map(|f| {
+                        let meta = std::fs::metadata(&f.abs).ok()?;
+                        Some(((f.location, f.path), (f.abs, meta)))
+                })
+                .collect();
+
+        let mut changes = Vec::new();
+        for entry in backup {
+                let change = match live_files.remove(&(entry.location, entry.path.clone())) {
+                        | None => Some((FileChange::Added, None)),
+                        | Some((path, meta)) => {
+                                let same = meta.len() == entry.size
+                                        && store::hash_file(&path)? == entry.hash;
+                                (!same).then_some((FileChange::Modified, Some(meta)))
+                        },
+                };
+                if let Some((change, meta)) = change {
+                        changes.push(FileDiff {
+                                location: entry.location,
+                                path: entry.path,
+                                change,
+                                backup_size: Some(entry.size),
+                                live_size: meta.as_ref().map(|m| m.len()),
+                                backup_modified: entry.modified,
+                                live_modified: meta
+                                        .and_then(|m| m.modified().ok())
+                                        .map(DateTime::<Local>::from),
+                        });
+                }
+        }
+
+        changes.extend(live_files
+                .into_iter()
+                .map(|((location, path), (_, meta))| FileDiff {
+                        location,
+                        path,
+                        change: FileChange::Deleted,
+                        backup_size: None,
+                        live_size: Some(meta.len()),
+                        backup_modified: None,
+                        live_modified: meta.modified().ok().map(DateTime::<Local>::from),
+                }));
+
+        changes.sort_by(|a, b| (a.location, &a.path).cmp(&(b.location, &b.path)));
+        Ok(changes)
+}
+
+/// 用指定快照恢复存档，恢复后各存档位置中受管理的文件与快照完全一致
+///
+/// 被 include / exclude 规则排除在外的文件不会被删除
+pub fn restore(
+        root: &Path,
+        game_id: &str,
+        snapshot_id: &str,
+        set: &SaveSet,
+) -> Result<(), AppError> {
+        let tracked: HashSet<(u32, String)> = match locate(root, game_id, snapshot_id)? {
+                | (path, SnapshotFormat::Manifest) => {
+                        let manifest = store::read_manifest(&path)?;
+                        store::restore_manifest(root, &manifest, set)?;
+                        manifest.files
+                                .into_iter()
+                                .map(|f| (f.location, f.path))
+                                .collect()
+                },
+                | (path, SnapshotFormat::Archive) => {
+                        // 旧版整包快照只对应一个存档位置
+                        let dst = set.roots.first().map(|r| &r.dir).ok_or_else(|| {
+                                AppError::Resolve("none".into(), "该游戏未设置存档路径".into())
+                        })?;
+                        extract_zip(&path, dst)?;
+                        list_zip(&path.to_string_lossy())?
+                                .into_iter()
+                                .filter(|e| !e.is_dir)
+                                .map(|e| (0, e.name.replace('\\', "/")))
+                                .collect()
+                },
+        };
+        remove_untracked(set, &tracked)
+}
+
+/// 删除存档位置里不属于快照的文件（只处理备份范围内的文件）
+fn remove_untracked(
+        set: &SaveSet,
+        tracked: &HashSet<(u32, String)>,
+) -> Result<(), AppError> {
+        for file in set.files() {
+                if !tracked.contains(&(file.location, file.path)) {
+                        std::fs::remove_file(&file.abs)?;
+                }
+        }
+        Ok(())
+}
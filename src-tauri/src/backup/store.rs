@@ -0,0 +1,276 @@
+//! 内容寻址的存档存储
+//!
+//! ```
+//! backup_save_path/
+//! ├── blobs/
+//! │   └── 3f/
+//! │       └── 3fa9…e1        文件内容，以 SHA-256 命名，全部游戏共享
+//! └── game_<id>/
+//!     └── 20260418-213005123.json   快照清单：存档位置 + 相对路径 → 内容哈希
+//! ```
+//!
+//! 同一份内容只存一次，存档里只改了一个槽位时新快照几乎不占额外空间。
+//! 快照被清理后，不再被任何清单引用的 blob 由 [`gc`] 回收。
+
+use std::{
+        collections::HashSet,
+        fs::File,
+        io::{BufReader, Read},
+        path::{Component, Path, PathBuf},
+        sync::{Mutex, MutexGuard},
+};
+
+use chrono::{DateTime, Local};
+use sha2::{Digest, Sha256};
+use tauri_plugin_log::log::{info, warn};
+use walkdir::WalkDir;
+
+use crate::{
+        backup::{
+                entity::{ManifestEntry, SnapshotKind, SnapshotManifest},
+                location::{SaveFile, SaveSet},
+        },
+        error::AppError,
+};
+
+/// 清单格式版本，结构不兼容地变化时递增（2：文件带存档位置序号）
+pub const MANIFEST_VERSION: u32 = 2;
+/// 快照清单的扩展名
+pub const MANIFEST_EXT: &str = "json";
+
+const BLOB_DIR: &str = "blobs";
+
+/// 写快照与回收互斥：避免回收时误删另一个备份任务刚写入、清单还没落盘的 blob
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 获取仓库写锁，持有期间可以安全地写入快照或执行回收
+pub fn lock() -> Result<MutexGuard<'static, ()>, AppError> {
+        STORE_LOCK.lock().map_err(|e| AppError::Lock(e.to_string()))
+}
+
+// ── blob ──────────────────────────────────────────────────────────────────────
+
+/// blob 的存放路径（按哈希前两位分桶，避免单目录文件过多）
+pub fn blob_path(
+        root: &Path,
+        hash: &str,
+) -> PathBuf {
+        root.join(BLOB_DIR).join(&hash[..2]).join(hash)
+}
+
+/// 计算文件内容的 SHA-256
+pub fn hash_file(path: &Path) -> Result<String, AppError> {
+        hash_reader(&mut BufReader::new(File::open(path)?))
+}
+
+/// 计算数据流的 SHA-256
+pub fn hash_reader(reader: &mut dyn Read) -> Result<String, AppError> {
+        let mut hasher = Sha256::new();
+        let mut buf = [0u8; 64 * 1024];
+        loop {
+                let n = reader.read(&mut buf)?;
+                if n == 0 {
+                        break;
+                }
+                hasher.update(&buf[..n]);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// 把文件存入 blob 仓库，已存在相同内容时直接复用
+///
+/// 返回内容哈希，以及本次是否真正写入了新 blob
+fn put_file(
+        root: &Path,
+        path: &Path,
+) -> Result<(String, bool), AppError> {
+        let hash = hash_file(path)?;
+        let dst = blob_path(root, &hash);
+        if dst.is_file() {
+                return Ok((hash, false));
+        }
+
+        if let Some(parent) = dst.parent() {
+                std::fs::create_dir_all(parent)?;
+        }
+        // 先写临时文件再改名，避免中断后留下内容不完整的 blob
+        let tmp = dst.with_extension("tmp");
+        std::fs::copy(path, &tmp)?;
+        std::fs::rename(&tmp, &dst)?;
+        Ok((hash, true))
+}
+
+// ── 清单 ──────────────────────────────────────────────────────────────────────
+
+/// 把存档文件存入仓库，生成快照清单（不落盘）
+///
+/// 返回清单以及本次新写入 blob 的总字节数
+pub fn snapshot_files(
+        root: &Path,
+        game_id: &str,
+        save_files: &[SaveFile],
+        created_at: DateTime<Local>,
+        kind: SnapshotKind,
+) -> Result<(SnapshotManifest, u64), AppError> {
+        let mut files = Vec::new();
+        let mut added_bytes = 0;
+
+        for file in save_files {
+                let meta = std::fs::metadata(&file.abs)?;
+                let (hash, is_new) = put_file(root, &file.abs)?;
+                if is_new {
+                        added_bytes += meta.len();
+                }
+
+                files.push(ManifestEntry {
+                        location: file.location,
+                        path: file.path.clone(),
+                        hash,
+                        size: meta.len(),
+                        modified: meta.modified().ok().map(DateTime::<Local>::from),
+                });
+        }
+
+        files.sort_by(|a, b| (a.location, &a.path).cmp(&(b.location, &b.path)));
+
+        Ok((
+                SnapshotManifest {
+                        version: MANIFEST_VERSION,
+                        game_id: game_id.to_string(),
+                        created_at,
+                        kind,
+                        files,
+                },
+                added_bytes,
+        ))
+}
+
+/// 清单里统一使用 `/` 分隔的相对路径，跨平台恢复时不受分隔符影响
+pub fn to_manifest_path(rel: &Path) -> String {
+        rel.components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/")
+}
+
+/// 清单路径 → 目标目录下的实际路径；拒绝绝对路径和 `..`，防止写到存档目录之外
+pub fn resolve_manifest_path(
+        dst: &Path,
+        rel: &str,
+) -> Result<PathBuf, AppError> {
+        let rel = Path::new(rel);
+        if rel.components().any(|c| !matches!(c, Component::Normal(_))) {
+                return Err(AppError::Fs(format!("清单中存在非法路径: {:?}", rel)));
+        }
+        Ok(dst.join(rel))
+}
+
+pub fn write_manifest(
+        path: &Path,
+        manifest: &SnapshotManifest,
+) -> Result<(), AppError> {
+        let json = serde_json::to_vec_pretty(manifest)
+                .map_err(|e| AppError::Generic(e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)?;
+        Ok(())
+}
+
+pub fn read_manifest(path: &Path) -> Result<SnapshotManifest, AppError> {
+        let text = std::fs::read_to_string(path)?;
+        let manifest: SnapshotManifest = serde_json::from_str(&text)
+                .map_err(|e| AppError::Fs(format!("快照清单 {:?} 解析失败: {}", path, e)))?;
+        if manifest.version > MANIFEST_VERSION {
+                return Err(AppError::Fs(format!(
+                        "快照清单 {:?} 的版本 {} 高于当前支持的 {}",
+                        path, manifest.version, MANIFEST_VERSION
+                )));
+        }
+        Ok(manifest)
+}
+
+// ── 恢复 ──────────────────────────────────────────────────────────────────────
+
+/// 把清单中的所有文件写回各自的存档位置（不处理存档位置里多出来的文件）
+pub fn restore_manifest(
+        root: &Path,
+        manifest: &SnapshotManifest,
+        set: &SaveSet,
+) -> Result<(), AppError> {
+        // 先确认所有 blob 都在，避免恢复到一半才发现缺失
+        for entry in &manifest.files {
+                if !blob_path(root, &entry.hash).is_file() {
+                        return Err(AppError::Fs(format!(
+                                "快照内容缺失: {} ({})",
+                                entry.path, entry.hash
+                        )));
+                }
+        }
+
+        // 同样先确认所有文件都能对应到当前的存档位置
+        let targets = manifest
+                .files
+                .iter()
+                .map(|entry| set.target(entry.location, &entry.path))
+                .collect::<Result<Vec<_>, _>>()?;
+
+        for (entry, out) in manifest.files.iter().zip(targets) {
+                if let Some(parent) = out.parent() {
+                        std::fs::create_dir_all(parent)?;
+                }
+                std::fs::copy(blob_path(root, &entry.hash), out)?;
+        }
+
+        Ok(())
+}
+
+// ── 垃圾回收 ──────────────────────────────────────────────────────────────────
+
+/// 删除不再被任何快照清单引用的 blob，返回回收的字节数
+///
+/// 调用方需持有 [`lock`]；读取失败的清单会让本次回收中止，宁可多留也不误删
+pub fn gc(root: &Path) -> Result<u64, AppError> {
+        let blob_root = root.join(BLOB_DIR);
+        if !blob_root.is_dir() {
+                return Ok(0);
+        }
+
+        let mut referenced = HashSet::new();
+        for entry in std::fs::read_dir(root)?.flatten() {
+                let dir = entry.path();
+                let is_game_dir =
+                        dir.is_dir() && entry.file_name().to_string_lossy().starts_with("game_");
+                if !is_game_dir {
+                        continue;
+                }
+                for file in std::fs::read_dir(&dir)?.flatten() {
+                        let path = file.path();
+                        if path.extension().is_some_and(|ext| ext == MANIFEST_EXT) {
+                                let manifest = read_manifest(&path)?;
+                                referenced.extend(manifest.files.into_iter().map(|f| f.hash));
+                        }
+                }
+        }
+
+        let mut freed = 0;
+        for entry in WalkDir::new(&blob_root).into_iter().filter_map(|e| e.ok()) {
+                if !entry.file_type().is_file() {
+                        continue;
+                }
+                let name = entry.file_name().to_string_lossy();
+                if referenced.contains(name.as_ref()) {
+                        continue;
+                }
+                let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
+                match std::fs::remove_file(entry.path()) {
+                        | Ok(_) => freed += size,
+                        | Err(e) => warn!("删除 blob {:?} 失败: {}", entry.path(), e),
+                }
+        }
+
+        if freed > 0 {
+                info!("存档仓库回收 {:.2} KB", freed as f64 / 1024.0);
+        }
+        Ok(freed)
+}
@@ -0,0 +1,165 @@
+//! 备份完整性校验
+//!
+//! 逐个读取所有快照：ZIP 快照靠 zip 库读取到条目末尾时的 CRC 校验，
+//! 清单快照重新计算每个 blob 的 SHA-256 与清单比对。
+//! 另外对照数据库检查缺失、过期与孤立的备份。
+
+use std::{collections::HashSet, io, path::Path};
+
+use chrono::{DateTime, Local};
+
+use crate::{
+        backup::{
+                entity::{
+                        BackupIssue, BackupIssueKind, BackupSnapshot, SnapshotFormat, SnapshotKind,
+                        VerifyReport,
+                },
+                location::SaveSet,
+                snapshot, store,
+        },
+        error::AppError,
+        infra::archive::read_zip_files,
+};
+
+/// 校验备份目录
+///
+/// `games` 为数据库中的全部游戏及其存档位置，不在其中的快照目录视为孤立备份
+pub fn verify_all(
+        root: &Path,
+        games: &[(String, Option<SaveSet>)],
+) -> Result<VerifyReport, AppError> {
+        let mut report = VerifyReport {
+                checked_at: Some(Local::now()),
+                ..Default::default()
+        };
+
+        for (game_id, save_set) in games {
+                let snapshots = snapshot::list(root, game_id)?;
+                report.game_count += 1;
+
+                for snap in &snapshots {
+                        report.snapshot_count += 1;
+                        if let Err(e) = verify_snapshot(root, snap) {
+                                report.issues.push(BackupIssue {
+                                        game_id: game_id.clone(),
+                                        snapshot_id: Some(snap.id.clone()),
+                                        kind: BackupIssueKind::Corrupt,
+                                        detail: e.to_string(),
+                                });
+                        }
+                }
+
+                let Some(save_set) = save_set else {
+                        continue;
+                };
+                let latest = snapshots.iter().find(|s| s.kind == SnapshotKind::Regular);
+                match latest {
+                        | None => report.issues.push(BackupIssue {
+                                game_id: game_id.clone(),
+                                snapshot_id: None,
+                                kind: BackupIssueKind::Missing,
+                                detail: "已设置存档路径，但还没有任何备份".into(),
+                        }),
+                        | Some(latest) => {
+                                let modified = latest_mtime(save_set);
+                                if modified.is_some_and(|m| m > latest.created_at) {
+                                        report.issues.push(BackupIssue {
+                                                game_id: game_id.clone(),
+                                                snapshot_id: None,
+                                                kind: BackupIssueKind::Stale,
+                                                detail: format!(
+                                                        "最新备份 {} 之后存档又有修改",
+                                                        latest.id
+                                                ),
+                                        });
+                                }
+                        },
+                }
+        }
+
+        let known: HashSet<&str> = games.iter().map(|(id, _)| id.as_str()).collect();
+        for game_id in backed_up_games(root)? {
+                if !known.contains(game_id.as_str()) {
+                        report.issues.push(BackupIssue {
+                                detail: format!("游戏 {} 已不在库中", game_id),
+                                game_id,
+                                snapshot_id: None,
+                                kind: BackupIssueKind::Orphaned,
+                        });
+                }
+        }
+
+        Ok(report)
+}
+
+/// 完整读取一份快照，任何读取错误、CRC 或哈希不一致都视为损坏
+fn verify_snapshot(
+        root: &Path,
+        snap: &BackupSnapshot,
+) -> Result<(), AppError> {
+        let (path, _) = snapshot::locate(root, &snap.game_id, &snap.id)?;
+        match snap.format {
+                | SnapshotFormat::Archive => read_zip_files(&path, |_, reader| {
+                        io::copy(reader, &mut io::sink())?;
+                        Ok(())
+                }),
+                | SnapshotFormat::Manifest => {
+                        let manifest = store::read_manifest(&path)?;
+                        // 持锁避免校验途中 blob 被回收，误报为缺失
+                        let _guard = store::lock()?;
+                        for entry in &manifest.files {
+                                let blob = store::blob_path(root, &entry.hash);
+                                if !blob.is_file() {
+                                        return Err(AppError::Fs(format!(
+                                                "{} 的内容缺失",
+                                                entry.path
+                                        )));
+                                }
+                                if store::hash_file(&blob)? != entry.hash {
+                                        return Err(AppError::Fs(format!(
+                                                "{} 的内容校验不一致",
+                                                entry.path
+                                        )));
+                                }
+                        }
+                        Ok(())
+                },
+        }
+}
+
+/// 存档里最晚的文件修改时间
+fn latest_mtime(set: &SaveSet) -> Option<DateTime<Local>> {
+        set.files()
+                .iter()
+                .filter_map(|f| std::fs::metadata(&f.abs).ok()?.modified().ok())
+                .max()
+                .map(DateTime::<Local>::from)
+}
+
+/// 备份目录里出现过的游戏 ID（包括旧版的 `game_<id>.zip`）
+fn backed_up_games(root: &Path) -> Result<Vec<String>, AppError> {
+        if !root.is_dir() {
+                return Ok(Vec::new());
+        }
+
+        let mut ids = Vec::new();
+        for entry in std::fs::read_dir(root)?.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                let Some(rest) = name.strip_prefix("game_") else {
+                        continue;
+                };
+                let path = entry.path();
+                let id = if path.is_dir() {
+                        rest
+                } else {
+                        match rest.strip_suffix(".zip") {
+                                | Some(id) => id,
+                                | None => continue,
+                        }
+                };
+                ids.push(id.to_string());
+        }
+        ids.sort();
+        ids.dedup();
+        Ok(ids)
+}
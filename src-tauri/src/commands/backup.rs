@@ -1,30 +1,132 @@
 use sqlx::{Pool, Sqlite};
-use tauri::State;
+use tauri::{AppHandle, State};
+use tauri_plugin_notification::NotificationExt;
 
-use crate::{backup::commands as bc, error::AppError};
+use crate::{
+        backup::{
+                commands as bc,
+                entity::{
+                        BackupIssueKind, BackupRun, BackupSnapshot, RestoreReport, SaveCandidate,
+                        VerifyReport,
+                },
+        },
+        config::entity::RetentionPolicy,
+        error::AppError,
+        user::profile::active_id,
+};
 
 #[tauri::command]
 pub async fn backup_archive(pool: State<'_, Pool<Sqlite>>) -> Result<(), AppError> {
         bc::backup_all(&pool).await
 }
 
+/// `force` 为 true 时跳过与上一份快照的缩水检查（用户确认存档确实变小了）
 #[tauri::command]
 pub async fn backup_archive_by_id(
         pool: State<'_, Pool<Sqlite>>,
         id: String,
-) -> Result<(), AppError> {
-        bc::backup_by_game_id(pool.inner().clone(), id).await
+        force: Option<bool>,
+) -> Result<BackupSnapshot, AppError> {
+        bc::backup_by_game_id(pool.inner().clone(), id, force.unwrap_or(false)).await
 }
 
+/// `dry_run` 为 true 时只返回恢复会带来的改动，不写入存档目录
 #[tauri::command]
 pub async fn restore_archive_by_id(
         pool: State<'_, Pool<Sqlite>>,
         id: String,
+        dry_run: Option<bool>,
+) -> Result<RestoreReport, AppError> {
+        bc::restore_by_game_id(&pool, &id, dry_run.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn restore_all_archives(
+        pool: State<'_, Pool<Sqlite>>,
+        dry_run: Option<bool>,
+) -> Result<Vec<RestoreReport>, AppError> {
+        bc::restore_all(&pool, dry_run.unwrap_or(false)).await
+}
+
+/// 列出游戏的所有存档快照（从新到旧）
+#[tauri::command]
+pub async fn get_backup_snapshots(id: String) -> Result<Vec<BackupSnapshot>, AppError> {
+        bc::list_snapshots(id).await
+}
+
+#[tauri::command]
+pub async fn restore_archive_snapshot(
+        pool: State<'_, Pool<Sqlite>>,
+        id: String,
+        snapshot_id: String,
+        dry_run: Option<bool>,
+) -> Result<RestoreReport, AppError> {
+        bc::restore_snapshot(&pool, &id, &snapshot_id, dry_run.unwrap_or(false)).await
+}
+
+/// 获取游戏单独设置的保留策略，`None` 表示使用全局默认
+#[tauri::command]
+pub async fn get_backup_policy(
+        pool: State<'_, Pool<Sqlite>>,
+        id: String,
+) -> Result<Option<RetentionPolicy>, AppError> {
+        bc::fetch_custom_policy(&pool, &id).await
+}
+
+#[tauri::command]
+pub async fn update_backup_policy(
+        pool: State<'_, Pool<Sqlite>>,
+        id: String,
+        policy: Option<RetentionPolicy>,
 ) -> Result<(), AppError> {
-        bc::restore_by_game_id(&pool, &id).await
+        bc::save_custom_policy(&pool, &id, policy).await
 }
 
+/// 校验所有备份，完成后弹出系统通知汇总结果
 #[tauri::command]
-pub async fn restore_all_archives(pool: State<'_, Pool<Sqlite>>) -> Result<(), AppError> {
-        bc::restore_all(&pool).await
+pub async fn verify_backups(
+        app: AppHandle,
+        pool: State<'_, Pool<Sqlite>>,
+) -> Result<VerifyReport, AppError> {
+        let report = bc::verify_backups(&pool).await?;
+
+        let count = |kind: BackupIssueKind| report.issues.iter().filter(|i| i.kind == kind).count();
+        let (title, body) = if report.issues.is_empty() {
+                (
+                        "✅ 备份校验通过",
+                        format!("{} 份快照全部完好", report.snapshot_count),
+                )
+        } else {
+                (
+                        "⚠️ 备份校验发现问题",
+                        format!(
+                                "损坏 {} · 缺失 {} · 过期 {} · 孤立 {}",
+                                count(BackupIssueKind::Corrupt),
+                                count(BackupIssueKind::Missing),
+                                count(BackupIssueKind::Stale),
+                                count(BackupIssueKind::Orphaned)
+                        ),
+                )
+        };
+        let _ = app.notification().builder().title(title).body(body).show();
+
+        Ok(report)
+}
+
+/// 最近的定时备份记录，默认 20 条
+#[tauri::command]
+pub async fn get_backup_runs(
+        pool: State<'_, Pool<Sqlite>>,
+        limit: Option<u32>,
+) -> Result<Vec<BackupRun>, AppError> {
+        bc::list_runs(&pool, limit.unwrap_or(20)).await
+}
+
+/// 为游戏搜索可能的存档目录（按可信度从高到低），建议在首次游玩结束后调用
+#[tauri::command]
+pub async fn discover_save_paths(
+        pool: State<'_, Pool<Sqlite>>,
+        id: String,
+) -> Result<Vec<SaveCandidate>, AppError> {
+        bc::discover_save_paths(&pool, &active_id()?, &id).await
 }
@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Sqlite};
 use tauri::State;
 
-use crate::error::AppError;
+use crate::{error::AppError, user::profile::active_id};
 
 #[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
 pub struct Collection {
@@ -12,12 +12,14 @@ pub struct Collection {
         pub created_at: Option<String>,
 }
 
-/// 获取所有收藏夹（含每个收藏夹内的 game_id 列表）
+/// 获取当前档案的所有收藏夹
 #[tauri::command]
 pub async fn get_collections(pool: State<'_, Pool<Sqlite>>) -> Result<Vec<Collection>, AppError> {
         let collections = sqlx::query_as::<_, Collection>(
-                "SELECT id, name, description, created_at FROM collections ORDER BY created_at ASC",
+                "SELECT id, name, description, created_at FROM collections \
+         WHERE account_id = ? ORDER BY created_at ASC",
         )
+        .bind(active_id()?)
         .fetch_all(&*pool)
         .await
         .map_err(AppError::from)?;
@@ -40,7 +42,7 @@ pub async fn get_collection_game_ids(
         Ok(rows)
 }
 
-/// 创建收藏夹
+/// 在当前档案下创建收藏夹
 #[tauri::command]
 pub async fn create_collection(
         pool: State<'_, Pool<Sqlite>>,
@@ -48,13 +50,16 @@ pub async fn create_collection(
         name: String,
         description: Option<String>,
 ) -> Result<Collection, AppError> {
-        sqlx::query("INSERT INTO collections (id, name, description) VALUES (?, ?, ?)")
-                .bind(&id)
-                .bind(&name)
-                .bind(&description)
-                .execute(&*pool)
-                .await
-                .map_err(AppError::from)?;
+        sqlx::query(
+                "INSERT INTO collections (id, name, description, account_id) VALUES (?, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(&name)
+        .bind(&description)
+        .bind(active_id()?)
+        .execute(&*pool)
+        .await
+        .map_err(AppError::from)?;
 
         let col = sqlx::query_as::<_, Collection>(
                 "SELECT id, name, description, created_at FROM collections WHERE id = ?",
@@ -73,8 +78,9 @@ pub async fn delete_collection(
         pool: State<'_, Pool<Sqlite>>,
         collection_id: String,
 ) -> Result<(), AppError> {
-        sqlx::query("DELETE FROM collections WHERE id = ?")
+        sqlx::query("DELETE FROM collections WHERE id = ? AND account_id = ?")
                 .bind(&collection_id)
+                .bind(active_id()?)
                 .execute(&*pool)
                 .await
                 .map_err(AppError::from)?;
@@ -88,9 +94,10 @@ pub async fn rename_collection(
         collection_id: String,
         name: String,
 ) -> Result<(), AppError> {
-        sqlx::query("UPDATE collections SET name = ? WHERE id = ?")
+        sqlx::query("UPDATE collections SET name = ? WHERE id = ? AND account_id = ?")
                 .bind(&name)
                 .bind(&collection_id)
+                .bind(active_id()?)
                 .execute(&*pool)
                 .await
                 .map_err(AppError::from)?;
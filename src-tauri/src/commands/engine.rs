@@ -0,0 +1,44 @@
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{
+        engine::{
+                commands as ec,
+                entity::{Engine, EngineDetectReport, EngineMatch, EngineProfile},
+        },
+        error::AppError,
+};
+
+/// 所有支持识别的引擎及其默认设置
+#[tauri::command]
+pub fn get_engine_profiles() -> Vec<EngineProfile> {
+        Engine::ALL.iter().map(|e| e.profile()).collect()
+}
+
+/// 重新识别游戏的引擎，识别成功时写入游戏记录
+#[tauri::command]
+pub async fn detect_game_engine(
+        pool: State<'_, Pool<Sqlite>>,
+        game_id: String,
+) -> Result<Option<EngineMatch>, AppError> {
+        ec::detect_game(&pool, &game_id).await
+}
+
+/// 为整个游戏库识别引擎；默认跳过已记录引擎的游戏
+#[tauri::command]
+pub async fn detect_all_game_engines(
+        pool: State<'_, Pool<Sqlite>>,
+        overwrite: Option<bool>,
+) -> Result<EngineDetectReport, AppError> {
+        ec::detect_all(&pool, overwrite.unwrap_or(false)).await
+}
+
+/// 手动指定游戏引擎，`engine` 为空时清除
+#[tauri::command]
+pub async fn set_game_engine(
+        pool: State<'_, Pool<Sqlite>>,
+        game_id: String,
+        engine: Option<Engine>,
+) -> Result<(), AppError> {
+        ec::set_engine(&pool, &game_id, engine).await
+}
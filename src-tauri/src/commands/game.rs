@@ -1,28 +1,31 @@
-use sqlx::{Pool, Row, Sqlite};
-use tauri::State;
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+use sqlx::{Pool, Row, Sqlite, SqliteConnection, types::Json};
+use tauri::{State, async_runtime};
 use tauri_plugin_log::log::{info, warn};
 
 use crate::{
+        backup::store,
         config::{read_config, write_config},
+        engine::detect,
         error::AppError,
         game::{
                 commands::launch,
-                entity::{GameEvent, GameMeta, GameMetaList, PlaySession, ResourceTarget},
+                entity::{
+                        GameEvent, GameHook, GameMeta, GameMetaList, LaunchProfile, PlaySession,
+                        ResourceTarget, RunnerPreset, SessionEdit, SessionInput,
+                },
+                hook, profile, progress, runner, session_edit,
         },
+        infra::fs::detect_config_exe,
         message::{GAME_HUB, traits::MessageHub},
+        user::profile::active_id,
 };
 
 #[tauri::command]
 pub async fn get_game_meta_list(pool: State<'_, Pool<Sqlite>>) -> Result<GameMetaList, AppError> {
-        let games = sqlx::query_as(
-                "SELECT id, name, abs_path, is_passed, is_displayed, cover, background, \
-         description, developer, local_cover, local_background, save_data_path, \
-         backup_data_path, play_time, length, size, last_played_at \
-         FROM games",
-        )
-        .fetch_all(&*pool)
-        .await
-        .map_err(AppError::from)?;
+        let games = progress::list(&pool, &active_id()?).await?;
         info!("查询游戏列表成功");
         Ok(games)
 }
@@ -32,25 +35,21 @@ pub async fn get_game_meta_by_id(
         pool: State<'_, Pool<Sqlite>>,
         id: String,
 ) -> Result<GameMeta, AppError> {
-        sqlx::query_as(
-                "SELECT id, name, abs_path, is_passed, is_displayed, cover, background, \
-         description, developer, local_cover, local_background, save_data_path, \
-         backup_data_path, play_time, length, size, last_played_at \
-         FROM games WHERE id = ?",
-        )
-        .bind(id)
-        .fetch_one(&*pool)
-        .await
-        .map_err(AppError::from)
+        progress::find(&pool, &active_id()?, &id)
+                .await?
+                .ok_or_else(|| AppError::Resolve(id, "游戏不存在".into()))
 }
 
 #[tauri::command]
 pub async fn add_new_game(
         pool: State<'_, Pool<Sqlite>>,
-        game: GameMeta,
+        mut game: GameMeta,
 ) -> Result<(), AppError> {
         info!("添加新游戏: {:?}", game.name);
-        insert_game(&pool, &game).await?;
+        detect_missing(std::slice::from_mut(&mut game)).await?;
+        let mut tx = pool.begin().await.map_err(AppError::from)?;
+        insert_game(&mut tx, &active_id()?, &game).await?;
+        tx.commit().await.map_err(AppError::from)?;
         trigger_resource_download(&game, ResourceTarget::All)?;
         Ok(())
 }
@@ -58,30 +57,14 @@ pub async fn add_new_game(
 #[tauri::command]
 pub async fn add_new_game_list(
         pool: State<'_, Pool<Sqlite>>,
-        games: Vec<GameMeta>,
+        mut games: Vec<GameMeta>,
 ) -> Result<(), AppError> {
+        detect_missing(&mut games).await?;
+        let account_id = active_id()?;
         let mut tx = pool.begin().await.map_err(AppError::from)?;
 
         for game in &games {
-                sqlx::query(INSERT_GAME_SQL)
-                        .bind(&game.id)
-                        .bind(&game.name)
-                        .bind(&game.abs_path)
-                        .bind(game.is_passed)
-                        .bind(game.is_displayed)
-                        .bind(&game.cover)
-                        .bind(&game.background)
-                        .bind(&game.description)
-                        .bind(&game.developer)
-                        .bind(&game.save_data_path)
-                        .bind(&game.backup_data_path)
-                        .bind(game.play_time)
-                        .bind(game.length)
-                        .bind(game.size)
-                        .bind(game.last_played_at)
-                        .execute(&mut *tx)
-                        .await
-                        .map_err(AppError::from)?;
+                insert_game(&mut tx, &account_id, game).await?;
         }
 
         tx.commit().await.map_err(AppError::from)?;
@@ -90,7 +73,7 @@ pub async fn add_new_game_list(
         if allow {
                 for game in games {
                         GAME_HUB.publish(GameEvent::GameResourceTask {
-                                meta: game,
+                                meta: Box::new(game),
                                 target: ResourceTarget::All,
                         });
                 }
@@ -98,6 +81,7 @@ pub async fn add_new_game_list(
         Ok(())
 }
 
+/// 更新游戏信息；游玩时长与最后游玩时间由会话累计，提交的值不会写入
 #[tauri::command]
 pub async fn update_game(
         pool: State<'_, Pool<Sqlite>>,
@@ -126,17 +110,18 @@ pub async fn update_game(
                 | None => Some(ResourceTarget::All),
         };
 
+        let mut tx = pool.begin().await.map_err(AppError::from)?;
         sqlx::query(
                 "UPDATE games SET \
-         name=?, abs_path=?, is_passed=?, is_displayed=?, cover=?, background=?, \
+         name=?, abs_path=?, is_displayed=?, cover=?, background=?, \
          description=?, developer=?, \
          local_cover=COALESCE(?, local_cover), local_background=COALESCE(?, local_background), \
-         save_data_path=?, backup_data_path=?, play_time=?, length=?, size=?, last_played_at=? \
+         save_data_path=?, save_locations=?, backup_data_path=?, \
+         length=?, size=?, engine=COALESCE(?, engine), config_path=? \
          WHERE id=?",
         )
         .bind(&game.name)
         .bind(&game.abs_path)
-        .bind(game.is_passed)
         .bind(game.is_displayed)
         .bind(&game.cover)
         .bind(&game.background)
@@ -145,15 +130,18 @@ pub async fn update_game(
         .bind(&game.local_cover)
         .bind(&game.local_background)
         .bind(&game.save_data_path)
+        .bind(Json(&game.save_locations))
         .bind(&game.backup_data_path)
-        .bind(game.play_time)
         .bind(game.length)
         .bind(game.size)
-        .bind(game.last_played_at)
+        .bind(game.engine)
+        .bind(&game.config_path)
         .bind(&game.id)
-        .execute(&*pool)
+        .execute(&mut *tx)
         .await
         .map_err(AppError::from)?;
+        progress::save(&mut tx, &active_id()?, &game).await?;
+        tx.commit().await.map_err(AppError::from)?;
 
         if let Some(target) = resource_target
                 && (game.cover.starts_with("http") || game.background.starts_with("http"))
@@ -242,6 +230,12 @@ pub async fn delete_game_by_id(
                 }
         }
 
+        // 回收该游戏快照独占的存档内容
+        let backup_root = &resource_dirs[1];
+        if let Err(e) = store::lock().and_then(|_guard| store::gc(backup_root)) {
+                warn!("回收存档仓库失败: {}", e);
+        }
+
         Ok(())
 }
 
@@ -277,22 +271,86 @@ pub async fn delete_all_games(pool: State<'_, Pool<Sqlite>>) -> Result<(), AppEr
         Ok(())
 }
 
+/// 启动游戏；`profile_id` 为空时使用默认启动配置
 #[tauri::command]
 pub async fn start_game(
         pool: State<'_, Pool<Sqlite>>,
         game: GameMeta,
+        profile_id: Option<String>,
+) -> Result<(), AppError> {
+        let launch_profile = profile::resolve(&pool, &game, profile_id.as_deref()).await?;
+        launch(pool.inner().clone(), game, launch_profile).await
+}
+
+#[tauri::command]
+pub async fn get_launch_profiles(
+        pool: State<'_, Pool<Sqlite>>,
+        game_id: String,
+) -> Result<Vec<LaunchProfile>, AppError> {
+        let game = get_game_meta_by_id(pool.clone(), game_id).await?;
+        profile::list(&pool, &game).await
+}
+
+#[tauri::command]
+pub async fn save_launch_profile(
+        pool: State<'_, Pool<Sqlite>>,
+        profile: LaunchProfile,
+) -> Result<LaunchProfile, AppError> {
+        profile::save(&pool, profile).await
+}
+
+#[tauri::command]
+pub async fn delete_launch_profile(
+        pool: State<'_, Pool<Sqlite>>,
+        id: String,
+) -> Result<(), AppError> {
+        profile::delete(&pool, &id).await
+}
+
+#[tauri::command]
+pub async fn set_default_launch_profile(
+        pool: State<'_, Pool<Sqlite>>,
+        game_id: String,
+        profile_id: Option<String>,
+) -> Result<(), AppError> {
+        profile::set_default(&pool, &game_id, profile_id.as_deref()).await
+}
+
+#[tauri::command]
+pub async fn get_game_hooks(
+        pool: State<'_, Pool<Sqlite>>,
+        game_id: String,
+) -> Result<Vec<GameHook>, AppError> {
+        hook::list(&pool, &game_id).await
+}
+
+/// 整体替换游戏的启动前 / 退出后脚本
+#[tauri::command]
+pub async fn update_game_hooks(
+        pool: State<'_, Pool<Sqlite>>,
+        game_id: String,
+        hooks: Vec<GameHook>,
 ) -> Result<(), AppError> {
-        launch(pool.inner().clone(), game).await
+        hook::replace(&pool, &game_id, hooks).await
+}
+
+/// 各运行方式（直接运行、转区工具、Wine、Proton 等）的预设环境变量
+#[tauri::command]
+pub fn get_runner_presets() -> Vec<RunnerPreset> {
+        runner::presets()
 }
 
 #[tauri::command]
 pub async fn get_sessions(pool: State<'_, Pool<Sqlite>>) -> Result<Vec<PlaySession>, AppError> {
         sqlx::query_as(
-        "SELECT id, game_id, play_date, duration_minutes, last_played_at FROM game_play_sessions",
-    )
-    .fetch_all(&*pool)
-    .await
-    .map_err(AppError::from)
+                "SELECT id, game_id, play_date, duration_minutes, last_played_at, profile_id, \
+         active_seconds, idle_seconds, source FROM game_play_sessions \
+         WHERE account_id = ? AND is_open = 0",
+        )
+        .bind(active_id()?)
+        .fetch_all(&*pool)
+        .await
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
@@ -301,45 +359,153 @@ pub async fn get_sessions_by_year(
         pool: State<'_, Pool<Sqlite>>,
 ) -> Result<Vec<PlaySession>, AppError> {
         sqlx::query_as(
-                "SELECT id, game_id, play_date, duration_minutes, last_played_at \
-         FROM game_play_sessions WHERE strftime('%Y', play_date) = ?",
+                "SELECT id, game_id, play_date, duration_minutes, last_played_at, profile_id, \
+         active_seconds, idle_seconds, source FROM game_play_sessions \
+         WHERE account_id = ? AND is_open = 0 AND strftime('%Y', play_date) = ?",
         )
+        .bind(active_id()?)
         .bind(year)
         .fetch_all(&*pool)
         .await
         .map_err(AppError::from)
 }
 
+/// 手动录入一次会话（如在其他设备上游玩）
+#[tauri::command]
+pub async fn create_session(
+        pool: State<'_, Pool<Sqlite>>,
+        session: SessionInput,
+) -> Result<PlaySession, AppError> {
+        session_edit::create(&pool, &active_id()?, session).await
+}
+
+#[tauri::command]
+pub async fn update_session(
+        pool: State<'_, Pool<Sqlite>>,
+        id: String,
+        session: SessionInput,
+) -> Result<PlaySession, AppError> {
+        session_edit::update(&pool, &active_id()?, &id, session).await
+}
+
+/// 在 `at` 处把一次会话拆成两次
+#[tauri::command]
+pub async fn split_session(
+        pool: State<'_, Pool<Sqlite>>,
+        id: String,
+        at: DateTime<Local>,
+) -> Result<Vec<PlaySession>, AppError> {
+        session_edit::split(&pool, &active_id()?, &id, at).await
+}
+
+/// 合并同一游戏的多次会话
+#[tauri::command]
+pub async fn merge_sessions(
+        pool: State<'_, Pool<Sqlite>>,
+        ids: Vec<String>,
+) -> Result<PlaySession, AppError> {
+        session_edit::merge(&pool, &active_id()?, &ids).await
+}
+
+#[tauri::command]
+pub async fn delete_session(
+        pool: State<'_, Pool<Sqlite>>,
+        id: String,
+) -> Result<(), AppError> {
+        session_edit::delete(&pool, &active_id()?, &id).await
+}
+
+/// 会话的手动修改记录；不指定游戏时返回全部
+#[tauri::command]
+pub async fn get_session_edits(
+        pool: State<'_, Pool<Sqlite>>,
+        game_id: Option<String>,
+) -> Result<Vec<SessionEdit>, AppError> {
+        session_edit::list_edits(&pool, &active_id()?, game_id.as_deref()).await
+}
+
 // ── 内部复用 ──────────────────────────────────────────────────────────────────
 
-const INSERT_GAME_SQL: &str = "INSERT OR REPLACE INTO games \
-     (id, name, abs_path, is_passed, is_displayed, cover, background, description, \
-      developer, save_data_path, backup_data_path, play_time, length, size, last_played_at) \
-     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)";
+/// 游戏已存在时只更新元数据列；不用 INSERT OR REPLACE，它会先删除旧记录，
+/// 级联删掉各档案的进度、游玩记录、启动配置与钩子
+const INSERT_GAME_SQL: &str = "INSERT INTO games \
+     (id, name, abs_path, is_displayed, cover, background, description, \
+      developer, save_data_path, save_locations, backup_data_path, length, size, \
+      engine, config_path) \
+     VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+     ON CONFLICT(id) DO UPDATE SET name=excluded.name, abs_path=excluded.abs_path, \
+     is_displayed=excluded.is_displayed, cover=excluded.cover, background=excluded.background, \
+     description=excluded.description, developer=excluded.developer, \
+     save_data_path=excluded.save_data_path, save_locations=excluded.save_locations, \
+     backup_data_path=excluded.backup_data_path, length=excluded.length, size=excluded.size, \
+     engine=excluded.engine, config_path=excluded.config_path";
 
+/// 写入游戏；游戏信息中带有的通关标记记为当前档案的进度
 async fn insert_game(
-        pool: &Pool<Sqlite>,
+        tx: &mut SqliteConnection,
+        account_id: &str,
         game: &GameMeta,
 ) -> Result<(), AppError> {
         sqlx::query(INSERT_GAME_SQL)
                 .bind(&game.id)
                 .bind(&game.name)
                 .bind(&game.abs_path)
-                .bind(game.is_passed)
                 .bind(game.is_displayed)
                 .bind(&game.cover)
                 .bind(&game.background)
                 .bind(&game.description)
                 .bind(&game.developer)
                 .bind(&game.save_data_path)
+                .bind(Json(&game.save_locations))
                 .bind(&game.backup_data_path)
-                .bind(game.play_time)
                 .bind(game.length)
                 .bind(game.size)
-                .bind(game.last_played_at)
-                .execute(pool)
+                .bind(game.engine)
+                .bind(&game.config_path)
+                .execute(&mut *tx)
                 .await
                 .map_err(AppError::from)?;
+        if game.is_passed {
+                progress::save(tx, account_id, game).await?;
+        }
+        Ok(())
+}
+
+/// 为没有指定引擎、设置程序的游戏按游戏目录自动识别
+async fn detect_missing(games: &mut [GameMeta]) -> Result<(), AppError> {
+        let targets: Vec<(String, bool, bool)> = games
+                .iter()
+                .map(|g| {
+                        (
+                                g.abs_path.clone(),
+                                g.engine.is_none(),
+                                g.config_path.is_none(),
+                        )
+                })
+                .collect();
+        let detected = async_runtime::spawn_blocking(move || {
+                targets.into_iter()
+                        .map(|(abs_path, engine, config)| {
+                                let dir = Path::new(&abs_path)
+                                        .parent()
+                                        .map(|p| p.to_string_lossy().into_owned())
+                                        .unwrap_or_default();
+                                (
+                                        engine.then(|| detect::detect_for_exe(&abs_path))
+                                                .flatten()
+                                                .map(|m| m.engine),
+                                        config.then(|| detect_config_exe(&dir)).flatten(),
+                                )
+                        })
+                        .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|e| AppError::Fs(e.to_string()))?;
+
+        for (game, (engine, config_path)) in games.iter_mut().zip(detected) {
+                game.engine = game.engine.or(engine);
+                game.config_path = game.config_path.take().or(config_path);
+        }
         Ok(())
 }
 
@@ -350,7 +516,7 @@ fn trigger_resource_download(
         let allow = read_config()?.storage.allow_downloading_resources;
         if allow {
                 GAME_HUB.publish(GameEvent::GameResourceTask {
-                        meta: game.clone(),
+                        meta: Box::new(game.clone()),
                         target,
                 });
         }
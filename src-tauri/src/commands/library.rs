@@ -0,0 +1,93 @@
+use std::path::{Path, PathBuf};
+
+use sqlx::{Pool, Sqlite};
+use tauri::{AppHandle, State};
+
+use crate::{
+        error::AppError,
+        library::{
+                commands as lc,
+                entity::{
+                        ExportOptions, ExportReport, HealthFixReport, HealthReport, ImportMode,
+                        ImportOptions, ImportReport, RelocationReport,
+                },
+                health, relocate,
+        },
+        sys,
+};
+
+/// 导出整个游戏库；图片与存档备份默认不打包
+#[tauri::command]
+pub async fn export_library(
+        pool: State<'_, Pool<Sqlite>>,
+        path: String,
+        include_images: Option<bool>,
+        include_backups: Option<bool>,
+) -> Result<ExportReport, AppError> {
+        let options = ExportOptions {
+                include_images: include_images.unwrap_or(false),
+                include_backups: include_backups.unwrap_or(false),
+        };
+        lc::export_library(&pool, PathBuf::from(path), options).await
+}
+
+/// 导入游戏库；`gal_root_dir` 为空时按当前配置的游戏根目录重映射路径，
+/// `dry_run` 为 true 时只返回导入报告
+#[tauri::command]
+pub async fn import_library(
+        pool: State<'_, Pool<Sqlite>>,
+        path: String,
+        gal_root_dir: Option<String>,
+        mode: Option<ImportMode>,
+        include_config: Option<bool>,
+        dry_run: Option<bool>,
+) -> Result<ImportReport, AppError> {
+        let options = ImportOptions {
+                gal_root_dir,
+                mode: mode.unwrap_or_default(),
+                include_config: include_config.unwrap_or(false),
+                dry_run: dry_run.unwrap_or(false),
+        };
+        lc::import_library(&pool, PathBuf::from(path), options).await
+}
+
+/// 把游戏路径从旧前缀迁移到新前缀；`dry_run` 为 true 时只预览，
+/// 新路径有缺失时不写入，除非 `force` 为 true
+#[tauri::command]
+pub async fn relocate_library(
+        app_handle: AppHandle,
+        pool: State<'_, Pool<Sqlite>>,
+        old_prefix: String,
+        new_prefix: String,
+        dry_run: Option<bool>,
+        force: Option<bool>,
+) -> Result<RelocationReport, AppError> {
+        let report = relocate::relocate(
+                &pool,
+                &old_prefix,
+                &new_prefix,
+                dry_run.unwrap_or(false),
+                force.unwrap_or(false),
+        )
+        .await?;
+        for scope in &report.scopes {
+                sys::allow_scope(&app_handle, Path::new(scope));
+        }
+        Ok(report)
+}
+
+/// 检查游戏库中失效的路径、图片和授权
+#[tauri::command]
+pub async fn check_library_health(pool: State<'_, Pool<Sqlite>>) -> Result<HealthReport, AppError> {
+        health::check(&pool).await
+}
+
+/// 批量执行体检给出的安全修复；`game_ids` 为空时处理全部游戏
+#[tauri::command]
+pub async fn fix_library_health(
+        app_handle: AppHandle,
+        pool: State<'_, Pool<Sqlite>>,
+        game_ids: Option<Vec<String>>,
+) -> Result<HealthFixReport, AppError> {
+        health::apply_safe_fixes(&app_handle, &pool, game_ids).await
+}
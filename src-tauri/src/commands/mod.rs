@@ -7,9 +7,13 @@ pub mod backup;
 pub mod collection;
 pub mod companion;
 pub mod config;
+pub mod engine;
 pub mod game;
+pub mod library;
+pub mod scanner;
 pub mod screenshot;
 pub mod shortcut;
+pub mod stats;
 pub mod system;
 pub mod user;
 
@@ -18,8 +22,12 @@ pub use backup::*;
 pub use collection::*;
 pub use companion::*;
 pub use config::*;
+pub use engine::*;
 pub use game::*;
+pub use library::*;
+pub use scanner::*;
 pub use screenshot::*;
 pub use shortcut::*;
+pub use stats::*;
 pub use system::*;
 pub use user::*;
@@ -0,0 +1,16 @@
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{
+        error::AppError,
+        scanner::{commands as sc, entity::ScanReport},
+};
+
+/// 扫描游戏根目录中尚未导入的游戏；`depth` 为空时使用配置中的扫描层数
+#[tauri::command]
+pub async fn scan_game_library(
+        pool: State<'_, Pool<Sqlite>>,
+        depth: Option<u32>,
+) -> Result<ScanReport, AppError> {
+        sc::scan_library(&pool, depth).await
+}
@@ -4,16 +4,20 @@ use tauri::State;
 use crate::{
         error::AppError,
         shortcut::{commands::refresh_shortcuts, entity::ShortcutSetting},
+        user::profile::active_id,
 };
 
 #[tauri::command]
 pub async fn get_shortcuts(
         pool: State<'_, Pool<Sqlite>>
 ) -> Result<Vec<ShortcutSetting>, AppError> {
-        sqlx::query_as::<_, ShortcutSetting>("SELECT id, key_combo, is_global FROM shortcut")
-                .fetch_all(pool.inner())
-                .await
-                .map_err(AppError::from)
+        sqlx::query_as::<_, ShortcutSetting>(
+                "SELECT id, key_combo, is_global FROM shortcut WHERE account_id = ?",
+        )
+        .bind(active_id()?)
+        .fetch_all(pool.inner())
+        .await
+        .map_err(AppError::from)
 }
 
 #[tauri::command]
@@ -22,12 +26,14 @@ pub async fn update_shortcuts(
         pool: State<'_, SqlitePool>,
         shortcuts: Vec<ShortcutSetting>,
 ) -> Result<(), AppError> {
+        let account_id = active_id()?;
         let mut tx = pool.begin().await.map_err(AppError::from)?;
 
         for s in shortcuts {
-                sqlx::query("UPDATE shortcut SET key_combo = ? WHERE id = ?")
+                sqlx::query("UPDATE shortcut SET key_combo = ? WHERE id = ? AND account_id = ?")
                         .bind(&s.key_combo)
                         .bind(&s.id)
+                        .bind(&account_id)
                         .execute(&mut *tx)
                         .await
                         .map_err(AppError::from)?;
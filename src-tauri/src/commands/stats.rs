@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+
+use sqlx::SqlitePool;
+use tauri::State;
+
+use crate::{
+        error::AppError,
+        stats::{
+                commands as stc,
+                entity::{
+                        DateRange, DeveloperCompletion, GameTotal, Granularity, PeriodTotal,
+                        PlayHeatmap, PlayRecords, YearReview, YearReviewExport,
+                },
+                review,
+        },
+        user::profile::active_id,
+};
+
+/// 按日 / 周 / 月汇总范围内的游玩时长
+#[tauri::command]
+pub async fn get_play_totals(
+        pool: State<'_, SqlitePool>,
+        range: DateRange,
+        granularity: Granularity,
+) -> Result<Vec<PeriodTotal>, AppError> {
+        stc::period_totals(&pool, &active_id()?, range, granularity).await
+}
+
+/// 各游戏在范围内的游玩时长
+#[tauri::command]
+pub async fn get_game_play_totals(
+        pool: State<'_, SqlitePool>,
+        range: DateRange,
+) -> Result<Vec<GameTotal>, AppError> {
+        stc::game_totals(&pool, &active_id()?, range).await
+}
+
+/// 最长会话与连续游玩天数
+#[tauri::command]
+pub async fn get_play_records(pool: State<'_, SqlitePool>) -> Result<PlayRecords, AppError> {
+        stc::records(&pool, &active_id()?).await
+}
+
+/// 按星期与小时的游玩热力图
+#[tauri::command]
+pub async fn get_play_heatmap(
+        pool: State<'_, SqlitePool>,
+        range: DateRange,
+) -> Result<PlayHeatmap, AppError> {
+        stc::heatmap(&pool, &active_id()?, range).await
+}
+
+/// 各开发商的通关率
+#[tauri::command]
+pub async fn get_developer_completion(
+        pool: State<'_, SqlitePool>
+) -> Result<Vec<DeveloperCompletion>, AppError> {
+        stc::developer_completion(&pool, &active_id()?).await
+}
+
+/// 某一年的年度回顾
+#[tauri::command]
+pub async fn get_year_review(
+        pool: State<'_, SqlitePool>,
+        year: i32,
+) -> Result<YearReview, AppError> {
+        review::year_review(&pool, &active_id()?, year).await
+}
+
+/// 把年度回顾导出为 JSON 与 HTML 文件，写入 `dir`
+#[tauri::command]
+pub async fn export_year_review(
+        pool: State<'_, SqlitePool>,
+        year: i32,
+        dir: PathBuf,
+) -> Result<YearReviewExport, AppError> {
+        review::export(&pool, &active_id()?, year, &dir).await
+}
@@ -12,7 +12,10 @@ use uuid::Uuid;
 use crate::{
         config::read_config,
         error::AppError,
-        infra::fs::{detect_game_exe, dir_size},
+        infra::{
+                exe::{self, ExeCandidate},
+                fs::{detect_game_exe, dir_size},
+        },
         theme::ThemeState,
 };
 
@@ -29,6 +32,12 @@ pub fn get_start_up_path(parent_path: String) -> Result<String, AppError> {
         detect_game_exe(&parent_path)
 }
 
+/// 列出游戏目录中可能的启动程序，按得分从高到低排列并附带理由与用途
+#[tauri::command]
+pub fn rank_start_up_paths(parent_path: String) -> Result<Vec<ExeCandidate>, AppError> {
+        exe::rank(Path::new(&parent_path))
+}
+
 /// 判断拖入路径是文件还是目录
 #[tauri::command]
 pub fn get_path_kinds(paths: Vec<String>) -> Vec<PathKind> {
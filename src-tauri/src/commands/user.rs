@@ -1,5 +1,5 @@
 use sqlx::{Pool, Sqlite};
-use tauri::State;
+use tauri::{AppHandle, State};
 use tauri_plugin_log::log::info;
 
 use crate::{
@@ -7,38 +7,46 @@ use crate::{
         error::AppError,
         game::entity::GameEvent,
         message::{GAME_HUB, traits::MessageHub},
-        user::entity::User,
+        user::{self, entity::User, profile},
 };
 
 #[tauri::command]
 pub async fn get_user_info(pool: State<'_, Pool<Sqlite>>) -> Result<User, AppError> {
-        let user = sqlx::query_as::<_, User>("SELECT * FROM account LIMIT 1")
-                .fetch_optional(&*pool)
-                .await
-                .map_err(AppError::from)?;
-        Ok(user.unwrap_or_default())
+        user::commands::load(&pool, &profile::active_id()?).await
 }
 
+/// 保存用户信息；统计项只保存 `overrides` 中的覆盖值
 #[tauri::command]
 pub async fn update_user_info(
         pool: State<'_, Pool<Sqlite>>,
         account: User,
 ) -> Result<(), AppError> {
         let is_network = account.avatar.starts_with("http");
+        let overrides = &account.overrides;
 
         sqlx::query(
-                "INSERT OR REPLACE INTO account \
+                "INSERT INTO account \
          (id, user_name, avatar, favorite_game, games_count, total_play_time, \
           games_completed_number, selected_disk, last_play_at, created_at) \
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET user_name=excluded.user_name, avatar=excluded.avatar, \
+         favorite_game=excluded.favorite_game, games_count=excluded.games_count, \
+         total_play_time=excluded.total_play_time, \
+         games_completed_number=excluded.games_completed_number, \
+         selected_disk=excluded.selected_disk, last_play_at=excluded.last_play_at, \
+         created_at=excluded.created_at",
         )
         .bind(&account.id)
         .bind(&account.user_name)
         .bind(&account.avatar)
-        .bind(&account.favorite_game)
-        .bind(account.games_count)
-        .bind(account.total_play_time)
-        .bind(account.games_completed_number)
+        .bind(overrides
+                .favorite_game
+                .as_deref()
+                .map(str::trim)
+                .filter(|name| !name.is_empty()))
+        .bind(overrides.games_count)
+        .bind(overrides.total_play_time)
+        .bind(overrides.games_completed_number)
         .bind(&account.selected_disk)
         .bind(account.last_play_at)
         .bind(account.created_at)
@@ -54,8 +62,43 @@ pub async fn update_user_info(
 
         if is_network && allow {
                 info!("触发用户头像下载任务");
-                GAME_HUB.publish(GameEvent::UserResourceTask { meta: account });
+                GAME_HUB.publish(GameEvent::UserResourceTask {
+                        meta: Box::new(account),
+                });
         }
 
         Ok(())
 }
+
+/// 全部用户档案
+#[tauri::command]
+pub async fn get_user_profiles(pool: State<'_, Pool<Sqlite>>) -> Result<Vec<User>, AppError> {
+        profile::list(&pool).await
+}
+
+/// 新建用户档案（不会切换过去）
+#[tauri::command]
+pub async fn create_user_profile(
+        pool: State<'_, Pool<Sqlite>>,
+        user_name: String,
+) -> Result<User, AppError> {
+        profile::create(&pool, &user_name).await
+}
+
+#[tauri::command]
+pub async fn delete_user_profile(
+        pool: State<'_, Pool<Sqlite>>,
+        id: String,
+) -> Result<(), AppError> {
+        profile::delete(&pool, &id).await
+}
+
+/// 切换当前用户档案，无需重启
+#[tauri::command]
+pub async fn switch_user_profile(
+        app_handle: AppHandle,
+        pool: State<'_, Pool<Sqlite>>,
+        id: String,
+) -> Result<User, AppError> {
+        profile::switch(&app_handle, &pool, &id).await
+}
@@ -3,6 +3,7 @@
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
 use tokio::sync::broadcast;
 
 use crate::message::traits::{MessageEvent, MessageHub};
@@ -126,6 +127,22 @@ pub struct System {
         /// 是否将日志持久化写入文件
         #[serde(default)]
         pub persist_log: bool,
+        /// 计入游玩时长的时间
+        #[serde(default)]
+        pub play_time_mode: PlayTimeMode,
+        /// 超过这么久没有键鼠输入、或游戏窗口不在前台，视为离开
+        #[serde(default = "default_idle_threshold_secs")]
+        pub idle_threshold_secs: u64,
+        /// 「喜爱的游戏」的选择方式
+        #[serde(default)]
+        pub favorite_game_rule: FavoriteGameRule,
+        /// 在后台识别从本程序之外（如桌面快捷方式）启动的游戏，并记录会话
+        #[serde(default)]
+        pub detect_external_launch: bool,
+}
+
+fn default_idle_threshold_secs() -> u64 {
+        300
 }
 
 impl Default for System {
@@ -137,10 +154,49 @@ impl Default for System {
                         log_level: LogLevel::Info,
                         download_concurrency: 5,
                         persist_log: false,
+                        play_time_mode: PlayTimeMode::default(),
+                        idle_threshold_secs: default_idle_threshold_secs(),
+                        favorite_game_rule: FavoriteGameRule::default(),
+                        detect_external_launch: false,
                 }
         }
 }
 
+/// 游玩时长的计算方式
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum PlayTimeMode {
+        /// 只计有操作且游戏在前台的时间，需要在设置中开启
+        Active,
+        /// 从启动到退出的全部时间，与旧版本的统计方式一致
+        #[default]
+        Total,
+}
+
+/// 「喜爱的游戏」的选择方式；最近若干天内没有记录时退回累计时长最多的游戏
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(
+        tag = "kind",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+)]
+pub enum FavoriteGameRule {
+        /// 最近若干天内游玩时长最多
+        RecentMostPlayed { days: u32 },
+        /// 最近若干天内游玩次数最多
+        RecentMostSessions { days: u32 },
+        /// 累计游玩时长最多
+        MostPlayed,
+        /// 最后游玩
+        LastPlayed,
+}
+
+impl Default for FavoriteGameRule {
+        fn default() -> Self {
+                Self::RecentMostPlayed { days: 90 }
+        }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum CloseBehavior {
         Exit,
@@ -167,6 +223,26 @@ pub struct Storage {
         pub gal_root_dir: PathBuf,
         pub allow_downloading_resources: bool,
         pub auto_backup: bool,
+        /// 存档快照的默认保留策略（可被单个游戏覆盖）
+        #[serde(default)]
+        pub backup_retention: RetentionPolicy,
+        /// 后台定时备份计划，与游玩结束时的自动备份互不影响
+        #[serde(default)]
+        pub backup_schedule: BackupSchedule,
+        /// 扫描游戏根目录时向下查找游戏文件夹的最大层数
+        #[serde(default = "default_scan_depth")]
+        pub scan_depth: u32,
+        /// 在后台监听游戏根目录，发现新增、删除、改名的游戏文件夹
+        #[serde(default = "default_watch_gal_root")]
+        pub watch_gal_root: bool,
+}
+
+fn default_scan_depth() -> u32 {
+        3
+}
+
+fn default_watch_gal_root() -> bool {
+        true
 }
 
 impl Default for Storage {
@@ -178,10 +254,51 @@ impl Default for Storage {
                         gal_root_dir: PathBuf::new(),
                         allow_downloading_resources: true,
                         auto_backup: false,
+                        backup_retention: RetentionPolicy::default(),
+                        backup_schedule: BackupSchedule::default(),
+                        scan_depth: default_scan_depth(),
+                        watch_gal_root: default_watch_gal_root(),
+                }
+        }
+}
+
+/// 存档快照保留策略
+///
+/// 三条规则取并集：命中任意一条的快照都会被保留，其余在新快照写入后清理
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+        /// 无条件保留最近 N 份
+        pub keep_last: u32,
+        /// 最近 N 个有备份的日子，每天保留最新一份
+        pub keep_daily: u32,
+        /// 最近 N 个有备份的周，每周保留最新一份
+        pub keep_weekly: u32,
+}
+
+impl Default for RetentionPolicy {
+        fn default() -> Self {
+                Self {
+                        keep_last: 5,
+                        keep_daily: 7,
+                        keep_weekly: 4,
                 }
         }
 }
 
+/// 后台定时备份计划
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum BackupSchedule {
+        /// 不做定时备份
+        #[default]
+        Off,
+        /// 距上次定时备份满 N 分钟后执行
+        Interval { minutes: u32 },
+        /// 五段式 cron 表达式：`分 时 日 月 周`，如 `30 3 * * *` 表示每天 03:30
+        Cron { expr: String },
+}
+
 // ── 权限设置 ──────────────────────────────────────────────────────────────────
 
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, PartialOrd)]
@@ -72,13 +72,16 @@ pub fn load(app_handle: AppHandle) -> Result<(), AppError> {
 pub fn save(app_handle: &AppHandle) -> Result<(), AppError> {
         info!("持久化配置到磁盘...");
 
-        // 退出前更新最后游玩的游戏到展示顺序第一位
+        // 退出前更新当前档案最后游玩的游戏到展示顺序第一位
         if let Some(pool) = app_handle.try_state::<SqlitePool>() {
+                let account_id = crate::user::profile::active_id()?;
                 let latest: Option<String> = tauri::async_runtime::block_on(async {
                         sqlx::query_scalar(
-                                "SELECT id FROM games WHERE last_played_at IS NOT NULL \
+                                "SELECT game_id FROM game_progress \
+                 WHERE account_id = ? AND last_played_at IS NOT NULL \
                  ORDER BY last_played_at DESC LIMIT 1",
                         )
+                        .bind(&account_id)
                         .fetch_optional(pool.inner())
                         .await
                         .ok()
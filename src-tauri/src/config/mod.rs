@@ -12,7 +12,11 @@ use std::{
 use lazy_static::lazy_static;
 use tauri::{AppHandle, Manager};
 
-use crate::error::AppError;
+use crate::{
+        config::entity::ConfigEvent,
+        error::AppError,
+        message::{CONFIG_HUB, traits::MessageHub},
+};
 
 pub mod entity;
 pub mod fs;
@@ -40,6 +44,33 @@ pub fn write_config() -> Result<RwLockWriteGuard<'static, Config>, AppError> {
                 .map_err(|e| AppError::Lock(e.to_string()))
 }
 
+/// 与当前配置比较，只对发生变化的子配置发布事件，避免无意义的副作用
+pub fn publish_changes(config: Config) -> Result<(), AppError> {
+        let old = read_config()?.clone();
+
+        if old.basic != config.basic {
+                CONFIG_HUB.publish(ConfigEvent::Basic { base: config.basic });
+        }
+        if old.interface != config.interface {
+                CONFIG_HUB.publish(ConfigEvent::Interface {
+                        interface: config.interface,
+                });
+        }
+        if old.storage != config.storage {
+                CONFIG_HUB.publish(ConfigEvent::Storage {
+                        storage: config.storage,
+                });
+        }
+        if old.system != config.system {
+                CONFIG_HUB.publish(ConfigEvent::System { sys: config.system });
+        }
+        if old.auth != config.auth {
+                CONFIG_HUB.publish(ConfigEvent::Authorization { auth: config.auth });
+        }
+
+        Ok(())
+}
+
 /// config 模块入口，在 life_cycle::init 中调用
 pub fn init(app_handle: &AppHandle) -> Result<(), Box<dyn Error>> {
         // 设置路径
@@ -106,6 +106,15 @@ pub fn start_listener(app: AppHandle) {
                                                 |c| c.system.log_level = sys.log_level.clone()
                                         );
                                         write_config!(|c| c.system.persist_log = sys.persist_log);
+                                        write_config!(
+                                                |c| c.system.play_time_mode = sys.play_time_mode
+                                        );
+                                        write_config!(|c| c.system.idle_threshold_secs =
+                                                sys.idle_threshold_secs);
+                                        write_config!(|c| c.system.favorite_game_rule =
+                                                sys.favorite_game_rule);
+                                        write_config!(|c| c.system.detect_external_launch =
+                                                sys.detect_external_launch);
                                         if level_changed || persist_changed {
                                                 apply_log_level(
                                                         &app,
@@ -156,6 +165,13 @@ pub fn start_listener(app: AppHandle) {
                                         write_config!(
                                                 |c| c.storage.auto_backup = storage.auto_backup
                                         );
+                                        write_config!(|c| c.storage.backup_retention =
+                                                storage.backup_retention);
+                                        write_config!(|c| c.storage.backup_schedule =
+                                                storage.backup_schedule);
+                                        write_config!(|c| c.storage.scan_depth = storage.scan_depth);
+                                        write_config!(|c| c.storage.watch_gal_root =
+                                                storage.watch_gal_root);
                                 },
                                 | ConfigEvent::Authorization { auth } => {
                                         write_config!(|c| c.auth.bangumi_token = auth.bangumi_token);
@@ -0,0 +1,95 @@
+use sqlx::SqlitePool;
+use tauri::async_runtime;
+use tauri_plugin_log::log::info;
+
+use crate::{
+        engine::{
+                detect,
+                entity::{DetectedEngine, Engine, EngineDetectReport, EngineMatch},
+        },
+        error::AppError,
+};
+
+/// 重新识别一个游戏的引擎并写入记录；未能识别时保留原有记录
+pub async fn detect_game(
+        pool: &SqlitePool,
+        game_id: &str,
+) -> Result<Option<EngineMatch>, AppError> {
+        let abs_path: String = sqlx::query_scalar("SELECT abs_path FROM games WHERE id = ?")
+                .bind(game_id)
+                .fetch_one(pool)
+                .await?;
+        let detected = async_runtime::spawn_blocking(move || detect::detect_for_exe(&abs_path))
+                .await
+                .map_err(|e| AppError::Fs(e.to_string()))?;
+
+        if let Some(m) = &detected {
+                set_engine(pool, game_id, Some(m.engine)).await?;
+        }
+        Ok(detected)
+}
+
+/// 批量识别引擎；`overwrite` 为 false 时跳过已记录引擎的游戏
+pub async fn detect_all(
+        pool: &SqlitePool,
+        overwrite: bool,
+) -> Result<EngineDetectReport, AppError> {
+        let rows: Vec<(String, String, String, Option<Engine>)> =
+                sqlx::query_as("SELECT id, name, abs_path, engine FROM games")
+                        .fetch_all(pool)
+                        .await?;
+        let skipped = rows.iter().filter(|r| r.3.is_some() && !overwrite).count();
+        let targets: Vec<(String, String, String, Option<Engine>)> = rows
+                .into_iter()
+                .filter(|r| r.3.is_none() || overwrite)
+                .collect();
+
+        let detected: Vec<(String, String, Option<Engine>, Option<EngineMatch>)> =
+                async_runtime::spawn_blocking(move || {
+                        targets.into_iter()
+                                .map(|(id, name, abs_path, old)| {
+                                        let m = detect::detect_for_exe(&abs_path);
+                                        (id, name, old, m)
+                                })
+                                .collect()
+                })
+                .await
+                .map_err(|e| AppError::Fs(e.to_string()))?;
+
+        let mut games = Vec::with_capacity(detected.len());
+        for (game_id, name, old, detected) in detected {
+                let new = detected.as_ref().map(|m| m.engine);
+                let updated = new.is_some() && new != old;
+                if updated {
+                        set_engine(pool, &game_id, new).await?;
+                }
+                games.push(DetectedEngine {
+                        game_id,
+                        name,
+                        detected,
+                        updated,
+                });
+        }
+
+        info!(
+                "识别游戏引擎：检查 {} 个 · 更新 {} 个 · 跳过 {} 个",
+                games.len(),
+                games.iter().filter(|g| g.updated).count(),
+                skipped
+        );
+        Ok(EngineDetectReport { games, skipped })
+}
+
+/// 手动设置游戏引擎，`None` 表示清除
+pub async fn set_engine(
+        pool: &SqlitePool,
+        game_id: &str,
+        engine: Option<Engine>,
+) -> Result<(), AppError> {
+        sqlx::query("UPDATE games SET engine = ? WHERE id = ?")
+                .bind(engine)
+                .bind(game_id)
+                .execute(pool)
+                .await?;
+        Ok(())
+}
@@ -0,0 +1,352 @@
+//! 按目录内容识别游戏引擎
+//!
+//! 只看游戏目录本身和少数固定位置的子目录，不递归。
+//! 每个引擎有若干条特征规则，按 [`RULES`] 的顺序匹配：特征越独特的引擎越靠前，
+//! Unity 运行库、`.xp3` 数据包这类可能被其他引擎借用的特征放在最后兜底。
+
+use std::path::{Path, PathBuf};
+
+use crate::engine::entity::{Engine, EngineMatch};
+
+/// 目录的直接内容，名称均为小写
+struct Listing {
+        dir: PathBuf,
+        files: Vec<String>,
+        dirs: Vec<(String, PathBuf)>,
+}
+
+impl Listing {
+        fn read(dir: &Path) -> Option<Self> {
+                let mut files = Vec::new();
+                let mut dirs = Vec::new();
+                for entry in std::fs::read_dir(dir).ok()?.flatten() {
+                        let name = entry.file_name().to_string_lossy().to_lowercase();
+                        match entry.file_type() {
+                                | Ok(t) if t.is_dir() => dirs.push((name, entry.path())),
+                                | Ok(_) => files.push(name),
+                                | Err(_) => {},
+                        }
+                }
+                files.sort();
+                dirs.sort();
+                Some(Self {
+                        dir: dir.to_path_buf(),
+                        files,
+                        dirs,
+                })
+        }
+
+        fn file(
+                &self,
+                name: &str,
+        ) -> Option<String> {
+                self.files.iter().find(|f| *f == name).cloned()
+        }
+
+        fn file_where(
+                &self,
+                pred: impl Fn(&str) -> bool,
+        ) -> Option<String> {
+                self.files.iter().find(|f| pred(f)).cloned()
+        }
+
+        fn ext(
+                &self,
+                ext: &str,
+        ) -> Option<String> {
+                self.file_where(|f| f.rsplit_once('.').is_some_and(|(_, e)| e == ext))
+        }
+
+        fn subdir(
+                &self,
+                name: &str,
+        ) -> Option<&Path> {
+                self.dirs
+                        .iter()
+                        .find(|(n, _)| n == name)
+                        .map(|(_, p)| p.as_path())
+        }
+
+        /// 相对路径的文件是否存在（大小写按实际文件系统）
+        fn has(
+                &self,
+                rel: &str,
+        ) -> bool {
+                self.dir.join(rel).is_file()
+        }
+}
+
+type Rule = fn(&Listing) -> Option<String>;
+
+/// 按顺序匹配的引擎特征
+const RULES: &[(Engine, &[Rule])] = &[
+        (
+                Engine::Siglus,
+                &[
+                        |l| l.file("scene.pck").map(|_| "脚本包 Scene.pck".into()),
+                        |l| l.file("gameexe.dat").map(|_| "配置 Gameexe.dat".into()),
+                        |l| {
+                                l.file_where(|f| {
+                                        f.starts_with("siglusengine") && f.ends_with(".exe")
+                                })
+                                .map(|f| format!("启动程序 {}", f))
+                        },
+                ],
+        ),
+        (
+                Engine::Bgi,
+                &[
+                        |l| l.file("bgi.exe").map(|_| "启动程序 BGI.exe".into()),
+                        |l| l.file("bgi.gdb").map(|_| "配置 BGI.gdb".into()),
+                        |l| l.file("sysprg.arc").map(|_| "系统数据包 sysprg.arc".into()),
+                ],
+        ),
+        (
+                Engine::CatSystem2,
+                &[
+                        |l| l.file("cs2conf.dll").map(|_| "配置程序 cs2conf.dll".into()),
+                        |l| l.file("scene.int").map(|_| "脚本包 scene.int".into()),
+                ],
+        ),
+        (
+                Engine::YuRis,
+                &[
+                        |l| l.ext("ypf").map(|f| format!("数据包 {}", f)),
+                        |l| {
+                                l.subdir("pac")
+                                        .and_then(Listing::read)
+                                        .and_then(|pac| pac.ext("ypf"))
+                                        .map(|f| format!("数据包 pac/{}", f))
+                        },
+                ],
+        ),
+        (
+                Engine::Artemis,
+                &[|l| {
+                        l.file_where(|f| f.ends_with(".pfs") || f.contains(".pfs."))
+                                .map(|f| format!("数据包 {}", f))
+                }],
+        ),
+        (
+                Engine::NScripter,
+                &[
+                        |l| l.file("nscript.dat").map(|_| "脚本 nscript.dat".into()),
+                        |l| l.file("nscr_sec.dat").map(|_| "脚本 nscr_sec.dat".into()),
+                        |l| l.ext("nsa").map(|f| format!("数据包 {}", f)),
+                        |l| l.ext("sar").map(|f| format!("数据包 {}", f)),
+                ],
+        ),
+        (
+                Engine::RpgMakerMz,
+                &[|l| {
+                        l.has("js/rmmz_core.js")
+                                .then(|| "脚本 js/rmmz_core.js".into())
+                }],
+        ),
+        (
+                Engine::RpgMakerMv,
+                &[
+                        |l| {
+                                l.has("www/js/rpg_core.js")
+                                        .then(|| "脚本 www/js/rpg_core.js".into())
+                        },
+                        |l| {
+                                l.has("js/rpg_core.js")
+                                        .then(|| "脚本 js/rpg_core.js".into())
+                        },
+                ],
+        ),
+        (
+                Engine::RpgMakerVxAce,
+                &[
+                        |l| l.ext("rgss3a").map(|f| format!("数据包 {}", f)),
+                        |l| {
+                                l.file("game.rvproj2")
+                                        .map(|_| "工程文件 Game.rvproj2".into())
+                        },
+                        |l| rgss_dll(l, "rgss3"),
+                ],
+        ),
+        (
+                Engine::RpgMakerVx,
+                &[
+                        |l| l.ext("rgss2a").map(|f| format!("数据包 {}", f)),
+                        |l| l.file("game.rvproj").map(|_| "工程文件 Game.rvproj".into()),
+                        |l| rgss_dll(l, "rgss2"),
+                ],
+        ),
+        (
+                Engine::RpgMakerXp,
+                &[
+                        |l| l.ext("rgssad").map(|f| format!("数据包 {}", f)),
+                        |l| l.file("game.rxproj").map(|_| "工程文件 Game.rxproj".into()),
+                        |l| rgss_dll(l, "rgss1"),
+                ],
+        ),
+        (
+                Engine::RenPy,
+                &[
+                        |l| l.subdir("renpy").map(|_| "运行库目录 renpy/".into()),
+                        |l| {
+                                let game = Listing::read(l.subdir("game")?)?;
+                                game.ext("rpa")
+                                        .or_else(|| game.ext("rpyc"))
+                                        .map(|f| format!("脚本 game/{}", f))
+                        },
+                ],
+        ),
+        (
+                Engine::Unity,
+                &[
+                        |l| {
+                                l.dirs.iter().find_map(|(name, path)| {
+                                        let data = name.ends_with("_data")
+                                                && [
+                                                        "globalgamemanagers",
+                                                        "mainData",
+                                                        "data.unity3d",
+                                                ]
+                                                .iter()
+                                                .any(|f| path.join(f).is_file());
+                                        data.then(|| format!("数据目录 {}", file_name(path)))
+                                })
+                        },
+                        |l| {
+                                l.file("unityplayer.dll")
+                                        .map(|_| "运行库 UnityPlayer.dll".into())
+                        },
+                ],
+        ),
+        (
+                Engine::Kirikiri,
+                &[
+                        |l| l.ext("xp3").map(|f| format!("数据包 {}", f)),
+                        |l| {
+                                l.file("krkrsteam.dll")
+                                        .map(|_| "运行库 krkrsteam.dll".into())
+                        },
+                ],
+        ),
+];
+
+/// 识别游戏目录使用的引擎，并给出全部命中的特征
+pub fn detect(dir: &Path) -> Option<EngineMatch> {
+        let listing = Listing::read(dir)?;
+        RULES.iter().find_map(|(engine, rules)| {
+                let evidence: Vec<String> =
+                        rules.iter().filter_map(|rule| rule(&listing)).collect();
+                (!evidence.is_empty()).then_some(EngineMatch {
+                        engine: *engine,
+                        evidence,
+                })
+        })
+}
+
+/// 按启动程序路径识别（取其所在目录）
+pub fn detect_for_exe(abs_path: &str) -> Option<EngineMatch> {
+        let path = Path::new(abs_path);
+        if path.is_dir() {
+                return detect(path);
+        }
+        detect(path.parent()?)
+}
+
+/// RGSS 运行库，新版放在 `System/` 下
+fn rgss_dll(
+        listing: &Listing,
+        prefix: &str,
+) -> Option<String> {
+        let is_dll = |f: &str| f.starts_with(prefix) && f.ends_with(".dll");
+        listing.file_where(is_dll)
+                .map(|f| format!("运行库 {}", f))
+                .or_else(|| {
+                        Listing::read(listing.subdir("system")?)?
+                                .file_where(is_dll)
+                                .map(|f| format!("运行库 System/{}", f))
+                })
+}
+
+fn file_name(path: &Path) -> String {
+        path.file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+        use super::{detect, detect_for_exe};
+        use crate::{engine::entity::Engine, infra::tempdir::TempDir};
+
+        fn engine_of(files: &[&str]) -> Option<Engine> {
+                detect(TempDir::with_files(files).path()).map(|m| m.engine)
+        }
+
+        #[test]
+        fn detects_each_engine_signature() {
+                let cases: &[(&[&str], Engine)] = &[
+                        (
+                                &["SiglusEngine.exe", "Scene.pck", "Gameexe.dat"],
+                                Engine::Siglus,
+                        ),
+                        (&["BGI.exe", "sysprg.arc"], Engine::Bgi),
+                        (&["cs2conf.dll", "scene.int"], Engine::CatSystem2),
+                        (&["pac/bn.ypf"], Engine::YuRis),
+                        (&["root.pfs", "root.pfs.000"], Engine::Artemis),
+                        (&["nscript.dat", "arc.nsa"], Engine::NScripter),
+                        (&["js/rmmz_core.js"], Engine::RpgMakerMz),
+                        (&["www/js/rpg_core.js"], Engine::RpgMakerMv),
+                        (&["Game.rgss3a"], Engine::RpgMakerVxAce),
+                        (&["Game.rvproj"], Engine::RpgMakerVx),
+                        (&["System/RGSS102J.dll"], Engine::RpgMakerXp),
+                        (&["renpy/__init__.py"], Engine::RenPy),
+                        (&["game/script.rpyc"], Engine::RenPy),
+                        (&["Game_Data/globalgamemanagers"], Engine::Unity),
+                        (&["UnityPlayer.dll"], Engine::Unity),
+                        (&["data.xp3"], Engine::Kirikiri),
+                ];
+                for (files, engine) in cases {
+                        assert_eq!(engine_of(files), Some(*engine), "{:?}", files);
+                }
+        }
+
+        #[test]
+        fn collects_all_matching_evidence() {
+                let fixture =
+                        TempDir::with_files(&["Scene.pck", "Gameexe.dat", "SiglusEngine.exe"]);
+                let found = detect(fixture.path()).unwrap();
+                assert_eq!(found.engine, Engine::Siglus);
+                assert_eq!(found.evidence.len(), 3);
+        }
+
+        #[test]
+        fn shared_signatures_fall_back_last() {
+                // 借用 .xp3 数据包或 Unity 运行库的引擎按自身特征识别
+                assert_eq!(engine_of(&["Scene.pck", "patch.xp3"]), Some(Engine::Siglus));
+                assert_eq!(
+                        engine_of(&["www/js/rpg_core.js", "UnityPlayer.dll"]),
+                        Some(Engine::RpgMakerMv)
+                );
+                assert_eq!(
+                        engine_of(&["UnityPlayer.dll", "data.xp3"]),
+                        Some(Engine::Unity)
+                );
+        }
+
+        #[test]
+        fn detects_from_exe_path() {
+                let fixture = TempDir::with_files(&["Game.exe", "Game.rgssad"]);
+                let exe = fixture.path().join("Game.exe");
+                let found = detect_for_exe(&exe.to_string_lossy()).unwrap();
+                assert_eq!(found.engine, Engine::RpgMakerXp);
+        }
+
+        #[test]
+        fn unknown_layout_matches_nothing() {
+                assert_eq!(
+                        engine_of(&["Game.exe", "readme.txt", "data/image.png"]),
+                        None
+                );
+                let fixture = TempDir::new();
+                assert!(detect(&fixture.path().join("missing")).is_none());
+        }
+}
@@ -0,0 +1,170 @@
+//! 游戏引擎相关数据结构
+
+use serde::{Deserialize, Serialize};
+
+use crate::game::entity::SaveLocation;
+
+/// 游戏引擎，记录在游戏的 `engine` 列中
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, sqlx::Type)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub enum Engine {
+        /// 吉里吉里 / KAG
+        Kirikiri,
+        #[serde(rename = "nscripter")]
+        #[sqlx(rename = "nscripter")]
+        NScripter,
+        RenPy,
+        RpgMakerXp,
+        RpgMakerVx,
+        RpgMakerVxAce,
+        RpgMakerMv,
+        RpgMakerMz,
+        Unity,
+        Artemis,
+        /// SiglusEngine
+        Siglus,
+        /// BGI / Ethornell
+        Bgi,
+        CatSystem2,
+        YuRis,
+}
+
+impl Engine {
+        pub const ALL: &[Engine] = &[
+                Engine::Kirikiri,
+                Engine::NScripter,
+                Engine::RenPy,
+                Engine::RpgMakerXp,
+                Engine::RpgMakerVx,
+                Engine::RpgMakerVxAce,
+                Engine::RpgMakerMv,
+                Engine::RpgMakerMz,
+                Engine::Unity,
+                Engine::Artemis,
+                Engine::Siglus,
+                Engine::Bgi,
+                Engine::CatSystem2,
+                Engine::YuRis,
+        ];
+
+        pub fn label(self) -> &'static str {
+                match self {
+                        | Engine::Kirikiri => "吉里吉里 / KAG",
+                        | Engine::NScripter => "NScripter",
+                        | Engine::RenPy => "Ren'Py",
+                        | Engine::RpgMakerXp => "RPG Maker XP",
+                        | Engine::RpgMakerVx => "RPG Maker VX",
+                        | Engine::RpgMakerVxAce => "RPG Maker VX Ace",
+                        | Engine::RpgMakerMv => "RPG Maker MV",
+                        | Engine::RpgMakerMz => "RPG Maker MZ",
+                        | Engine::Unity => "Unity",
+                        | Engine::Artemis => "Artemis",
+                        | Engine::Siglus => "SiglusEngine",
+                        | Engine::Bgi => "BGI / Ethornell",
+                        | Engine::CatSystem2 => "CatSystem2",
+                        | Engine::YuRis => "YU-RIS",
+                }
+        }
+
+        /// 该引擎的惯用存档位置
+        pub fn save_locations(self) -> Vec<SaveLocation> {
+                let dir = |template: &str| SaveLocation {
+                        template: template.into(),
+                        ..Default::default()
+                };
+                let files = |patterns: &[&str]| SaveLocation {
+                        template: "{game_dir}".into(),
+                        include: patterns.iter().map(|p| p.to_string()).collect(),
+                        exclude: Vec::new(),
+                };
+                match self {
+                        | Engine::Kirikiri
+                        | Engine::Siglus
+                        | Engine::CatSystem2
+                        | Engine::YuRis => {
+                                vec![dir("{game_dir}/savedata")]
+                        },
+                        | Engine::NScripter => {
+                                vec![files(&["save*.dat", "gloval.sav", "kidoku.dat", "envdata"])]
+                        },
+                        | Engine::RenPy => vec![dir("{game_dir}/game/saves")],
+                        | Engine::RpgMakerXp => vec![files(&["Save*.rxdata"])],
+                        | Engine::RpgMakerVx => vec![files(&["Save*.rvdata"])],
+                        | Engine::RpgMakerVxAce => vec![files(&["Save*.rvdata2"])],
+                        | Engine::RpgMakerMv => vec![dir("{game_dir}/www/save")],
+                        | Engine::RpgMakerMz => vec![dir("{game_dir}/save")],
+                        | Engine::Artemis => vec![dir("{game_dir}/save")],
+                        | Engine::Bgi => vec![dir("{game_dir}/UserData")],
+                        // 存在 AppData/LocalLow/<公司名>/<产品名> 下，无法从引擎推断
+                        | Engine::Unity => Vec::new(),
+                }
+        }
+
+        /// 非日文系统上需要转区运行（引擎按 Shift-JIS 处理文件名与文本）
+        pub fn needs_japanese_locale(self) -> bool {
+                !matches!(
+                        self,
+                        Engine::RenPy | Engine::RpgMakerMv | Engine::RpgMakerMz | Engine::Unity
+                )
+        }
+
+        /// 常见的文本提取工具可以直接挂钩（浏览器或 Python 运行时的引擎需要专门的插件）
+        pub fn supports_text_hook(self) -> bool {
+                !matches!(
+                        self,
+                        Engine::RenPy | Engine::RpgMakerMv | Engine::RpgMakerMz
+                )
+        }
+
+        pub fn profile(self) -> EngineProfile {
+                EngineProfile {
+                        engine: self,
+                        label: self.label(),
+                        save_locations: self.save_locations(),
+                        needs_japanese_locale: self.needs_japanese_locale(),
+                        supports_text_hook: self.supports_text_hook(),
+                }
+        }
+}
+
+/// 引擎的默认设置，供启动选项、存档位置猜测和文本提取工具选择使用
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineProfile {
+        pub engine: Engine,
+        pub label: &'static str,
+        pub save_locations: Vec<SaveLocation>,
+        pub needs_japanese_locale: bool,
+        pub supports_text_hook: bool,
+}
+
+/// 引擎识别结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineMatch {
+        pub engine: Engine,
+        /// 判断依据
+        pub evidence: Vec<String>,
+}
+
+/// 一个游戏的识别结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedEngine {
+        pub game_id: String,
+        pub name: String,
+        /// 未能识别时为空
+        pub detected: Option<EngineMatch>,
+        /// 是否写入了游戏记录
+        pub updated: bool,
+}
+
+/// 批量识别结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EngineDetectReport {
+        pub games: Vec<DetectedEngine>,
+        /// 已记录引擎、未重新识别的游戏数
+        pub skipped: usize,
+}
@@ -0,0 +1,7 @@
+//! 游戏引擎识别
+//!
+//! 按游戏目录中的特征文件识别引擎并记录到游戏上，
+//! 启动选项、存档位置猜测和文本提取工具据此按引擎选择默认设置。
+pub mod commands;
+pub mod detect;
+pub mod entity;
@@ -1,16 +1,18 @@
 //! 游戏启动与生命周期管理
 
 use std::{
+        collections::HashSet,
+        ffi::OsStr,
         path::{Path, PathBuf},
-        process::{Command, Stdio},
+        process::Stdio,
         time::{Duration, Instant},
 };
-use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use sysinfo::{Pid, Process, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
 
-use chrono::Local;
+use chrono::{DateTime, Local};
 use sqlx::SqlitePool;
-use tauri_plugin_log::log::error;
-use uuid::Uuid;
+use tauri_plugin_log::log::{error, warn};
+use tokio::task::JoinHandle;
 
 use crate::{
         backup::commands::backup_by_game_id,
@@ -19,9 +21,15 @@ use crate::{
         error::AppError,
         game::{
                 RUNNING_GAMES,
-                entity::{GameMeta, RunningGame},
+                entity::{GameMeta, HookStage, LaunchProfile, RunningGame, SessionSource},
+                hook::{self, HookContext},
+                profile, runner,
+                session::{self, SessionActivity},
+        },
+        infra::{
+                activity::{self, ActivityProbe, ActivitySample},
+                process::kill_by_name,
         },
-        infra::process::kill_by_name,
 };
 
 /// 轮询间隔
@@ -46,133 +54,491 @@ fn path_starts_with_ci(
         parent_c.len() <= child_c.len() && child_c.starts_with(parent_c.as_slice())
 }
 
+/// 命令行参数中的路径：Wine 下形如 `Z:\\home\\...` 的路径换成对应的 Unix 路径
+fn arg_path(arg: &OsStr) -> Option<PathBuf> {
+        let arg = arg.to_string_lossy();
+        let arg = arg.trim_matches('"');
+        match arg.as_bytes() {
+                | [b'z' | b'Z', b':', b'\\' | b'/', ..] => {
+                        Some(PathBuf::from(arg[2..].replace('\\', "/")))
+                },
+                | [b'/', ..] => Some(PathBuf::from(arg)),
+                | [drive, b':', b'\\' | b'/', ..] if drive.is_ascii_alphabetic() => {
+                        Some(PathBuf::from(arg))
+                },
+                | _ => None,
+        }
+}
+
+/// 是否属于游戏的进程：可执行文件在游戏目录内；
+/// 经兼容层运行时（`follow_tree`）还包括命令行参数指向游戏目录的进程
+pub fn is_game_process(
+        process: &Process,
+        game_dirs: &[PathBuf],
+        follow_tree: bool,
+) -> bool {
+        let in_game_dirs = |path: &Path| game_dirs.iter().any(|dir| path_starts_with_ci(path, dir));
+        process.exe().is_some_and(in_game_dirs)
+                || (follow_tree
+                        && process
+                                .cmd()
+                                .iter()
+                                .take(2)
+                                .any(|arg| arg_path(arg).is_some_and(|path| in_game_dirs(&path))))
+}
+
+fn refresh_kind(follow_tree: bool) -> ProcessRefreshKind {
+        ProcessRefreshKind::nothing()
+                .with_exe(UpdateKind::Always)
+                .with_cmd(if follow_tree {
+                        UpdateKind::Always
+                } else {
+                        UpdateKind::Never
+                })
+}
+
+/// 需要监控的目录：游戏目录，以及启动配置的程序所在目录
+/// （可能在游戏目录之外，如单独解压的补丁版）
+fn watch_dirs(
+        game: &GameMeta,
+        launch_profile: &LaunchProfile,
+) -> Result<Vec<PathBuf>, AppError> {
+        let game_dir = Path::new(&game.abs_path)
+                .parent()
+                .ok_or_else(|| AppError::Process("无法解析游戏目录".into()))?
+                .to_path_buf();
+        let mut dirs = vec![game_dir];
+        if let Some(exe_dir) = Path::new(&launch_profile.exe_path).parent()
+                && !dirs.iter().any(|d| path_starts_with_ci(exe_dir, d))
+        {
+                dirs.push(exe_dir.to_path_buf());
+        }
+        Ok(dirs)
+}
+
+/// 监控中的会话
+struct WatchedSession {
+        id: String,
+        game_id: String,
+        pid: u32,
+        watch_dirs: Vec<PathBuf>,
+        follow_tree: bool,
+        started_at: DateTime<Local>,
+        /// 从外部启动的会话没有脚本
+        hook_ctx: Option<HookContext>,
+        /// 随游戏启动、会话结束时关闭的连携程序
+        companion_names: Vec<String>,
+        /// 开始（或恢复）监控前已统计的时长
+        activity: SessionActivity,
+}
+
 /// 持续监控，直到"游戏目录范围内的进程"全部退出（并经过宽限期确认）后才返回。
 /// 用于应对启动的可执行文件只是个启动器（拉起真正游戏主程序后自己退出）的情况：
 /// 启动器退出后会继续在游戏目录范围内扫描是否有新进程被拉起，
 /// 直到一段宽限期内都没有任何匹配进程存活，才认为会话真正结束。
+///
+/// 经转区工具或兼容层运行时（`follow_tree`），游戏进程的可执行文件是 Wine 等程序本身，
+/// 因此还会跟踪初始进程的子孙进程（兼容层的辅助进程除外），
+/// 以及命令行参数指向游戏目录的进程。
+///
+/// 监控期间按 [`POLL_INTERVAL`] 采样用户活动：超过 `idle_threshold_secs` 没有键鼠输入、
+/// 或游戏窗口离开前台超过同样时长，这段时间记为离开，其余记为有效游玩；
+/// 统计结果随心跳定期写入会话。
 async fn wait_for_game_session_end(
-        initial_pid: u32,
-        game_dir: &Path,
-) {
+        pool: &SqlitePool,
+        session: &WatchedSession,
+) -> SessionActivity {
         let mut sys = System::new();
         let mut last_alive_at = Instant::now();
-        let refresh_kind = ProcessRefreshKind::nothing().with_exe(UpdateKind::Always);
+        let mut last_heartbeat_at = Instant::now();
+        let mut probe = Some(activity::probe());
+        let mut probe_task = None;
+        let mut activity = session.activity;
+        let idle_threshold = read_config()
+                .map(|cfg| cfg.system.idle_threshold_secs)
+                .unwrap_or_else(|e| {
+                        error!("读取离开判定时长失败: {}", e);
+                        300
+                })
+                .max(POLL_INTERVAL.as_secs());
+        let mut last_sample = Instant::now();
+        let mut last_focused_at = Instant::now();
+        let follow_tree = session.follow_tree;
+        let refresh_kind = refresh_kind(follow_tree);
+        let mut tree = HashSet::from([Pid::from_u32(session.pid)]);
 
         loop {
                 sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind);
 
-                let any_alive = sys.process(Pid::from_u32(initial_pid)).is_some()
-                        || sys.processes().values().any(|p| {
-                                p.exe().is_some_and(|exe| path_starts_with_ci(exe, game_dir))
-                        });
+                tree.retain(|pid| sys.process(*pid).is_some());
+                if follow_tree {
+                        // 逐层加入新拉起的子进程，直到没有新成员
+                        loop {
+                                let children: Vec<Pid> = sys
+                                        .processes()
+                                        .iter()
+                                        .filter(|(pid, p)| {
+                                                !tree.contains(*pid)
+                                                        && p.parent().is_some_and(|parent| tree.contains(&parent))
+                                                        && !runner::HELPER_PROCESSES.iter().any(|name| {
+                                                                p.name().eq_ignore_ascii_case(name)
+                                                        })
+                                        })
+                                        .map(|(pid, _)| *pid)
+                                        .collect();
+                                if children.is_empty() {
+                                        break;
+                                }
+                                tree.extend(children);
+                        }
+                }
+
+                let game_pids: HashSet<Pid> = sys
+                        .processes()
+                        .iter()
+                        .filter(|(pid, p)| {
+                                tree.contains(*pid)
+                                        || is_game_process(p, &session.watch_dirs, follow_tree)
+                        })
+                        .map(|(pid, _)| *pid)
+                        .collect();
+
+                let now = Instant::now();
+                let elapsed = now.duration_since(last_sample);
+                last_sample = now;
 
-                if any_alive {
-                        last_alive_at = Instant::now();
+                if !game_pids.is_empty() {
+                        last_alive_at = now;
+                        let sample = sample_activity(&mut probe, &mut probe_task).await;
+                        // 无法识别前台窗口时视为游戏在前台
+                        let focused = sample
+                                .foreground_pid
+                                .is_none_or(|pid| game_pids.contains(&Pid::from_u32(pid)));
+                        if focused {
+                                last_focused_at = now;
+                        }
+                        let away = sample.idle_seconds.is_some_and(|s| s >= idle_threshold)
+                                || now.duration_since(last_focused_at).as_secs() >= idle_threshold;
+                        if away {
+                                activity.idle += elapsed;
+                        } else {
+                                activity.active += elapsed;
+                        }
                 } else if last_alive_at.elapsed() >= EXIT_GRACE_PERIOD {
                         break;
                 }
 
+                if last_heartbeat_at.elapsed() >= session::HEARTBEAT_INTERVAL {
+                        last_heartbeat_at = Instant::now();
+                        if let Err(e) = session::heartbeat(pool, &session.id, &activity).await {
+                                error!("写入游戏 {} 的会话心跳失败: {}", session.game_id, e);
+                        }
+                }
+
                 tokio::time::sleep(POLL_INTERVAL).await;
         }
+        activity
+}
+
+type ProbeTask = JoinHandle<(Box<dyn ActivityProbe>, ActivitySample)>;
+
+/// 在阻塞线程中检测一次用户活动，最多等待 [`POLL_INTERVAL`]
+///
+/// 检测要与 X11 / D-Bus 往返，不能占用异步运行时的线程；桌面环境没有响应时本次按未知处理，
+/// 下次继续等待同一个检测，不会堆积新的线程
+async fn sample_activity(
+        probe: &mut Option<Box<dyn ActivityProbe>>,
+        task: &mut Option<ProbeTask>,
+) -> ActivitySample {
+        if let Some(mut p) = probe.take() {
+                *task = Some(tokio::task::spawn_blocking(move || {
+                        let sample = p.sample();
+                        (p, sample)
+                }));
+        }
+        let Some(pending) = task.as_mut() else {
+                return ActivitySample::default();
+        };
+        match tokio::time::timeout(POLL_INTERVAL, pending).await {
+                | Ok(Ok((p, sample))) => {
+                        *task = None;
+                        *probe = Some(p);
+                        sample
+                },
+                | Ok(Err(e)) => {
+                        error!("用户活动检测失败: {}", e);
+                        *task = None;
+                        *probe = Some(activity::probe());
+                        ActivitySample::default()
+                },
+                | Err(_) => {
+                        warn!("用户活动检测超时，本次按未知处理");
+                        ActivitySample::default()
+                },
+        }
 }
 
-/// 启动游戏进程，并异步等待结束后记录会话、触发自动备份、关闭连携程序
+/// 按启动配置启动游戏进程，并异步等待结束后记录会话、触发自动备份、关闭连携程序；
+/// 游戏进程前后分别执行启动前 / 退出后脚本
 pub async fn launch(
         pool: SqlitePool,
         game: GameMeta,
+        launch_profile: LaunchProfile,
 ) -> Result<(), AppError> {
-        let start_time = Local::now();
-        let start_instant = Instant::now();
+        let started_at = Local::now();
         let game_id = game.id.clone();
-        let exe_path = PathBuf::from(&game.abs_path);
-        let game_dir = exe_path
-                .parent()
-                .ok_or_else(|| AppError::Process("无法解析游戏目录".into()))?
-                .to_path_buf();
+        let watch_dirs = watch_dirs(&game, &launch_profile)?;
+        // ── 启动前脚本（失败且设为中止时取消启动）──────────────────────────────
+        let hook_ctx = HookContext::new(&game, &launch_profile);
+        hook::run_stage(&pool, &game_id, HookStage::PreLaunch, &hook_ctx).await?;
+        // ── 启动游戏主进程（注意：它可能只是一个启动器）─────────────────────────
+        let follow_tree = launch_profile.runner.is_some();
+        let (session_id, pid) = match spawn_game(&pool, &game_id, &launch_profile, started_at).await
+        {
+                | Ok(started) => started,
+                | Err(e) => {
+                        // 启动前脚本可能已改动系统（挂载镜像、切换分辨率等），
+                        // 游戏没能启动时同样执行退出后脚本把它还原
+                        if let Err(hook_err) =
+                                hook::run_stage(&pool, &game_id, HookStage::PostExit, &hook_ctx)
+                                        .await
+                        {
+                                error!("执行退出后脚本失败: {}", hook_err);
+                        }
+                        return Err(e);
+                },
+        };
         // ── 启动随游戏触发的连携程序 ──────────────────────────────────────────
+        // 放在游戏成功启动之后：前面任一步失败时不会留下无人管理的连携程序
         let companion_names = companion::commands::launch_game_companions(&pool).await;
-        // ── 启动游戏主进程（注意：它可能只是一个启动器）─────────────────────────
-        let child = Command::new(&exe_path)
-                .current_dir(&game_dir)
+        // ── 异步监听进程退出 ───────────────────────────────────────────────────
+        tauri::async_runtime::spawn(watch(
+                pool,
+                WatchedSession {
+                        id: session_id,
+                        game_id,
+                        pid,
+                        watch_dirs,
+                        follow_tree,
+                        started_at,
+                        hook_ctx: Some(hook_ctx),
+                        companion_names,
+                        activity: SessionActivity::default(),
+                },
+        ));
+        Ok(())
+}
+
+/// 写入进行中的会话并启动游戏进程，返回会话 ID 与进程 ID；启动失败时删除该会话
+async fn spawn_game(
+        pool: &SqlitePool,
+        game_id: &str,
+        launch_profile: &LaunchProfile,
+        started_at: DateTime<Local>,
+) -> Result<(String, u32), AppError> {
+        let mut command = profile::command(launch_profile)?;
+        // 先写入进行中的会话，程序中途退出时下次启动可据此恢复
+        let session_id = session::open(
+                pool,
+                game_id,
+                Some(&launch_profile.id),
+                SessionSource::Hub,
+                started_at,
+        )
+        .await?;
+        let spawned = command
                 .stdin(Stdio::null())
                 .stdout(Stdio::inherit())
                 .stderr(Stdio::inherit())
-                .spawn()
-                .map_err(|e| {
-                        AppError::Resolve(game.abs_path.clone(), format!("启动失败: {}", e))
-                })?;
+                .spawn();
+        let mut child = match spawned {
+                | Ok(child) => child,
+                | Err(e) => {
+                        if let Err(e) = session::discard(pool, &session_id).await {
+                                error!("删除未开始的会话失败: {}", e);
+                        }
+                        return Err(AppError::Resolve(
+                                launch_profile.exe_path.clone(),
+                                format!("启动失败: {}", e),
+                        ));
+                },
+        };
         let pid = child.id();
-        // 后续改为基于目录扫描的轮询监控，不再需要持有 child 去 wait()；
-        // Drop 一个 Child 不会杀掉对应的子进程，丢弃是安全的
-        drop(child);
+        // 会话结束以目录 / 进程树扫描为准；这里只负责回收子进程，
+        // 否则在 Linux 上它退出后会作为僵尸进程一直留在进程表中，被误判为仍在运行
+        tauri::async_runtime::spawn_blocking(move || child.wait());
         RUNNING_GAMES
                 .lock()
                 .map_err(|e| AppError::Lock(e.to_string()))?
-                .insert(game_id.clone(), RunningGame { pid });
-        // ── 异步监听进程退出 ───────────────────────────────────────────────────
-        let pool_clone = pool.clone();
-        let game_id_clone = game_id.clone();
-        tauri::async_runtime::spawn(async move {
-                if let Err(e) = (async {
-                        // 等待游戏会话结束：启动器退出后会继续在游戏目录范围内扫描，
-                        // 直到宽限期内都不再有存活进程才视为结束
-                        wait_for_game_session_end(pid, &game_dir).await;
-                        let duration_minutes = (start_instant.elapsed().as_secs() / 60) as i64;
-                        // 自动备份
-                        let auto_backup = read_config()
-                                .map(|cfg| cfg.storage.auto_backup)
-                                .unwrap_or_else(|e| {
-                                        error!("读取自动备份配置失败: {}", e);
-                                        false
-                                });
-                        if auto_backup
-                                && let Err(e) =
-                                        backup_by_game_id(pool.clone(), game_id_clone.clone()).await
-                        {
-                                error!("自动备份游戏 {} 失败: {}", game_id_clone, e);
-                        }
-                        // 清理运行状态
-                        match RUNNING_GAMES.lock() {
-                                | Ok(mut running) => {
-                                        running.remove(&game_id_clone);
-                                },
-                                | Err(e) => error!("清理运行中游戏状态失败: {}", e),
-                        }
-                        // 关闭随游戏启动的连携程序
-                        for name in &companion_names {
-                                kill_by_name(name);
+                .insert(game_id.to_string(), RunningGame { pid });
+        Ok((session_id, pid))
+}
+
+/// 为上次运行遗留的会话重新接上监控；游戏进程已不在运行时返回 `false`
+pub async fn reattach(
+        pool: SqlitePool,
+        game: &GameMeta,
+        launch_profile: &LaunchProfile,
+        session_id: String,
+        started_at: DateTime<Local>,
+        source: SessionSource,
+        activity: SessionActivity,
+) -> Result<bool, AppError> {
+        if RUNNING_GAMES
+                .lock()
+                .map_err(|e| AppError::Lock(e.to_string()))?
+                .contains_key(&game.id)
+        {
+                return Ok(false);
+        }
+        let watch_dirs = watch_dirs(game, launch_profile)?;
+        let follow_tree = launch_profile.runner.is_some();
+        let mut sys = System::new();
+        sys.refresh_processes_specifics(ProcessesToUpdate::All, true, refresh_kind(follow_tree));
+        let Some(pid) = sys
+                .processes()
+                .iter()
+                .find(|(_, p)| is_game_process(p, &watch_dirs, follow_tree))
+                .map(|(pid, _)| pid.as_u32())
+        else {
+                return Ok(false);
+        };
+
+        RUNNING_GAMES
+                .lock()
+                .map_err(|e| AppError::Lock(e.to_string()))?
+                .insert(game.id.clone(), RunningGame { pid });
+        tauri::async_runtime::spawn(watch(
+                pool,
+                WatchedSession {
+                        id: session_id,
+                        game_id: game.id.clone(),
+                        pid,
+                        watch_dirs,
+                        follow_tree,
+                        started_at,
+                        hook_ctx: (source == SessionSource::Hub)
+                                .then(|| HookContext::new(game, launch_profile)),
+                        companion_names: Vec::new(),
+                        activity,
+                },
+        ));
+        Ok(true)
+}
+
+/// 监控从本程序之外启动的游戏进程，记为外部会话；游戏已在监控中时什么也不做
+pub async fn attach_external(
+        pool: SqlitePool,
+        game_id: &str,
+        game_dir: PathBuf,
+        pid: u32,
+) -> Result<(), AppError> {
+        // 检查与占位在同一次加锁内完成，避免与其他启动流程重复记录
+        {
+                let mut running = RUNNING_GAMES
+                        .lock()
+                        .map_err(|e| AppError::Lock(e.to_string()))?;
+                if running.contains_key(game_id) {
+                        return Ok(());
+                }
+                running.insert(game_id.to_string(), RunningGame { pid });
+        }
+        let started_at = Local::now();
+        let session_id = match session::open(
+                &pool,
+                game_id,
+                None,
+                SessionSource::External,
+                started_at,
+        )
+        .await
+        {
+                | Ok(id) => id,
+                | Err(e) => {
+                        if let Ok(mut running) = RUNNING_GAMES.lock() {
+                                running.remove(game_id);
                         }
-                        // 写入会话记录
-                        let mut tx = pool_clone.begin().await.map_err(AppError::from)?;
-                        sqlx::query(
-                                "INSERT INTO game_play_sessions \
-                 (id, game_id, play_date, duration_minutes, last_played_at) \
-                 VALUES (?, ?, ?, ?, ?)",
+                        return Err(e);
+                },
+        };
+        tauri::async_runtime::spawn(watch(
+                pool,
+                WatchedSession {
+                        id: session_id,
+                        game_id: game_id.to_string(),
+                        pid,
+                        watch_dirs: vec![game_dir],
+                        follow_tree: false,
+                        started_at,
+                        hook_ctx: None,
+                        companion_names: Vec::new(),
+                        activity: SessionActivity::default(),
+                },
+        ));
+        Ok(())
+}
+
+/// 等待会话结束，然后执行退出后脚本、自动备份、关闭连携程序并结束会话
+async fn watch(
+        pool: SqlitePool,
+        session: WatchedSession,
+) {
+        let game_id = session.game_id.clone();
+        if let Err(e) = (async {
+                // 等待游戏会话结束：启动器退出后会继续在游戏目录范围内扫描，
+                // 直到宽限期内都不再有存活进程才视为结束
+                let activity = wait_for_game_session_end(&pool, &session).await;
+                let ended_at = Local::now();
+                let duration_minutes = activity.counted_minutes(session.started_at, ended_at);
+                // 退出后脚本：先于自动备份执行，便于脚本把存档同步回原位
+                if let Some(hook_ctx) = &session.hook_ctx
+                        && let Err(e) = hook::run_stage(
+                                &pool,
+                                &game_id,
+                                HookStage::PostExit,
+                                &hook_ctx.clone().with_duration(duration_minutes),
                         )
-                        .bind(Uuid::new_v4().to_string())
-                        .bind(&game_id_clone)
-                        .bind(start_time)
-                        .bind(duration_minutes)
-                        .bind(Local::now())
-                        .execute(&mut *tx)
                         .await
-                        .map_err(AppError::from)?;
-                        sqlx::query(
-                "UPDATE games SET play_time = play_time + ?, last_played_at = ? WHERE id = ?",
-            )
-            .bind(duration_minutes)
-            .bind(Local::now())
-            .bind(&game_id_clone)
-            .execute(&mut *tx)
-            .await
-            .map_err(AppError::from)?;
-                        tx.commit().await.map_err(AppError::from)?;
-                        Ok::<(), AppError>(())
-                })
-                .await
                 {
-                        error!("游戏进程监听出错 [{}]: {}", game_id, e);
+                        error!("执行游戏 {} 的退出后脚本失败: {}", game_id, e);
                 }
-        });
-        Ok(())
+                // 自动备份
+                let auto_backup = read_config()
+                        .map(|cfg| cfg.storage.auto_backup)
+                        .unwrap_or_else(|e| {
+                                error!("读取自动备份配置失败: {}", e);
+                                false
+                        });
+                if auto_backup
+                        && let Err(e) =
+                                backup_by_game_id(pool.clone(), game_id.clone(), false).await
+                {
+                        error!("自动备份游戏 {} 失败: {}", game_id, e);
+                }
+                // 清理运行状态
+                match RUNNING_GAMES.lock() {
+                        | Ok(mut running) => {
+                                running.remove(&game_id);
+                        },
+                        | Err(e) => error!("清理运行中游戏状态失败: {}", e),
+                }
+                // 关闭随游戏启动的连携程序
+                for name in &session.companion_names {
+                        kill_by_name(name);
+                }
+                // 结束会话并累计游玩时长
+                session::close(
+                        &pool,
+                        &session.id,
+                        &game_id,
+                        &activity,
+                        duration_minutes,
+                        ended_at,
+                )
+                .await
+        })
+        .await
+        {
+                error!("游戏进程监听出错 [{}]: {}", game_id, e);
+        }
 }
@@ -1,11 +1,14 @@
 //! 游戏相关数据结构
 
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 use sqlx::prelude::FromRow;
 use tokio::sync::broadcast;
 
 use crate::{
+        engine::entity::Engine,
         message::traits::{MessageEvent, MessageHub},
         user::entity::User,
 };
@@ -18,6 +21,10 @@ pub struct GameMeta {
         pub id: String,
         pub name: String,
         pub abs_path: String,
+        /// 设置程序（画面、音量等），没有时为空
+        #[serde(default)]
+        #[sqlx(default)]
+        pub config_path: Option<String>,
         pub is_passed: bool,
         pub is_displayed: bool,
         pub cover: String,
@@ -27,18 +34,196 @@ pub struct GameMeta {
         pub local_cover: Option<String>,
         pub local_background: Option<String>,
         pub save_data_path: Option<String>,
+        /// 存档位置列表；非空时取代 `save_data_path`
+        #[serde(default)]
+        #[sqlx(json)]
+        pub save_locations: Vec<SaveLocation>,
         pub backup_data_path: Option<String>,
         pub play_time: i64,
         pub length: Option<i64>,
         pub size: Option<i64>,
         pub last_played_at: Option<DateTime<Local>>,
+        /// 游戏引擎，未识别时为空
+        #[serde(default)]
+        #[sqlx(default)]
+        pub engine: Option<Engine>,
 }
 
 pub type GameMetaList = Vec<GameMeta>;
 
+/// 一个存档位置：路径模板加文件过滤规则
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveLocation {
+        /// 路径模板，支持 `{game_dir}` `{home}` `{appdata}` `{documents}` 占位符；
+        /// 相对路径视为相对游戏目录
+        pub template: String,
+        /// 只备份匹配的文件（按相对该位置的路径匹配），为空时备份全部
+        #[serde(default)]
+        pub include: Vec<String>,
+        /// 不备份匹配的文件，优先于 `include`
+        #[serde(default)]
+        pub exclude: Vec<String>,
+}
+
+// ── 启动配置 ──────────────────────────────────────────────────────────────────
+
+/// 由 `abs_path` 生成的内置启动配置 ID
+pub const MAIN_PROFILE_ID: &str = "main";
+/// 由 `config_path` 生成的内置启动配置 ID
+pub const CONFIG_PROFILE_ID: &str = "config";
+
+/// 一种启动方式：同一个游戏可以有「主程序」「设置」「英文补丁」「调试」等多个
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchProfile {
+        /// 新建时为空
+        #[serde(default)]
+        pub id: String,
+        pub game_id: String,
+        pub name: String,
+        /// 可执行文件的绝对路径
+        pub exe_path: String,
+        #[serde(default)]
+        #[sqlx(json)]
+        pub args: Vec<String>,
+        /// 工作目录，为空时使用可执行文件所在目录
+        #[serde(default)]
+        pub working_dir: Option<String>,
+        /// 额外的环境变量
+        #[serde(default)]
+        #[sqlx(json)]
+        pub env: BTreeMap<String, String>,
+        /// 转区工具或兼容层，为空时直接运行
+        #[serde(default)]
+        #[sqlx(json)]
+        pub runner: Option<Runner>,
+        /// 不指定启动配置时使用
+        #[serde(default)]
+        pub is_default: bool,
+        #[serde(default)]
+        pub sort_order: i64,
+        /// 由游戏路径生成的内置配置，不能修改或删除
+        #[serde(default)]
+        #[sqlx(skip)]
+        pub builtin: bool,
+}
+
+/// 运行方式：把游戏程序包装在转区工具或兼容层中运行
+///
+/// 包装命令的参数中，`{exe}` 替换为游戏程序路径；没有 `{exe}` 时游戏程序接在最后
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(
+        tag = "kind",
+        rename_all = "camelCase",
+        rename_all_fields = "camelCase"
+)]
+pub enum Runner {
+        /// 转区工具，如 Locale Emulator 的 `LEProc.exe -run {exe}`
+        LocaleEmulator {
+                command: String,
+                #[serde(default)]
+                args: Vec<String>,
+        },
+        /// Wine；`prefix` 为空时每个游戏使用单独的前缀
+        Wine {
+                /// Wine 程序，为空时使用 `wine`
+                #[serde(default)]
+                binary: Option<String>,
+                #[serde(default)]
+                prefix: Option<String>,
+        },
+        /// Proton；`compat_data` 为空时每个游戏使用单独的目录
+        Proton {
+                /// 含 `proton` 脚本的目录
+                proton_dir: String,
+                #[serde(default)]
+                compat_data: Option<String>,
+        },
+        /// 自定义包装命令
+        Custom {
+                command: String,
+                #[serde(default)]
+                args: Vec<String>,
+        },
+}
+
+/// 一种运行方式的预设环境变量，启动配置中的同名变量优先
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunnerPreset {
+        /// 与 [`Runner`] 序列化后的 `kind` 相同；直接运行为 `native`
+        pub kind: &'static str,
+        pub label: &'static str,
+        pub env: BTreeMap<&'static str, &'static str>,
+}
+
+// ── 启动前 / 退出后脚本 ───────────────────────────────────────────────────────
+
+/// 游戏的启动前 / 退出后脚本，通过系统 shell 执行
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct GameHook {
+        /// 新建时为空
+        #[serde(default)]
+        pub id: String,
+        #[serde(default)]
+        pub game_id: String,
+        pub name: String,
+        pub stage: HookStage,
+        /// 交给 shell 执行的命令行（Windows 为 `cmd /C`，其余为 `sh -c`）
+        pub command: String,
+        /// 工作目录，为空时使用游戏目录
+        #[serde(default)]
+        pub working_dir: Option<String>,
+        /// 超时后结束脚本进程并视为失败
+        pub timeout_secs: i64,
+        pub on_failure: HookFailure,
+        pub is_enabled: bool,
+        #[serde(default)]
+        pub sort_order: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub enum HookStage {
+        /// 启动游戏进程之前
+        PreLaunch,
+        /// 游戏会话结束之后（自动备份之前）
+        PostExit,
+}
+
+/// 脚本失败（退出码非 0、超时或无法启动）时的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub enum HookFailure {
+        /// 启动前脚本失败时取消启动；退出后脚本失败时跳过其余退出后脚本
+        Abort,
+        /// 记录警告后继续
+        Warn,
+}
+
+/// 一次脚本执行的结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HookRun {
+        pub hook_id: String,
+        pub name: String,
+        pub stage: HookStage,
+        pub success: bool,
+        /// 超时或无法启动时为空
+        pub exit_code: Option<i32>,
+        pub timed_out: bool,
+        pub duration_ms: u64,
+        pub stdout: String,
+        pub stderr: String,
+}
+
 // ── 游戏会话 ──────────────────────────────────────────────────────────────────
 
-#[derive(Debug, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Serialize, Deserialize, Clone, FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct PlaySession {
         pub id: String,
@@ -46,6 +231,75 @@ pub struct PlaySession {
         pub play_date: DateTime<Local>,
         pub duration_minutes: i64,
         pub last_played_at: DateTime<Local>,
+        /// 使用的启动配置，旧会话为空
+        #[serde(default)]
+        #[sqlx(default)]
+        pub profile_id: Option<String>,
+        /// 有操作且游戏在前台的秒数，旧会话为空
+        #[serde(default)]
+        #[sqlx(default)]
+        pub active_seconds: Option<i64>,
+        /// 离开（无操作或游戏不在前台）的秒数，旧会话为空
+        #[serde(default)]
+        #[sqlx(default)]
+        pub idle_seconds: Option<i64>,
+        /// 会话的来源
+        #[serde(default)]
+        #[sqlx(default)]
+        pub source: SessionSource,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, sqlx::Type)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub enum SessionSource {
+        /// 从本程序启动
+        #[default]
+        Hub,
+        /// 从本程序之外启动，由后台进程监控识别
+        External,
+        /// 手动录入（如在其他设备上游玩）
+        Manual,
+}
+
+/// 手动录入或修改会话时提交的内容
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionInput {
+        pub game_id: String,
+        /// 开始时间
+        pub play_date: DateTime<Local>,
+        pub duration_minutes: i64,
+        /// 结束时间，未填写时按开始时间加时长计算
+        #[serde(default)]
+        pub last_played_at: Option<DateTime<Local>>,
+}
+
+/// 一次手动修改会话的记录
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEdit {
+        pub id: String,
+        pub game_id: String,
+        pub action: SessionEditAction,
+        /// 修改前的会话（新建时为空）
+        #[sqlx(json)]
+        pub before: Vec<PlaySession>,
+        /// 修改后的会话（删除时为空）
+        #[sqlx(json)]
+        pub after: Vec<PlaySession>,
+        pub created_at: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, sqlx::Type)]
+#[serde(rename_all = "camelCase")]
+#[sqlx(rename_all = "camelCase")]
+pub enum SessionEditAction {
+        Create,
+        Update,
+        Split,
+        Merge,
+        Delete,
 }
 
 // ── 压缩包条目（转发 infra 类型） ─────────────────────────────────────────────
@@ -72,11 +326,11 @@ pub enum ResourceTarget {
 pub enum GameEvent {
         /// 游戏封面 / 背景图片需要下载
         GameResourceTask {
-                meta: GameMeta,
+                meta: Box<GameMeta>,
                 target: ResourceTarget,
         },
         /// 用户头像需要下载
-        UserResourceTask { meta: User },
+        UserResourceTask { meta: Box<User> },
 }
 
 impl MessageEvent for GameEvent {}
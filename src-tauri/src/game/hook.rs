@@ -0,0 +1,484 @@
+//! 游戏的启动前 / 退出后脚本
+//!
+//! 脚本按排序值依次通过系统 shell 执行，输出写入日志。
+//! 脚本可以通过环境变量拿到游戏信息：
+//! `YUMIHUB_GAME_ID` `YUMIHUB_GAME_NAME` `YUMIHUB_GAME_DIR` `YUMIHUB_EXE` `YUMIHUB_PROFILE_ID`，
+//! 退出后脚本另有本次游玩时长 `YUMIHUB_DURATION_MINUTES`。
+
+use std::{
+        io::Read,
+        path::{Path, PathBuf},
+        process::{Command, Stdio},
+        sync::mpsc,
+        time::{Duration, Instant},
+};
+
+use sqlx::SqlitePool;
+use tauri::async_runtime;
+use tauri_plugin_log::log::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+        error::AppError,
+        game::entity::{GameHook, GameMeta, HookFailure, HookRun, HookStage, LaunchProfile},
+        infra::process::kill_by_pid,
+};
+
+/// 日志中每路输出最多保留的字节数
+const MAX_OUTPUT: usize = 16 * 1024;
+/// 脚本进程结束后，等待输出读取完毕的时间（脚本拉起的后台进程可能一直占着输出管道）
+const OUTPUT_GRACE: Duration = Duration::from_secs(1);
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+const HOOK_SQL: &str = "SELECT id, game_id, name, stage, command, working_dir, timeout_secs, \
+     on_failure, is_enabled, sort_order FROM game_hooks";
+
+/// 脚本的运行环境
+#[derive(Clone)]
+pub struct HookContext {
+        game_dir: PathBuf,
+        env: Vec<(&'static str, String)>,
+}
+
+impl HookContext {
+        pub fn new(
+                game: &GameMeta,
+                profile: &LaunchProfile,
+        ) -> Self {
+                let exe = Path::new(&game.abs_path);
+                let game_dir = exe.parent().unwrap_or(exe).to_path_buf();
+                Self {
+                        env: vec![
+                                ("YUMIHUB_GAME_ID", game.id.clone()),
+                                ("YUMIHUB_GAME_NAME", game.name.clone()),
+                                ("YUMIHUB_GAME_DIR", game_dir.to_string_lossy().into_owned()),
+                                ("YUMIHUB_EXE", profile.exe_path.clone()),
+                                ("YUMIHUB_PROFILE_ID", profile.id.clone()),
+                        ],
+                        game_dir,
+                }
+        }
+
+        pub fn with_duration(
+                mut self,
+                minutes: i64,
+        ) -> Self {
+                self.env.push(("YUMIHUB_DURATION_MINUTES", minutes.to_string()));
+                self
+        }
+}
+
+/// 游戏的全部脚本
+pub async fn list(
+        pool: &SqlitePool,
+        game_id: &str,
+) -> Result<Vec<GameHook>, AppError> {
+        sqlx::query_as(&format!(
+                "{} WHERE game_id = ? ORDER BY stage, sort_order",
+                HOOK_SQL
+        ))
+        .bind(game_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// 整体替换游戏的脚本列表
+pub async fn replace(
+        pool: &SqlitePool,
+        game_id: &str,
+        hooks: Vec<GameHook>,
+) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        sqlx::query("DELETE FROM game_hooks WHERE game_id = ?")
+                .bind(game_id)
+                .execute(&mut *tx)
+                .await?;
+        for hook in hooks {
+                if hook.command.trim().is_empty() {
+                        return Err(AppError::Resolve(hook.name, "脚本命令不能为空".into()));
+                }
+                let id = if hook.id.is_empty() {
+                        Uuid::new_v4().to_string()
+                } else {
+                        hook.id
+                };
+                sqlx::query(
+                        "INSERT INTO game_hooks \
+             (id, game_id, name, stage, command, working_dir, timeout_secs, on_failure, \
+              is_enabled, sort_order) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                )
+                .bind(id)
+                .bind(game_id)
+                .bind(hook.name)
+                .bind(hook.stage)
+                .bind(hook.command)
+                .bind(hook.working_dir)
+                .bind(hook.timeout_secs.max(1))
+                .bind(hook.on_failure)
+                .bind(hook.is_enabled)
+                .bind(hook.sort_order)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+}
+
+/// 依次执行某一阶段已启用的脚本
+///
+/// 失败处理为 [`HookFailure::Abort`] 的启动前脚本失败时返回错误（调用方据此取消启动），
+/// 退出后脚本失败时跳过其余退出后脚本
+pub async fn run_stage(
+        pool: &SqlitePool,
+        game_id: &str,
+        stage: HookStage,
+        ctx: &HookContext,
+) -> Result<Vec<HookRun>, AppError> {
+        let hooks: Vec<GameHook> = sqlx::query_as(&format!(
+                "{} WHERE game_id = ? AND stage = ? AND is_enabled = 1 ORDER BY sort_order",
+                HOOK_SQL
+        ))
+        .bind(game_id)
+        .bind(stage)
+        .fetch_all(pool)
+        .await?;
+        let ctx = ctx.clone();
+        async_runtime::spawn_blocking(move || run_hooks(&hooks, stage, |hook| run_one(hook, &ctx)))
+                .await
+                .map_err(|e| AppError::Process(e.to_string()))?
+}
+
+/// 按排序值依次执行 `hooks` 中属于该阶段且已启用的脚本，`exec` 负责执行单个脚本
+fn run_hooks(
+        hooks: &[GameHook],
+        stage: HookStage,
+        mut exec: impl FnMut(&GameHook) -> HookRun,
+) -> Result<Vec<HookRun>, AppError> {
+        let mut selected: Vec<&GameHook> = hooks
+                .iter()
+                .filter(|hook| hook.is_enabled && hook.stage == stage)
+                .collect();
+        selected.sort_by_key(|hook| hook.sort_order);
+
+        let mut runs = Vec::new();
+        for hook in selected {
+                let run = exec(hook);
+                log_run(&run);
+                let failed = !run.success;
+                runs.push(run);
+
+                if failed && hook.on_failure == HookFailure::Abort {
+                        match stage {
+                                | HookStage::PreLaunch => {
+                                        return Err(AppError::Process(format!(
+                                                "启动前脚本「{}」失败，已取消启动",
+                                                hook.name
+                                        )));
+                                },
+                                | HookStage::PostExit => {
+                                        warn!(
+                                                "退出后脚本「{}」失败，跳过其余退出后脚本",
+                                                hook.name
+                                        );
+                                        break;
+                                },
+                        }
+                }
+        }
+        Ok(runs)
+}
+
+/// 执行单个脚本，超时后结束脚本进程
+fn run_one(
+        hook: &GameHook,
+        ctx: &HookContext,
+) -> HookRun {
+        let started = Instant::now();
+        let mut run = HookRun {
+                hook_id: hook.id.clone(),
+                name: hook.name.clone(),
+                stage: hook.stage,
+                success: false,
+                exit_code: None,
+                timed_out: false,
+                duration_ms: 0,
+                stdout: String::new(),
+                stderr: String::new(),
+        };
+
+        let dir = hook
+                .working_dir
+                .as_deref()
+                .filter(|d| !d.is_empty())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| ctx.game_dir.clone());
+        let mut command = shell(&hook.command);
+        command.current_dir(dir)
+                .envs(ctx.env.iter().map(|(k, v)| (k, v)))
+                .stdin(Stdio::null())
+                .stdout(Stdio::piped())
+                .stderr(Stdio::piped());
+        let mut child = match command.spawn() {
+                | Ok(child) => child,
+                | Err(e) => {
+                        run.stderr = format!("无法启动脚本: {}", e);
+                        return run;
+                },
+        };
+
+        let stdout = capture(child.stdout.take());
+        let stderr = capture(child.stderr.take());
+        let timeout = Duration::from_secs(hook.timeout_secs.max(1) as u64);
+        let status = loop {
+                match child.try_wait() {
+                        | Ok(Some(status)) => break Some(status),
+                        | Ok(None) if started.elapsed() >= timeout => {
+                                run.timed_out = true;
+                                kill_by_pid(child.id());
+                                let _ = child.kill();
+                                let _ = child.wait();
+                                break None;
+                        },
+                        | Ok(None) => std::thread::sleep(POLL_INTERVAL),
+                        | Err(e) => {
+                                run.stderr = format!("等待脚本结束失败: {}", e);
+                                break None;
+                        },
+                }
+        };
+
+        run.exit_code = status.and_then(|s| s.code());
+        run.success = status.is_some_and(|s| s.success());
+        run.duration_ms = started.elapsed().as_millis() as u64;
+        run.stdout = stdout.recv_timeout(OUTPUT_GRACE).unwrap_or_default();
+        let captured = stderr.recv_timeout(OUTPUT_GRACE).unwrap_or_default();
+        if run.stderr.is_empty() {
+                run.stderr = captured;
+        }
+        run
+}
+
+#[cfg(target_os = "windows")]
+fn shell(line: &str) -> Command {
+        use std::os::windows::process::CommandExt;
+
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        let mut command = Command::new("cmd");
+        command.arg("/C")
+                .raw_arg(line)
+                .creation_flags(CREATE_NO_WINDOW);
+        command
+}
+
+#[cfg(not(target_os = "windows"))]
+fn shell(line: &str) -> Command {
+        let mut command = Command::new("sh");
+        command.arg("-c").arg(line);
+        command
+}
+
+/// 在后台线程读取一路输出，读完后通过通道送回
+fn capture(stream: Option<impl Read + Send + 'static>) -> mpsc::Receiver<String> {
+        let (tx, rx) = mpsc::channel();
+        if let Some(mut stream) = stream {
+                std::thread::spawn(move || {
+                        let mut buf = Vec::new();
+                        let _ = stream.read_to_end(&mut buf);
+                        let _ = tx.send(truncate(String::from_utf8_lossy(&buf).trim_end()));
+                });
+        }
+        rx
+}
+
+fn truncate(text: &str) -> String {
+        if text.len() <= MAX_OUTPUT {
+                return text.to_string();
+        }
+        let mut end = MAX_OUTPUT;
+        while !text.is_char_boundary(end) {
+                end -= 1;
+        }
+        format!("{}\n…（输出过长，已截断）", &text[..end])
+}
+
+fn log_run(run: &HookRun) {
+        let stage = match run.stage {
+                | HookStage::PreLaunch => "启动前脚本",
+                | HookStage::PostExit => "退出后脚本",
+        };
+        let mut output = String::new();
+        for (label, text) in [("stdout", &run.stdout), ("stderr", &run.stderr)] {
+                if !text.is_empty() {
+                        output.push_str(&format!("\n[{}]\n{}", label, text));
+                }
+        }
+
+        if run.success {
+                info!(
+                        "{}「{}」完成，用时 {} ms{}",
+                        stage, run.name, run.duration_ms, output
+                );
+        } else if run.timed_out {
+                warn!(
+                        "{}「{}」超时（{} ms），已结束{}",
+                        stage, run.name, run.duration_ms, output
+                );
+        } else {
+                warn!(
+                        "{}「{}」失败，退出码 {:?}{}",
+                        stage, run.name, run.exit_code, output
+                );
+        }
+}
+
+#[cfg(test)]
+mod tests {
+        use super::{HookContext, run_hooks, run_one};
+        use crate::{
+                error::AppError,
+                game::entity::{
+                        GameHook, GameMeta, HookFailure, HookRun, HookStage, LaunchProfile,
+                },
+        };
+
+        fn hook(
+                name: &str,
+                stage: HookStage,
+                sort_order: i64,
+                on_failure: HookFailure,
+        ) -> GameHook {
+                GameHook {
+                        id: name.to_string(),
+                        game_id: "game".into(),
+                        name: name.to_string(),
+                        stage,
+                        command: String::new(),
+                        working_dir: None,
+                        timeout_secs: 5,
+                        on_failure,
+                        is_enabled: true,
+                        sort_order,
+                }
+        }
+
+        /// 不启动进程的脚本执行：记录执行顺序，名称以 `fail` 开头的脚本失败
+        fn stub(order: &mut Vec<String>) -> impl FnMut(&GameHook) -> HookRun + '_ {
+                move |hook| {
+                        order.push(hook.name.clone());
+                        HookRun {
+                                hook_id: hook.id.clone(),
+                                name: hook.name.clone(),
+                                stage: hook.stage,
+                                success: !hook.name.starts_with("fail"),
+                                exit_code: Some(0),
+                                timed_out: false,
+                                duration_ms: 0,
+                                stdout: String::new(),
+                                stderr: String::new(),
+                        }
+                }
+        }
+
+        #[test]
+        fn runs_enabled_hooks_of_the_stage_in_sort_order() {
+                let mut disabled = hook("disabled", HookStage::PreLaunch, 0, HookFailure::Warn);
+                disabled.is_enabled = false;
+                let hooks = vec![
+                        hook("third", HookStage::PreLaunch, 30, HookFailure::Warn),
+                        hook("post", HookStage::PostExit, 0, HookFailure::Warn),
+                        hook("first", HookStage::PreLaunch, 10, HookFailure::Warn),
+                        disabled,
+                        hook("second", HookStage::PreLaunch, 20, HookFailure::Warn),
+                ];
+                let mut order = Vec::new();
+                let runs = run_hooks(&hooks, HookStage::PreLaunch, stub(&mut order)).unwrap();
+                assert_eq!(runs.len(), 3);
+                assert_eq!(order, ["first", "second", "third"]);
+        }
+
+        #[test]
+        fn failed_pre_launch_hook_set_to_abort_cancels_launch() {
+                let hooks = vec![
+                        hook("first", HookStage::PreLaunch, 0, HookFailure::Warn),
+                        hook("fail", HookStage::PreLaunch, 1, HookFailure::Abort),
+                        hook("after", HookStage::PreLaunch, 2, HookFailure::Warn),
+                ];
+                let mut order = Vec::new();
+                let result = run_hooks(&hooks, HookStage::PreLaunch, stub(&mut order));
+                assert!(matches!(result, Err(AppError::Process(_))));
+                assert_eq!(order, ["first", "fail"]);
+        }
+
+        #[test]
+        fn failed_hook_set_to_warn_continues() {
+                let hooks = vec![
+                        hook("fail", HookStage::PreLaunch, 0, HookFailure::Warn),
+                        hook("after", HookStage::PreLaunch, 1, HookFailure::Warn),
+                ];
+                let mut order = Vec::new();
+                let runs = run_hooks(&hooks, HookStage::PreLaunch, stub(&mut order)).unwrap();
+                assert_eq!(order, ["fail", "after"]);
+                assert!(!runs[0].success && runs[1].success);
+        }
+
+        #[test]
+        fn failed_post_exit_hook_set_to_abort_skips_the_rest() {
+                let hooks = vec![
+                        hook("fail", HookStage::PostExit, 0, HookFailure::Abort),
+                        hook("after", HookStage::PostExit, 1, HookFailure::Warn),
+                ];
+                let mut order = Vec::new();
+                let runs = run_hooks(&hooks, HookStage::PostExit, stub(&mut order)).unwrap();
+                assert_eq!(runs.len(), 1);
+                assert_eq!(order, ["fail"]);
+        }
+
+        #[cfg(unix)]
+        fn context() -> HookContext {
+                let game = GameMeta {
+                        id: "game-1".into(),
+                        name: "Game".into(),
+                        abs_path: std::env::temp_dir()
+                                .join("game.exe")
+                                .to_string_lossy()
+                                .into(),
+                        ..Default::default()
+                };
+                let profile = LaunchProfile {
+                        id: "main".into(),
+                        exe_path: game.abs_path.clone(),
+                        ..Default::default()
+                };
+                HookContext::new(&game, &profile)
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn shell_hook_reports_exit_code_output_and_env() {
+                let mut ok = hook("env", HookStage::PreLaunch, 0, HookFailure::Abort);
+                ok.command = "echo $YUMIHUB_GAME_ID $YUMIHUB_PROFILE_ID".into();
+                let run = run_one(&ok, &context());
+                assert!(run.success);
+                assert_eq!(run.stdout, "game-1 main");
+
+                let mut failing = hook("exit", HookStage::PreLaunch, 0, HookFailure::Abort);
+                failing.command = "echo oops >&2; exit 3".into();
+                let run = run_one(&failing, &context());
+                assert!(!run.success);
+                assert_eq!(run.exit_code, Some(3));
+                assert_eq!(run.stderr, "oops");
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn shell_hook_is_killed_after_timeout() {
+                let mut slow = hook("slow", HookStage::PreLaunch, 0, HookFailure::Abort);
+                slow.command = "sleep 10".into();
+                slow.timeout_secs = 1;
+                let run = run_one(&slow, &context());
+                assert!(run.timed_out && !run.success);
+                assert_eq!(run.exit_code, None);
+        }
+}
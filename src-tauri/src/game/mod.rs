@@ -6,14 +6,27 @@ use std::{
 };
 
 use lazy_static::lazy_static;
+use tauri::AppHandle;
 
 use crate::game::entity::RunningGame;
 
 pub mod commands;
 pub mod entity;
+pub mod hook;
+pub mod monitor;
+pub mod profile;
+pub mod progress;
+pub mod runner;
+pub mod session;
+pub mod session_edit;
 
 lazy_static! {
     /// 当前正在运行的游戏，key 为游戏 ID
     pub static ref RUNNING_GAMES: Arc<Mutex<HashMap<String, RunningGame>>> =
         Arc::new(Mutex::new(HashMap::new()));
 }
+
+pub fn init(handle: &AppHandle) {
+        session::recover(handle);
+        monitor::start(handle);
+}
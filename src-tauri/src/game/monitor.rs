@@ -0,0 +1,141 @@
+//! 外部启动识别
+//!
+//! 开启 `detect_external_launch` 后每隔 [`SCAN_INTERVAL`] 扫描一次进程：
+//! 可执行文件位于某个游戏目录下、且该游戏不在监控中的进程，连续两次扫描都存在时
+//! 视为从本程序之外（如桌面快捷方式）启动，按 [`SessionSource::External`] 记录会话。
+//! 设置随配置实时生效，无需重启。
+//!
+//! [`SessionSource::External`]: crate::game::entity::SessionSource::External
+
+use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+        time::Duration,
+};
+
+use sqlx::SqlitePool;
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_log::log::{error, info};
+
+use crate::{
+        config::read_config,
+        error::AppError,
+        game::{RUNNING_GAMES, commands as gc},
+};
+
+/// 扫描间隔
+const SCAN_INTERVAL: Duration = Duration::from_secs(10);
+
+pub fn start(handle: &AppHandle) {
+        let Some(pool) = handle.try_state::<SqlitePool>() else {
+                error!("未能获取 SqlitePool，外部启动识别未启动");
+                return;
+        };
+        let pool = pool.inner().clone();
+
+        tauri::async_runtime::spawn(async move {
+                let mut sys = System::new();
+                // 上一次扫描发现的候选：游戏 ID → PID
+                let mut pending: HashMap<String, u32> = HashMap::new();
+
+                loop {
+                        tokio::time::sleep(SCAN_INTERVAL).await;
+
+                        let enabled = read_config()
+                                .map(|cfg| cfg.system.detect_external_launch)
+                                .unwrap_or_else(|e| {
+                                        error!("读取外部启动识别配置失败: {}", e);
+                                        false
+                                });
+                        if !enabled {
+                                pending.clear();
+                                continue;
+                        }
+
+                        let found = match scan(&pool, &mut sys).await {
+                                | Ok(found) => found,
+                                | Err(e) => {
+                                        error!("扫描外部启动的游戏失败: {}", e);
+                                        continue;
+                                },
+                        };
+                        for (game_id, (pid, game_dir)) in &found {
+                                if !pending.contains_key(game_id) {
+                                        continue;
+                                }
+                                match gc::attach_external(
+                                        pool.clone(),
+                                        game_id,
+                                        game_dir.clone(),
+                                        *pid,
+                                )
+                                .await
+                                {
+                                        | Ok(()) => info!(
+                                                "识别到从外部启动的游戏 {}（PID {}）",
+                                                game_id, pid
+                                        ),
+                                        | Err(e) => {
+                                                error!("记录外部启动的游戏 {} 失败: {}", game_id, e)
+                                        },
+                                }
+                        }
+                        pending = found
+                                .into_iter()
+                                .map(|(game_id, (pid, _))| (game_id, pid))
+                                .collect();
+                }
+        });
+
+        info!("外部启动识别已就绪");
+}
+
+/// 找出可执行文件位于游戏目录下、且未在监控中的游戏：游戏 ID → (PID, 游戏目录)
+async fn scan(
+        pool: &SqlitePool,
+        sys: &mut System,
+) -> Result<HashMap<String, (u32, PathBuf)>, AppError> {
+        let games: Vec<(String, String)> = sqlx::query_as("SELECT id, abs_path FROM games")
+                .fetch_all(pool)
+                .await?;
+        let running: Vec<String> = RUNNING_GAMES
+                .lock()
+                .map_err(|e| AppError::Lock(e.to_string()))?
+                .keys()
+                .cloned()
+                .collect();
+        let targets: Vec<(String, PathBuf)> = games
+                .into_iter()
+                .filter(|(id, _)| !running.contains(id))
+                .filter_map(|(id, abs_path)| {
+                        Path::new(&abs_path)
+                                .parent()
+                                .filter(|dir| !dir.as_os_str().is_empty())
+                                .map(|dir| (id, dir.to_path_buf()))
+                })
+                .collect();
+        if targets.is_empty() {
+                return Ok(HashMap::new());
+        }
+
+        sys.refresh_processes_specifics(
+                ProcessesToUpdate::All,
+                true,
+                ProcessRefreshKind::nothing().with_exe(UpdateKind::OnlyIfNotSet),
+        );
+        let own_pid = std::process::id();
+        let mut found = HashMap::new();
+        for (pid, process) in sys.processes() {
+                if pid.as_u32() == own_pid {
+                        continue;
+                }
+                if let Some((game_id, game_dir)) = targets.iter().find(|(_, dir)| {
+                        gc::is_game_process(process, std::slice::from_ref(dir), false)
+                }) {
+                        found.entry(game_id.clone())
+                                .or_insert_with(|| (pid.as_u32(), game_dir.clone()));
+                }
+        }
+        Ok(found)
+}
@@ -0,0 +1,210 @@
+//! 游戏启动配置
+//!
+//! 「主程序」和「设置程序」由游戏的 `abs_path` / `config_path` 生成，随游戏路径变化，不写入数据库；
+//! 其余配置（如打了补丁的英文版、调试模式）存放在 `game_launch_profiles` 表中。
+
+use std::{
+        path::{Path, PathBuf},
+        process::Command,
+};
+
+use sqlx::{SqlitePool, types::Json};
+use uuid::Uuid;
+
+use crate::{
+        error::AppError,
+        game::{
+                entity::{CONFIG_PROFILE_ID, GameMeta, LaunchProfile, MAIN_PROFILE_ID},
+                runner,
+        },
+};
+
+const PROFILE_SQL: &str = "SELECT id, game_id, name, exe_path, args, working_dir, env, runner, \
+     is_default, sort_order FROM game_launch_profiles";
+
+/// 游戏的全部启动配置：内置配置在前，其余按排序值与名称
+pub async fn list(
+        pool: &SqlitePool,
+        game: &GameMeta,
+) -> Result<Vec<LaunchProfile>, AppError> {
+        let stored: Vec<LaunchProfile> = sqlx::query_as(&format!(
+                "{} WHERE game_id = ? ORDER BY sort_order, name",
+                PROFILE_SQL
+        ))
+        .bind(&game.id)
+        .fetch_all(pool)
+        .await?;
+
+        let has_default = stored.iter().any(|p| p.is_default);
+        let mut profiles = builtin(game);
+        if let Some(main) = profiles.first_mut() {
+                main.is_default = !has_default;
+        }
+        profiles.extend(stored);
+        Ok(profiles)
+}
+
+/// 按 ID 找到启动配置；未指定时使用默认配置
+pub async fn resolve(
+        pool: &SqlitePool,
+        game: &GameMeta,
+        profile_id: Option<&str>,
+) -> Result<LaunchProfile, AppError> {
+        let profiles = list(pool, game).await?;
+        let found = match profile_id {
+                | Some(id) => profiles.into_iter().find(|p| p.id == id),
+                | None => profiles.into_iter().find(|p| p.is_default),
+        };
+        found.ok_or_else(|| {
+                AppError::Resolve(
+                        profile_id.unwrap_or_default().to_string(),
+                        format!("游戏 {} 没有该启动配置", game.name),
+                )
+        })
+}
+
+/// 新建或修改启动配置，返回保存后的配置
+pub async fn save(
+        pool: &SqlitePool,
+        mut profile: LaunchProfile,
+) -> Result<LaunchProfile, AppError> {
+        if is_builtin(&profile.id) {
+                return Err(AppError::Resolve(profile.id, "内置启动配置不能修改".into()));
+        }
+        if profile.name.trim().is_empty() || profile.exe_path.trim().is_empty() {
+                return Err(AppError::Resolve(
+                        profile.name,
+                        "启动配置的名称和可执行文件不能为空".into(),
+                ));
+        }
+        if profile.id.is_empty() {
+                profile.id = Uuid::new_v4().to_string();
+        }
+        profile.builtin = false;
+
+        let mut tx = pool.begin().await?;
+        if profile.is_default {
+                clear_default(&mut tx, &profile.game_id).await?;
+        }
+        sqlx::query(
+                "INSERT INTO game_launch_profiles \
+         (id, game_id, name, exe_path, args, working_dir, env, runner, is_default, sort_order) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET name=excluded.name, exe_path=excluded.exe_path, \
+         args=excluded.args, working_dir=excluded.working_dir, env=excluded.env, \
+         runner=excluded.runner, is_default=excluded.is_default, sort_order=excluded.sort_order",
+        )
+        .bind(&profile.id)
+        .bind(&profile.game_id)
+        .bind(&profile.name)
+        .bind(&profile.exe_path)
+        .bind(Json(&profile.args))
+        .bind(&profile.working_dir)
+        .bind(Json(&profile.env))
+        .bind(Json(&profile.runner))
+        .bind(profile.is_default)
+        .bind(profile.sort_order)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(profile)
+}
+
+pub async fn delete(
+        pool: &SqlitePool,
+        id: &str,
+) -> Result<(), AppError> {
+        if is_builtin(id) {
+                return Err(AppError::Resolve(
+                        id.to_string(),
+                        "内置启动配置不能删除".into(),
+                ));
+        }
+        sqlx::query("DELETE FROM game_launch_profiles WHERE id = ?")
+                .bind(id)
+                .execute(pool)
+                .await?;
+        Ok(())
+}
+
+/// 设置默认启动配置；`None` 或内置的主程序表示回到主程序
+pub async fn set_default(
+        pool: &SqlitePool,
+        game_id: &str,
+        profile_id: Option<&str>,
+) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        clear_default(&mut tx, game_id).await?;
+        if let Some(id) = profile_id.filter(|id| *id != MAIN_PROFILE_ID) {
+                if id == CONFIG_PROFILE_ID {
+                        return Err(AppError::Resolve(
+                                id.to_string(),
+                                "设置程序不能作为默认启动配置".into(),
+                        ));
+                }
+                let updated = sqlx::query(
+                        "UPDATE game_launch_profiles SET is_default = 1 WHERE id = ? AND game_id = ?",
+                )
+                .bind(id)
+                .bind(game_id)
+                .execute(&mut *tx)
+                .await?;
+                if updated.rows_affected() == 0 {
+                        return Err(AppError::Resolve(id.to_string(), "启动配置不存在".into()));
+                }
+        }
+        tx.commit().await?;
+        Ok(())
+}
+
+/// 按启动配置组装进程命令（尚未启动）
+pub fn command(profile: &LaunchProfile) -> Result<Command, AppError> {
+        let invocation = runner::invocation(profile)?;
+        let mut command = Command::new(&invocation.program);
+        command.args(&invocation.args)
+                .current_dir(working_dir(profile))
+                .envs(&invocation.env);
+        Ok(command)
+}
+
+/// 进程的工作目录：未设置时为可执行文件所在目录
+pub fn working_dir(profile: &LaunchProfile) -> PathBuf {
+        match profile.working_dir.as_deref().filter(|d| !d.is_empty()) {
+                | Some(dir) => PathBuf::from(dir),
+                | None => {
+                        let exe = Path::new(&profile.exe_path);
+                        exe.parent().unwrap_or(exe).to_path_buf()
+                },
+        }
+}
+
+fn builtin(game: &GameMeta) -> Vec<LaunchProfile> {
+        let profile = |id: &str, name: &str, exe: &str| LaunchProfile {
+                id: id.to_string(),
+                game_id: game.id.clone(),
+                name: name.to_string(),
+                exe_path: exe.to_string(),
+                builtin: true,
+                ..Default::default()
+        };
+        let mut profiles = vec![profile(MAIN_PROFILE_ID, "主程序", &game.abs_path)];
+        if let Some(config) = game.config_path.as_deref().filter(|p| !p.is_empty()) {
+                profiles.push(profile(CONFIG_PROFILE_ID, "设置程序", config));
+        }
+        profiles
+}
+
+fn is_builtin(id: &str) -> bool {
+        id == MAIN_PROFILE_ID || id == CONFIG_PROFILE_ID
+}
+
+async fn clear_default(
+        tx: &mut sqlx::SqliteConnection,
+        game_id: &str,
+) -> Result<(), AppError> {
+        sqlx::query("UPDATE game_launch_profiles SET is_default = 0 WHERE game_id = ?")
+                .bind(game_id)
+                .execute(tx)
+                .await?;
+        Ok(())
+}
@@ -0,0 +1,107 @@
+//! 按档案的游戏进度
+//!
+//! 游戏本身（路径、元数据）由所有用户档案共用，存放在 `games` 表；
+//! 通关标记、通关时间、累计游玩时长与最后游玩时间按档案存放在 `game_progress` 表。
+//! 读取游戏时按档案连接进度，没有进度记录的游戏视为未游玩。
+
+use chrono::{DateTime, Local};
+use sqlx::{SqliteConnection, SqlitePool};
+
+use crate::{error::AppError, game::entity::GameMeta};
+
+/// 带进度的游戏，第一个参数为档案 ID；可以接着拼 `WHERE` / `ORDER BY`
+pub const GAME_SQL: &str = "SELECT g.id, g.name, g.abs_path, g.is_displayed, g.cover, \
+     g.background, g.description, g.developer, g.local_cover, g.local_background, \
+     g.save_data_path, g.save_locations, g.backup_data_path, g.length, g.size, g.engine, \
+     g.config_path, COALESCE(p.is_passed, 0) AS is_passed, COALESCE(p.play_time, 0) AS play_time, \
+     p.last_played_at FROM games g \
+     LEFT JOIN game_progress p ON p.game_id = g.id AND p.account_id = ?";
+
+/// 档案视角下的全部游戏
+pub async fn list(
+        pool: &SqlitePool,
+        account_id: &str,
+) -> Result<Vec<GameMeta>, AppError> {
+        sqlx::query_as(GAME_SQL)
+                .bind(account_id)
+                .fetch_all(pool)
+                .await
+                .map_err(AppError::from)
+}
+
+/// 档案视角下的一个游戏
+pub async fn find(
+        pool: &SqlitePool,
+        account_id: &str,
+        game_id: &str,
+) -> Result<Option<GameMeta>, AppError> {
+        sqlx::query_as(&format!("{} WHERE g.id = ?", GAME_SQL))
+                .bind(account_id)
+                .bind(game_id)
+                .fetch_optional(pool)
+                .await
+                .map_err(AppError::from)
+}
+
+/// 档案最后游玩的游戏
+pub async fn last_played(
+        pool: &SqlitePool,
+        account_id: &str,
+) -> Result<Option<GameMeta>, AppError> {
+        sqlx::query_as(&format!(
+                "{} WHERE p.last_played_at IS NOT NULL ORDER BY p.last_played_at DESC LIMIT 1",
+                GAME_SQL
+        ))
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// 写入游戏信息中的通关标记；标记通关时记录通关时间（已记录的保留），取消通关时清空
+///
+/// 游玩时长与最后游玩时间只由会话累计，手动调整走 [`session_edit`](crate::game::session_edit)，
+/// 这里不写入，以免客户端提交的旧值覆盖
+pub async fn save(
+        conn: &mut SqliteConnection,
+        account_id: &str,
+        game: &GameMeta,
+) -> Result<(), AppError> {
+        sqlx::query(
+                "INSERT INTO game_progress (account_id, game_id, is_passed, passed_at) \
+         VALUES (?1, ?2, ?3, CASE WHEN ?3 THEN ?4 ELSE NULL END) \
+         ON CONFLICT(account_id, game_id) DO UPDATE SET is_passed=excluded.is_passed, \
+         passed_at=CASE WHEN excluded.is_passed THEN COALESCE(passed_at, excluded.passed_at) \
+                   ELSE NULL END",
+        )
+        .bind(account_id)
+        .bind(&game.id)
+        .bind(game.is_passed)
+        .bind(Local::now())
+        .execute(conn)
+        .await?;
+        Ok(())
+}
+
+/// 累计一次会话的时长
+pub async fn add_play(
+        conn: &mut SqliteConnection,
+        account_id: &str,
+        game_id: &str,
+        minutes: i64,
+        played_at: DateTime<Local>,
+) -> Result<(), AppError> {
+        sqlx::query(
+                "INSERT INTO game_progress (account_id, game_id, play_time, last_played_at) \
+         VALUES (?, ?, ?, ?) \
+         ON CONFLICT(account_id, game_id) DO UPDATE SET play_time = play_time + excluded.play_time, \
+         last_played_at = MAX(COALESCE(last_played_at, ''), excluded.last_played_at)",
+        )
+        .bind(account_id)
+        .bind(game_id)
+        .bind(minutes)
+        .bind(played_at)
+        .execute(conn)
+        .await?;
+        Ok(())
+}
@@ -0,0 +1,323 @@
+//! 运行方式
+//!
+//! 按启动配置的 [`Runner`] 把游戏程序包装在转区工具、Wine 或 Proton 中运行。
+//! 每种运行方式带有一组预设环境变量（如 Wine 下的 `LANG=ja_JP.UTF-8`），
+//! 启动配置中的同名环境变量优先。
+//! Wine 前缀与 Proton 的兼容数据目录未指定时，每个游戏在应用数据目录下各用一个。
+
+use std::{
+        collections::BTreeMap,
+        ffi::OsString,
+        path::{Path, PathBuf},
+};
+
+use directories::BaseDirs;
+
+use crate::{
+        config::CONFIG_PATH,
+        error::AppError,
+        game::entity::{LaunchProfile, Runner, RunnerPreset},
+};
+
+const NATIVE_ENV: &[(&str, &str)] = &[];
+const LOCALE_EMULATOR_ENV: &[(&str, &str)] = &[];
+const WINE_ENV: &[(&str, &str)] = &[
+        ("LANG", "ja_JP.UTF-8"),
+        ("LC_ALL", "ja_JP.UTF-8"),
+        ("WINEDEBUG", "-all"),
+];
+const PROTON_ENV: &[(&str, &str)] = &[("LANG", "ja_JP.UTF-8"), ("LC_ALL", "ja_JP.UTF-8")];
+const CUSTOM_ENV: &[(&str, &str)] = &[];
+
+/// 兼容层自带的辅助进程，不算作游戏进程（游戏退出后它们还会短暂存留）
+pub const HELPER_PROCESSES: &[&str] = &[
+        "wineserver",
+        "wineboot.exe",
+        "services.exe",
+        "winedevice.exe",
+        "plugplay.exe",
+        "explorer.exe",
+        "rpcss.exe",
+        "svchost.exe",
+        "conhost.exe",
+        "tabtip.exe",
+];
+
+/// 组装好的命令行
+pub struct Invocation {
+        pub program: OsString,
+        pub args: Vec<OsString>,
+        /// 预设与启动配置合并后的环境变量
+        pub env: BTreeMap<String, String>,
+}
+
+/// 全部运行方式的预设环境变量
+pub fn presets() -> Vec<RunnerPreset> {
+        [
+                ("native", "直接运行", NATIVE_ENV),
+                ("localeEmulator", "转区工具", LOCALE_EMULATOR_ENV),
+                ("wine", "Wine", WINE_ENV),
+                ("proton", "Proton", PROTON_ENV),
+                ("custom", "自定义命令", CUSTOM_ENV),
+        ]
+        .into_iter()
+        .map(|(kind, label, env)| RunnerPreset {
+                kind,
+                label,
+                env: env.iter().copied().collect(),
+        })
+        .collect()
+}
+
+/// 按启动配置组装命令行；Wine 前缀等目录不存在时创建
+pub fn invocation(profile: &LaunchProfile) -> Result<Invocation, AppError> {
+        let exe = OsString::from(&profile.exe_path);
+        let mut env: BTreeMap<String, String> = preset_env(profile.runner.as_ref())
+                .iter()
+                .map(|(k, v)| (k.to_string(), v.to_string()))
+                .collect();
+
+        let (program, mut args) = match &profile.runner {
+                | None => (exe, Vec::new()),
+                | Some(Runner::LocaleEmulator { command, args })
+                | Some(Runner::Custom { command, args }) => {
+                        (OsString::from(command), wrap_args(args, &profile.exe_path))
+                },
+                | Some(Runner::Wine { binary, prefix }) => {
+                        let prefix = match prefix.as_deref().filter(|p| !p.is_empty()) {
+                                | Some(prefix) => PathBuf::from(prefix),
+                                | None => game_data_dir("wine-prefixes", &profile.game_id)?,
+                        };
+                        // wine 会自行初始化前缀，但上级目录必须存在
+                        if let Some(parent) = prefix.parent() {
+                                std::fs::create_dir_all(parent)?;
+                        }
+                        env.insert("WINEPREFIX".into(), path_string(&prefix));
+                        let binary = binary
+                                .as_deref()
+                                .filter(|b| !b.is_empty())
+                                .unwrap_or("wine");
+                        (OsString::from(binary), vec![exe])
+                },
+                | Some(Runner::Proton {
+                        proton_dir,
+                        compat_data,
+                }) => {
+                        let compat_data = match compat_data.as_deref().filter(|p| !p.is_empty()) {
+                                | Some(dir) => PathBuf::from(dir),
+                                | None => game_data_dir("proton-compat", &profile.game_id)?,
+                        };
+                        std::fs::create_dir_all(&compat_data)?;
+                        env.insert("STEAM_COMPAT_DATA_PATH".into(), path_string(&compat_data));
+                        if let Some(base) = BaseDirs::new() {
+                                env.insert(
+                                        "STEAM_COMPAT_CLIENT_INSTALL_PATH".into(),
+                                        path_string(&base.home_dir().join(".steam/steam")),
+                                );
+                        }
+                        let script = Path::new(proton_dir).join("proton");
+                        (script.into_os_string(), vec!["run".into(), exe])
+                },
+        };
+        args.extend(profile.args.iter().map(OsString::from));
+        env.extend(profile.env.clone());
+
+        Ok(Invocation { program, args, env })
+}
+
+fn preset_env(runner: Option<&Runner>) -> &'static [(&'static str, &'static str)] {
+        match runner {
+                | None => NATIVE_ENV,
+                | Some(Runner::LocaleEmulator { .. }) => LOCALE_EMULATOR_ENV,
+                | Some(Runner::Wine { .. }) => WINE_ENV,
+                | Some(Runner::Proton { .. }) => PROTON_ENV,
+                | Some(Runner::Custom { .. }) => CUSTOM_ENV,
+        }
+}
+
+/// 替换包装命令参数中的 `{exe}`；没有占位符时把游戏程序接在最后
+fn wrap_args(
+        args: &[String],
+        exe: &str,
+) -> Vec<OsString> {
+        let mut out: Vec<OsString> = args
+                .iter()
+                .map(|arg| OsString::from(arg.replace("{exe}", exe)))
+                .collect();
+        if !args.iter().any(|arg| arg.contains("{exe}")) {
+                out.push(exe.into());
+        }
+        out
+}
+
+/// 应用数据目录下某个游戏专用的目录
+fn game_data_dir(
+        kind: &str,
+        game_id: &str,
+) -> Result<PathBuf, AppError> {
+        CONFIG_PATH
+                .get()
+                .and_then(|config| config.parent())
+                .map(|dir| dir.join(kind).join(game_id))
+                .ok_or_else(|| AppError::Generic("应用数据目录尚未初始化".into()))
+}
+
+fn path_string(path: &Path) -> String {
+        path.to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+        use std::{
+                collections::BTreeMap,
+                ffi::OsString,
+                path::{Path, PathBuf},
+        };
+
+        use super::invocation;
+        use crate::{
+                game::entity::{LaunchProfile, Runner},
+                infra::tempdir::TempDir,
+        };
+
+        const EXE: &str = "/games/アトリエ/game.exe";
+
+        fn profile(runner: Option<Runner>) -> LaunchProfile {
+                LaunchProfile {
+                        id: "main".into(),
+                        game_id: "game-1".into(),
+                        exe_path: EXE.into(),
+                        args: vec!["-windowed".into()],
+                        runner,
+                        ..Default::default()
+                }
+        }
+
+        fn argv(args: &[OsString]) -> Vec<&str> {
+                args.iter().map(|arg| arg.to_str().unwrap()).collect()
+        }
+
+        #[test]
+        fn native_runs_exe_directly_with_profile_env() {
+                let mut native = profile(None);
+                native.env = BTreeMap::from([("FOO".into(), "bar".into())]);
+                let inv = invocation(&native).unwrap();
+                assert_eq!(inv.program, EXE);
+                assert_eq!(argv(&inv.args), ["-windowed"]);
+                assert_eq!(inv.env, native.env);
+        }
+
+        #[test]
+        fn locale_emulator_substitutes_exe_placeholder() {
+                let inv = invocation(&profile(Some(Runner::LocaleEmulator {
+                        command: "LEProc.exe".into(),
+                        args: vec!["-run".into(), "{exe}".into()],
+                })))
+                .unwrap();
+                assert_eq!(inv.program, "LEProc.exe");
+                assert_eq!(argv(&inv.args), ["-run", EXE, "-windowed"]);
+                assert!(inv.env.is_empty());
+        }
+
+        #[test]
+        fn custom_without_placeholder_appends_exe() {
+                let inv = invocation(&profile(Some(Runner::Custom {
+                        command: "gamemoderun".into(),
+                        args: vec!["--flag".into()],
+                })))
+                .unwrap();
+                assert_eq!(inv.program, "gamemoderun");
+                assert_eq!(argv(&inv.args), ["--flag", EXE, "-windowed"]);
+        }
+
+        #[test]
+        fn wine_sets_prefix_and_preset_env() {
+                let dir = TempDir::new();
+                let prefix = dir.path().join("prefixes").join("game-1");
+                let mut wine = profile(Some(Runner::Wine {
+                        binary: None,
+                        prefix: Some(prefix.to_string_lossy().into()),
+                }));
+                wine.env = BTreeMap::from([("LANG".into(), "zh_CN.UTF-8".into())]);
+                let inv = invocation(&wine).unwrap();
+
+                assert_eq!(inv.program, "wine");
+                assert_eq!(argv(&inv.args), [EXE, "-windowed"]);
+                assert_eq!(inv.env["WINEPREFIX"], prefix.to_string_lossy());
+                assert_eq!(inv.env["WINEDEBUG"], "-all");
+                assert_eq!(inv.env["LC_ALL"], "ja_JP.UTF-8");
+                // 启动配置中的同名变量优先于预设
+                assert_eq!(inv.env["LANG"], "zh_CN.UTF-8");
+                // 前缀由 wine 初始化，只需创建上级目录
+                assert!(prefix.parent().unwrap().is_dir());
+                assert!(!prefix.exists());
+        }
+
+        #[test]
+        fn wine_uses_custom_binary() {
+                let dir = TempDir::new();
+                let inv = invocation(&profile(Some(Runner::Wine {
+                        binary: Some("/opt/wine-staging/bin/wine64".into()),
+                        prefix: Some(dir.path().to_string_lossy().into()),
+                })))
+                .unwrap();
+                assert_eq!(inv.program, "/opt/wine-staging/bin/wine64");
+        }
+
+        #[test]
+        fn proton_runs_script_and_creates_compat_data() {
+                let dir = TempDir::new();
+                let compat = dir.path().join("compat");
+                let inv = invocation(&profile(Some(Runner::Proton {
+                        proton_dir: "/opt/proton".into(),
+                        compat_data: Some(compat.to_string_lossy().into()),
+                })))
+                .unwrap();
+
+                assert_eq!(
+                        PathBuf::from(&inv.program),
+                        Path::new("/opt/proton").join("proton")
+                );
+                assert_eq!(argv(&inv.args), ["run", EXE, "-windowed"]);
+                assert_eq!(inv.env["STEAM_COMPAT_DATA_PATH"], compat.to_string_lossy());
+                assert_eq!(inv.env["LANG"], "ja_JP.UTF-8");
+                assert!(compat.is_dir());
+        }
+
+        /// 用打印参数与环境变量的脚本代替 wine，检查实际启动的进程收到的内容
+        #[cfg(unix)]
+        #[test]
+        fn stub_runner_receives_argv_and_env() {
+                use std::os::unix::fs::PermissionsExt;
+
+                let dir = TempDir::new();
+                let stub = dir.path().join("wine");
+                std::fs::write(
+                        &stub,
+                        "#!/bin/sh\necho \"$@\"\necho \"$LANG $WINEPREFIX\"\n",
+                )
+                .unwrap();
+                std::fs::set_permissions(&stub, std::fs::Permissions::from_mode(0o755)).unwrap();
+                let prefix = dir.path().join("prefix");
+
+                let mut wine = profile(Some(Runner::Wine {
+                        binary: Some(stub.to_string_lossy().into()),
+                        prefix: Some(prefix.to_string_lossy().into()),
+                }));
+                wine.working_dir = Some(dir.path().to_string_lossy().into());
+                let output = crate::game::profile::command(&wine)
+                        .unwrap()
+                        .output()
+                        .unwrap();
+
+                assert!(output.status.success());
+                let stdout = String::from_utf8(output.stdout).unwrap();
+                assert_eq!(
+                        stdout.lines().collect::<Vec<_>>(),
+                        [
+                                format!("{} -windowed", EXE),
+                                format!("ja_JP.UTF-8 {}", prefix.display())
+                        ]
+                );
+        }
+}
@@ -0,0 +1,271 @@
+//! 游玩会话的记录
+//!
+//! 启动游戏时先写入一条进行中的会话（`is_open = 1`），监控期间每隔 [`HEARTBEAT_INTERVAL`]
+//! 写入心跳和已统计的时长，游戏结束时关闭会话并累计到游戏的游玩时长。
+//! 会话记在开始时的用户档案下，中途切换档案不影响它的归属。
+//! 程序崩溃或关机导致监控中断时，下次启动由 [`recover`] 处理遗留的会话：
+//! 游戏进程仍在运行的重新接上监控，否则按最后一次心跳结束。
+
+use std::time::Duration;
+
+use chrono::{DateTime, Local};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_log::log::{error, info, warn};
+use uuid::Uuid;
+
+use crate::{
+        config::{entity::PlayTimeMode, read_config},
+        error::AppError,
+        game::{
+                commands as gc,
+                entity::{GameMeta, LaunchProfile, SessionSource},
+                profile, progress,
+        },
+        user::profile::active_id,
+};
+
+/// 心跳间隔，也是程序意外退出时最多少记的时长
+pub const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(30);
+
+/// 一次会话中有效游玩与离开的时间
+#[derive(Clone, Copy, Default)]
+pub struct SessionActivity {
+        pub active: Duration,
+        pub idle: Duration,
+}
+
+impl SessionActivity {
+        /// 计入游玩时长的分钟数（四舍五入）：按 `play_time_mode` 取有效游玩时间或总时长
+        pub fn counted_minutes(
+                &self,
+                started_at: DateTime<Local>,
+                ended_at: DateTime<Local>,
+        ) -> i64 {
+                let mode = read_config()
+                        .map(|cfg| cfg.system.play_time_mode)
+                        .unwrap_or_else(|e| {
+                                error!("读取游玩时长计算方式失败: {}", e);
+                                PlayTimeMode::default()
+                        });
+                let secs = match mode {
+                        | PlayTimeMode::Active => self.active.as_secs() as i64,
+                        | PlayTimeMode::Total => (ended_at - started_at).num_seconds().max(0),
+                };
+                (secs + 30) / 60
+        }
+}
+
+/// 为当前档案写入一条进行中的会话，返回会话 ID
+pub async fn open(
+        pool: &SqlitePool,
+        game_id: &str,
+        profile_id: Option<&str>,
+        source: SessionSource,
+        started_at: DateTime<Local>,
+) -> Result<String, AppError> {
+        let id = Uuid::new_v4().to_string();
+        sqlx::query(
+                "INSERT INTO game_play_sessions \
+         (id, game_id, play_date, duration_minutes, last_played_at, profile_id, \
+          active_seconds, idle_seconds, is_open, heartbeat_at, source, account_id) \
+         VALUES (?, ?, ?, 0, ?, ?, 0, 0, 1, ?, ?, ?)",
+        )
+        .bind(&id)
+        .bind(game_id)
+        .bind(started_at)
+        .bind(started_at)
+        .bind(profile_id)
+        .bind(started_at)
+        .bind(source)
+        .bind(active_id()?)
+        .execute(pool)
+        .await?;
+        Ok(id)
+}
+
+/// 删除尚未开始的会话（游戏进程没能启动）
+pub async fn discard(
+        pool: &SqlitePool,
+        id: &str,
+) -> Result<(), AppError> {
+        sqlx::query("DELETE FROM game_play_sessions WHERE id = ? AND is_open = 1")
+                .bind(id)
+                .execute(pool)
+                .await?;
+        Ok(())
+}
+
+/// 写入心跳与目前为止统计的时长
+pub async fn heartbeat(
+        pool: &SqlitePool,
+        id: &str,
+        activity: &SessionActivity,
+) -> Result<(), AppError> {
+        sqlx::query(
+                "UPDATE game_play_sessions SET heartbeat_at = ?, active_seconds = ?, idle_seconds = ? \
+         WHERE id = ? AND is_open = 1",
+        )
+        .bind(Local::now())
+        .bind(activity.active.as_secs() as i64)
+        .bind(activity.idle.as_secs() as i64)
+        .bind(id)
+        .execute(pool)
+        .await?;
+        Ok(())
+}
+
+/// 结束会话，并把时长累计到会话所属档案的游戏进度；会话已结束时什么也不做
+pub async fn close(
+        pool: &SqlitePool,
+        id: &str,
+        game_id: &str,
+        activity: &SessionActivity,
+        duration_minutes: i64,
+        ended_at: DateTime<Local>,
+) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let closed: Option<Option<String>> = sqlx::query_scalar(
+                "UPDATE game_play_sessions SET is_open = 0, duration_minutes = ?, \
+         last_played_at = ?, heartbeat_at = ?, active_seconds = ?, idle_seconds = ? \
+         WHERE id = ? AND is_open = 1 RETURNING account_id",
+        )
+        .bind(duration_minutes)
+        .bind(ended_at)
+        .bind(ended_at)
+        .bind(activity.active.as_secs() as i64)
+        .bind(activity.idle.as_secs() as i64)
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(account_id) = closed else {
+                return Ok(());
+        };
+        if let Some(account_id) = account_id {
+                progress::add_play(&mut tx, &account_id, game_id, duration_minutes, ended_at)
+                        .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+}
+
+/// 启动时处理上次运行遗留的进行中会话
+pub fn recover(handle: &AppHandle) {
+        let Some(pool) = handle.try_state::<SqlitePool>() else {
+                error!("未能获取 SqlitePool，未处理遗留的游玩会话");
+                return;
+        };
+        let pool = pool.inner().clone();
+
+        tauri::async_runtime::spawn(async move {
+                if let Err(e) = recover_open_sessions(&pool).await {
+                        error!("处理遗留的游玩会话失败: {}", e);
+                }
+        });
+}
+
+#[derive(sqlx::FromRow)]
+struct OpenSession {
+        id: String,
+        game_id: String,
+        play_date: DateTime<Local>,
+        account_id: Option<String>,
+        profile_id: Option<String>,
+        active_seconds: Option<i64>,
+        idle_seconds: Option<i64>,
+        heartbeat_at: Option<DateTime<Local>>,
+        source: SessionSource,
+}
+
+async fn recover_open_sessions(pool: &SqlitePool) -> Result<(), AppError> {
+        // 新的在前：同一游戏有多条遗留会话时只有最新一条可能接上监控
+        let sessions: Vec<OpenSession> = sqlx::query_as(
+                "SELECT id, game_id, play_date, account_id, profile_id, active_seconds, \
+         idle_seconds, heartbeat_at, source FROM game_play_sessions WHERE is_open = 1 ORDER BY play_date DESC",
+        )
+        .fetch_all(pool)
+        .await?;
+
+        for session in sessions {
+                let activity = SessionActivity {
+                        active: seconds(session.active_seconds),
+                        idle: seconds(session.idle_seconds),
+                };
+                let account_id = session.account_id.clone().unwrap_or_default();
+                // 查不到游戏或启动配置时不再尝试接上监控，直接按最后一次心跳结束，
+                // 避免一条会话出错导致其后的会话一直停留在进行中
+                if let Some((game, launch_profile)) =
+                        reattach_target(pool, &account_id, &session).await
+                {
+                        match gc::reattach(
+                                pool.clone(),
+                                &game,
+                                &launch_profile,
+                                session.id.clone(),
+                                session.play_date,
+                                session.source,
+                                activity,
+                        )
+                        .await
+                        {
+                                | Ok(true) => {
+                                        info!("游戏 {} 仍在运行，已恢复会话监控", game.name);
+                                        continue;
+                                },
+                                | Ok(false) => {},
+                                | Err(e) => warn!("恢复游戏 {} 的会话监控失败: {}", game.name, e),
+                        }
+                }
+
+                let ended_at = session.heartbeat_at.unwrap_or(session.play_date);
+                let minutes = activity.counted_minutes(session.play_date, ended_at);
+                close(
+                        pool,
+                        &session.id,
+                        &session.game_id,
+                        &activity,
+                        minutes,
+                        ended_at,
+                )
+                .await?;
+                info!(
+                        "游戏 {} 的会话未正常结束，已按最后一次心跳（{}）记录 {} 分钟",
+                        session.game_id,
+                        ended_at.format("%Y-%m-%d %H:%M:%S"),
+                        minutes
+                );
+        }
+        Ok(())
+}
+
+/// 遗留会话对应的游戏与启动配置；出错时记录日志并返回 `None`
+async fn reattach_target(
+        pool: &SqlitePool,
+        account_id: &str,
+        session: &OpenSession,
+) -> Option<(GameMeta, LaunchProfile)> {
+        let game = match progress::find(pool, account_id, &session.game_id).await {
+                | Ok(game) => game?,
+                | Err(e) => {
+                        warn!("读取会话 {} 的游戏失败: {}", session.id, e);
+                        return None;
+                },
+        };
+        // 启动配置可能已被删除，此时按默认配置的路径查找进程
+        let launch_profile =
+                match profile::resolve(pool, &game, session.profile_id.as_deref()).await {
+                        | Ok(p) => p,
+                        | Err(_) => match profile::resolve(pool, &game, None).await {
+                                | Ok(p) => p,
+                                | Err(e) => {
+                                        warn!("解析游戏 {} 的启动配置失败: {}", game.name, e);
+                                        return None;
+                                },
+                        },
+                };
+        Some((game, launch_profile))
+}
+
+fn seconds(value: Option<i64>) -> Duration {
+        Duration::from_secs(value.unwrap_or(0).max(0) as u64)
+}
@@ -0,0 +1,356 @@
+//! 会话的手动修改
+//!
+//! 新建、修改、拆分、合并、删除当前档案已结束的会话。每次修改在一个事务内完成：
+//! 按修改前后的时长差调整涉及游戏在该档案下的 `play_time`（保留没有会话对应的那部分时长，
+//! 如旧版本累计或手动填写的时长），按剩余会话重新计算 `last_played_at`，
+//! 并在 `game_session_edits` 中留下修改前后的会话快照。
+
+use std::collections::{BTreeMap, HashSet};
+
+use chrono::{DateTime, Local, TimeDelta};
+use sqlx::{SqliteConnection, SqlitePool, types::Json};
+use uuid::Uuid;
+
+use crate::{
+        error::AppError,
+        game::entity::{PlaySession, SessionEdit, SessionEditAction, SessionInput, SessionSource},
+};
+
+const SESSION_SQL: &str = "SELECT id, game_id, play_date, duration_minutes, last_played_at, \
+     profile_id, active_seconds, idle_seconds, source FROM game_play_sessions";
+
+/// 会话修改记录，新的在前；不指定游戏时返回全部
+pub async fn list_edits(
+        pool: &SqlitePool,
+        account_id: &str,
+        game_id: Option<&str>,
+) -> Result<Vec<SessionEdit>, AppError> {
+        sqlx::query_as(
+                "SELECT id, game_id, action, before, after, created_at FROM game_session_edits \
+         WHERE account_id = ?1 AND (?2 IS NULL OR game_id = ?2) ORDER BY created_at DESC",
+        )
+        .bind(account_id)
+        .bind(game_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// 手动录入一次会话
+pub async fn create(
+        pool: &SqlitePool,
+        account_id: &str,
+        input: SessionInput,
+) -> Result<PlaySession, AppError> {
+        let session = with_input(
+                PlaySession {
+                        id: Uuid::new_v4().to_string(),
+                        game_id: String::new(),
+                        play_date: input.play_date,
+                        duration_minutes: 0,
+                        last_played_at: input.play_date,
+                        profile_id: None,
+                        active_seconds: None,
+                        idle_seconds: None,
+                        source: SessionSource::Manual,
+                },
+                input,
+        )?;
+
+        let mut tx = pool.begin().await?;
+        apply(
+                &mut tx,
+                account_id,
+                SessionEditAction::Create,
+                Vec::new(),
+                vec![session.clone()],
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(session)
+}
+
+/// 修改会话的游戏、时间与时长
+pub async fn update(
+        pool: &SqlitePool,
+        account_id: &str,
+        id: &str,
+        input: SessionInput,
+) -> Result<PlaySession, AppError> {
+        let mut tx = pool.begin().await?;
+        let before = fetch_closed(&mut tx, account_id, id).await?;
+        let session = with_input(before.clone(), input)?;
+        apply(
+                &mut tx,
+                account_id,
+                SessionEditAction::Update,
+                vec![before],
+                vec![session.clone()],
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(session)
+}
+
+/// 在 `at` 处把会话拆成两段，时长按两段的实际时间比例分配
+pub async fn split(
+        pool: &SqlitePool,
+        account_id: &str,
+        id: &str,
+        at: DateTime<Local>,
+) -> Result<Vec<PlaySession>, AppError> {
+        let mut tx = pool.begin().await?;
+        let before = fetch_closed(&mut tx, account_id, id).await?;
+        if at <= before.play_date || at >= before.last_played_at {
+                return Err(AppError::Resolve(
+                        id.to_string(),
+                        "拆分时间必须在会话的开始与结束之间".into(),
+                ));
+        }
+
+        let ratio = (at - before.play_date).num_seconds() as f64
+                / (before.last_played_at - before.play_date)
+                        .num_seconds()
+                        .max(1) as f64;
+        let part = |value: i64| (value as f64 * ratio).round() as i64;
+        let mut first = before.clone();
+        let mut second = before.clone();
+        first.last_played_at = at;
+        first.duration_minutes = part(before.duration_minutes);
+        first.active_seconds = before.active_seconds.map(part);
+        first.idle_seconds = before.idle_seconds.map(part);
+        second.id = Uuid::new_v4().to_string();
+        second.play_date = at;
+        second.duration_minutes = before.duration_minutes - first.duration_minutes;
+        second.active_seconds = before
+                .active_seconds
+                .zip(first.active_seconds)
+                .map(|(a, b)| a - b);
+        second.idle_seconds = before
+                .idle_seconds
+                .zip(first.idle_seconds)
+                .map(|(a, b)| a - b);
+
+        let after = vec![first, second];
+        apply(
+                &mut tx,
+                account_id,
+                SessionEditAction::Split,
+                vec![before],
+                after.clone(),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(after)
+}
+
+/// 把同一游戏的多次会话合并为一次：从最早的开始到最晚的结束，时长相加
+pub async fn merge(
+        pool: &SqlitePool,
+        account_id: &str,
+        ids: &[String],
+) -> Result<PlaySession, AppError> {
+        let ids: Vec<&String> = ids.iter().collect::<HashSet<_>>().into_iter().collect();
+        if ids.len() < 2 {
+                return Err(AppError::Generic("至少需要两次会话才能合并".into()));
+        }
+
+        let mut tx = pool.begin().await?;
+        let mut before = Vec::with_capacity(ids.len());
+        for id in ids {
+                before.push(fetch_closed(&mut tx, account_id, id).await?);
+        }
+        before.sort_by_key(|s| s.play_date);
+        if before.iter().any(|s| s.game_id != before[0].game_id) {
+                return Err(AppError::Generic("只能合并同一游戏的会话".into()));
+        }
+
+        let sum = |field: fn(&PlaySession) -> Option<i64>| {
+                let values: Vec<i64> = before.iter().filter_map(field).collect();
+                (!values.is_empty()).then(|| values.iter().sum())
+        };
+        let mut merged = before[0].clone();
+        merged.last_played_at = before
+                .iter()
+                .map(|s| s.last_played_at)
+                .max()
+                .unwrap_or(merged.last_played_at);
+        merged.duration_minutes = before.iter().map(|s| s.duration_minutes).sum();
+        merged.active_seconds = sum(|s| s.active_seconds);
+        merged.idle_seconds = sum(|s| s.idle_seconds);
+        if before.iter().any(|s| s.source != merged.source) {
+                merged.source = SessionSource::Manual;
+        }
+
+        apply(
+                &mut tx,
+                account_id,
+                SessionEditAction::Merge,
+                before,
+                vec![merged.clone()],
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(merged)
+}
+
+pub async fn delete(
+        pool: &SqlitePool,
+        account_id: &str,
+        id: &str,
+) -> Result<(), AppError> {
+        let mut tx = pool.begin().await?;
+        let before = fetch_closed(&mut tx, account_id, id).await?;
+        apply(
+                &mut tx,
+                account_id,
+                SessionEditAction::Delete,
+                vec![before],
+                Vec::new(),
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(())
+}
+
+/// 用提交的内容填写会话
+fn with_input(
+        mut session: PlaySession,
+        input: SessionInput,
+) -> Result<PlaySession, AppError> {
+        if input.duration_minutes < 0 {
+                return Err(AppError::Resolve(session.id, "时长不能为负".into()));
+        }
+        let last_played_at = match input.last_played_at {
+                | Some(at) => at,
+                | None => TimeDelta::try_minutes(input.duration_minutes)
+                        .and_then(|duration| input.play_date.checked_add_signed(duration))
+                        .ok_or_else(|| {
+                                AppError::Resolve(session.id.clone(), "时长超出范围".into())
+                        })?,
+        };
+        if last_played_at < input.play_date {
+                return Err(AppError::Resolve(
+                        session.id,
+                        "结束时间不能早于开始时间".into(),
+                ));
+        }
+        session.game_id = input.game_id;
+        session.play_date = input.play_date;
+        session.duration_minutes = input.duration_minutes;
+        session.last_played_at = last_played_at;
+        Ok(session)
+}
+
+/// 已结束的会话；进行中的会话由监控负责，不能手动修改
+async fn fetch_closed(
+        tx: &mut SqliteConnection,
+        account_id: &str,
+        id: &str,
+) -> Result<PlaySession, AppError> {
+        sqlx::query_as(&format!(
+                "{} WHERE id = ? AND account_id = ? AND is_open = 0",
+                SESSION_SQL
+        ))
+        .bind(id)
+        .bind(account_id)
+        .fetch_optional(tx)
+        .await?
+        .ok_or_else(|| AppError::Resolve(id.to_string(), "会话不存在或仍在进行中".into()))
+}
+
+/// 把 `before` 替换为 `after`，同步涉及游戏的游玩时长与最后游玩时间，并写入修改记录
+async fn apply(
+        tx: &mut SqliteConnection,
+        account_id: &str,
+        action: SessionEditAction,
+        before: Vec<PlaySession>,
+        after: Vec<PlaySession>,
+) -> Result<(), AppError> {
+        for game_id in after.iter().map(|s| &s.game_id).collect::<HashSet<_>>() {
+                let exists: bool =
+                        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM games WHERE id = ?)")
+                                .bind(game_id)
+                                .fetch_one(&mut *tx)
+                                .await?;
+                if !exists {
+                        return Err(AppError::Resolve(game_id.clone(), "游戏不存在".into()));
+                }
+        }
+
+        let kept: HashSet<&str> = after.iter().map(|s| s.id.as_str()).collect();
+        for session in before.iter().filter(|s| !kept.contains(s.id.as_str())) {
+                sqlx::query("DELETE FROM game_play_sessions WHERE id = ?")
+                        .bind(&session.id)
+                        .execute(&mut *tx)
+                        .await?;
+        }
+        for session in &after {
+                sqlx::query(
+                        "INSERT INTO game_play_sessions \
+             (id, game_id, play_date, duration_minutes, last_played_at, profile_id, \
+              active_seconds, idle_seconds, source, account_id) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+             ON CONFLICT(id) DO UPDATE SET game_id=excluded.game_id, \
+             play_date=excluded.play_date, duration_minutes=excluded.duration_minutes, \
+             last_played_at=excluded.last_played_at, active_seconds=excluded.active_seconds, \
+             idle_seconds=excluded.idle_seconds, source=excluded.source",
+                )
+                .bind(&session.id)
+                .bind(&session.game_id)
+                .bind(session.play_date)
+                .bind(session.duration_minutes)
+                .bind(session.last_played_at)
+                .bind(&session.profile_id)
+                .bind(session.active_seconds)
+                .bind(session.idle_seconds)
+                .bind(session.source)
+                .bind(account_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        // 各游戏的时长变化
+        let mut deltas: BTreeMap<&str, i64> = BTreeMap::new();
+        for session in &before {
+                *deltas.entry(&session.game_id).or_default() -= session.duration_minutes;
+        }
+        for session in &after {
+                *deltas.entry(&session.game_id).or_default() += session.duration_minutes;
+        }
+        for (game_id, delta) in &deltas {
+                sqlx::query(
+                        "INSERT INTO game_progress (account_id, game_id, play_time, last_played_at) \
+             VALUES (?1, ?2, MAX(?3, 0), (SELECT MAX(last_played_at) FROM game_play_sessions \
+                                          WHERE account_id = ?1 AND game_id = ?2 AND is_open = 0)) \
+             ON CONFLICT(account_id, game_id) DO UPDATE SET \
+             play_time = MAX(play_time + ?3, 0), last_played_at = excluded.last_played_at",
+                )
+                .bind(account_id)
+                .bind(game_id)
+                .bind(delta)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let game_id = after
+                .first()
+                .or(before.first())
+                .map(|s| s.game_id.clone())
+                .unwrap_or_default();
+        sqlx::query(
+                "INSERT INTO game_session_edits \
+         (id, account_id, game_id, action, before, after, created_at) \
+         VALUES (?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(Uuid::new_v4().to_string())
+        .bind(account_id)
+        .bind(game_id)
+        .bind(action)
+        .bind(Json(&before))
+        .bind(Json(&after))
+        .bind(Local::now())
+        .execute(&mut *tx)
+        .await?;
+        Ok(())
+}
@@ -0,0 +1,313 @@
+//! 用户活动检测：距离最后一次键鼠输入的时间，以及前台窗口所属的进程
+//!
+//! - Windows：`GetLastInputInfo` 与前台窗口
+//! - Linux：X11 下用 MIT-SCREEN-SAVER 扩展与 `_NET_ACTIVE_WINDOW`；
+//!   Wayland 下通过 D-Bus 向 GNOME（Mutter IdleMonitor）或 KDE（org.freedesktop.ScreenSaver）查询空闲时间，
+//!   前台窗口只能识别 XWayland 窗口
+//! - macOS：`ioreg` 中的 `HIDIdleTime`，不识别前台窗口
+//!
+//! 设置了环境变量 [`STUB_ENV`] 时改用 [`StubProbe`]，从指定文件读取检测结果，
+//! 便于在没有桌面环境的机器上测试会话统计。
+
+use std::path::PathBuf;
+
+/// 指向检测结果文件的环境变量，见 [`StubProbe`]
+pub const STUB_ENV: &str = "YUMIHUB_ACTIVITY_STUB";
+
+/// 活动检测；无法获取的项返回 `None`，由调用方按「未知」处理
+pub trait ActivityProbe: Send {
+        /// 距离最后一次键鼠输入的秒数
+        fn idle_seconds(&mut self) -> Option<u64>;
+        /// 前台窗口所属进程的 PID
+        fn foreground_pid(&mut self) -> Option<u32>;
+
+        /// 检测一次；可能阻塞（X11 / D-Bus 往返），异步代码中应放到阻塞线程里调用
+        fn sample(&mut self) -> ActivitySample {
+                ActivitySample {
+                        idle_seconds: self.idle_seconds(),
+                        foreground_pid: self.foreground_pid(),
+                }
+        }
+}
+
+/// 一次检测的结果
+#[derive(Clone, Copy, Default)]
+pub struct ActivitySample {
+        pub idle_seconds: Option<u64>,
+        pub foreground_pid: Option<u32>,
+}
+
+/// 当前平台的活动检测
+pub fn probe() -> Box<dyn ActivityProbe> {
+        match std::env::var_os(STUB_ENV) {
+                | Some(path) => Box::new(StubProbe {
+                        path: PathBuf::from(path),
+                }),
+                | None => platform::probe(),
+        }
+}
+
+/// 从文件读取检测结果：内容为 `<空闲秒数> [前台 PID]`，每次检测时重新读取，
+/// 任一项写作 `-` 或缺失表示未知
+pub struct StubProbe {
+        pub path: PathBuf,
+}
+
+impl StubProbe {
+        fn field<T: std::str::FromStr>(
+                &self,
+                index: usize,
+        ) -> Option<T> {
+                std::fs::read_to_string(&self.path)
+                        .ok()?
+                        .split_whitespace()
+                        .nth(index)?
+                        .parse()
+                        .ok()
+        }
+}
+
+impl ActivityProbe for StubProbe {
+        fn idle_seconds(&mut self) -> Option<u64> {
+                self.field(0)
+        }
+
+        fn foreground_pid(&mut self) -> Option<u32> {
+                self.field(1)
+        }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+        use windows::Win32::{
+                System::SystemInformation::GetTickCount,
+                UI::{
+                        Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
+                        WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId},
+                },
+        };
+
+        use super::ActivityProbe;
+
+        struct WindowsProbe;
+
+        impl ActivityProbe for WindowsProbe {
+                fn idle_seconds(&mut self) -> Option<u64> {
+                        let mut info = LASTINPUTINFO {
+                                cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+                                dwTime: 0,
+                        };
+                        unsafe {
+                                if !GetLastInputInfo(&mut info).as_bool() {
+                                        return None;
+                                }
+                                // 两者都是开机以来的毫秒数，约 49 天回绕一次
+                                Some(GetTickCount().wrapping_sub(info.dwTime) as u64 / 1000)
+                        }
+                }
+
+                fn foreground_pid(&mut self) -> Option<u32> {
+                        let mut pid = 0u32;
+                        unsafe {
+                                let hwnd = GetForegroundWindow();
+                                if hwnd.0.is_null() {
+                                        return None;
+                                }
+                                GetWindowThreadProcessId(hwnd, Some(&mut pid));
+                        }
+                        (pid != 0).then_some(pid)
+                }
+        }
+
+        pub fn probe() -> Box<dyn ActivityProbe> {
+                Box::new(WindowsProbe)
+        }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+        use x11rb::{
+                connection::Connection,
+                protocol::{
+                        screensaver::ConnectionExt as _,
+                        xproto::{AtomEnum, ConnectionExt as _, Window},
+                },
+                rust_connection::RustConnection,
+        };
+
+        use super::ActivityProbe;
+
+        struct X11 {
+                conn: RustConnection,
+                root: Window,
+                active_window: u32,
+                wm_pid: u32,
+        }
+
+        impl X11 {
+                fn connect() -> Option<Self> {
+                        std::env::var_os("DISPLAY")?;
+                        let (conn, screen) = x11rb::connect(None).ok()?;
+                        let root = conn.setup().roots.get(screen)?.root;
+                        let atom = |name: &[u8]| {
+                                conn.intern_atom(false, name)
+                                        .ok()?
+                                        .reply()
+                                        .ok()
+                                        .map(|r| r.atom)
+                        };
+                        let active_window = atom(b"_NET_ACTIVE_WINDOW")?;
+                        let wm_pid = atom(b"_NET_WM_PID")?;
+                        Some(Self {
+                                conn,
+                                root,
+                                active_window,
+                                wm_pid,
+                        })
+                }
+
+                fn idle_seconds(&self) -> Option<u64> {
+                        let reply = self
+                                .conn
+                                .screensaver_query_info(self.root)
+                                .ok()?
+                                .reply()
+                                .ok()?;
+                        Some(reply.ms_since_user_input as u64 / 1000)
+                }
+
+                fn property(
+                        &self,
+                        window: Window,
+                        property: u32,
+                        kind: AtomEnum,
+                ) -> Option<u32> {
+                        self.conn
+                                .get_property(false, window, property, kind, 0, 1)
+                                .ok()?
+                                .reply()
+                                .ok()?
+                                .value32()?
+                                .next()
+                }
+
+                fn foreground_pid(&self) -> Option<u32> {
+                        let window = self
+                                .property(self.root, self.active_window, AtomEnum::WINDOW)
+                                .filter(|w| *w != 0)?;
+                        self.property(window, self.wm_pid, AtomEnum::CARDINAL)
+                }
+        }
+
+        struct LinuxProbe {
+                x11: Option<X11>,
+                /// Wayland 会话的 D-Bus 连接（X11 会话为空）
+                session_bus: Option<zbus::blocking::Connection>,
+        }
+
+        impl LinuxProbe {
+                /// Wayland 合成器的空闲时间：GNOME 返回 u64 毫秒，KDE 返回 u32 毫秒
+                fn wayland_idle_seconds(&self) -> Option<u64> {
+                        let bus = self.session_bus.as_ref()?;
+                        let mutter = bus
+                                .call_method(
+                                        Some("org.gnome.Mutter.IdleMonitor"),
+                                        "/org/gnome/Mutter/IdleMonitor/Core",
+                                        Some("org.gnome.Mutter.IdleMonitor"),
+                                        "GetIdletime",
+                                        &(),
+                                )
+                                .ok()
+                                .and_then(|reply| reply.body().deserialize::<u64>().ok());
+                        let ms = match mutter {
+                                | Some(ms) => ms,
+                                | None => bus
+                                        .call_method(
+                                                Some("org.freedesktop.ScreenSaver"),
+                                                "/org/freedesktop/ScreenSaver",
+                                                Some("org.freedesktop.ScreenSaver"),
+                                                "GetSessionIdleTime",
+                                                &(),
+                                        )
+                                        .ok()?
+                                        .body()
+                                        .deserialize::<u32>()
+                                        .ok()? as u64,
+                        };
+                        Some(ms / 1000)
+                }
+        }
+
+        impl ActivityProbe for LinuxProbe {
+                fn idle_seconds(&mut self) -> Option<u64> {
+                        // Wayland 下 XWayland 只能看到 X 客户端收到的输入，以合成器的结果为准
+                        self.wayland_idle_seconds()
+                                .or_else(|| self.x11.as_ref()?.idle_seconds())
+                }
+
+                fn foreground_pid(&mut self) -> Option<u32> {
+                        self.x11.as_ref()?.foreground_pid()
+                }
+        }
+
+        pub fn probe() -> Box<dyn ActivityProbe> {
+                let wayland = std::env::var_os("WAYLAND_DISPLAY").is_some();
+                Box::new(LinuxProbe {
+                        x11: X11::connect(),
+                        session_bus: wayland
+                                .then(|| zbus::blocking::Connection::session().ok())
+                                .flatten(),
+                })
+        }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+        use std::process::Command;
+
+        use super::ActivityProbe;
+
+        struct MacProbe;
+
+        impl ActivityProbe for MacProbe {
+                fn idle_seconds(&mut self) -> Option<u64> {
+                        let output = Command::new("ioreg")
+                                .args(["-c", "IOHIDSystem", "-d", "4"])
+                                .output()
+                                .ok()?;
+                        let text = String::from_utf8_lossy(&output.stdout);
+                        let line = text.lines().find(|l| l.contains("\"HIDIdleTime\""))?;
+                        let ns: u64 = line.rsplit('=').next()?.trim().parse().ok()?;
+                        Some(ns / 1_000_000_000)
+                }
+
+                fn foreground_pid(&mut self) -> Option<u32> {
+                        None
+                }
+        }
+
+        pub fn probe() -> Box<dyn ActivityProbe> {
+                Box::new(MacProbe)
+        }
+}
+
+#[cfg(not(any(target_os = "windows", target_os = "linux", target_os = "macos")))]
+mod platform {
+        use super::ActivityProbe;
+
+        struct UnknownProbe;
+
+        impl ActivityProbe for UnknownProbe {
+                fn idle_seconds(&mut self) -> Option<u64> {
+                        None
+                }
+
+                fn foreground_pid(&mut self) -> Option<u32> {
+                        None
+                }
+        }
+
+        pub fn probe() -> Box<dyn ActivityProbe> {
+                Box::new(UnknownProbe)
+        }
+}
@@ -9,8 +9,10 @@ use std::{
 };
 
 use unrar::Archive as RarArchive;
-use walkdir::WalkDir;
-use zip::{ZipArchive, ZipWriter, write::FileOptions};
+use zip::{
+        CompressionMethod, ZipArchive, ZipWriter,
+        write::{FileOptions, SimpleFileOptions},
+};
 
 use crate::error::AppError;
 
@@ -64,6 +66,86 @@ pub fn list_rar(path: &str) -> Result<Vec<ArchiveEntry>, AppError> {
         .collect()
 }
 
+/// 逐个读取 ZIP 内的文件条目（跳过目录），把条目元数据和内容流交给回调
+///
+/// 内容读到末尾时 zip 库会校验 CRC，不一致时以 IO 错误返回
+pub fn read_zip_files(
+        zip_path: &Path,
+        mut f: impl FnMut(&ArchiveEntry, &mut dyn Read) -> Result<(), AppError>,
+) -> Result<(), AppError> {
+        let file = File::open(zip_path)?;
+        let mut archive = ZipArchive::new(file).map_err(|e| AppError::Fs(e.to_string()))?;
+
+        for i in 0..archive.len() {
+                let mut file = archive
+                        .by_index(i)
+                        .map_err(|e| AppError::Fs(e.to_string()))?;
+                if file.is_dir() {
+                        continue;
+                }
+                let entry = ArchiveEntry {
+                        name: file.name().to_string(),
+                        size: file.size(),
+                        is_dir: false,
+                        encrypted: file.encrypted(),
+                };
+                f(&entry, &mut file)?;
+        }
+        Ok(())
+}
+
+// ── 打包 ──────────────────────────────────────────────────────────────────────
+
+/// 逐个写入条目的 ZIP 打包器
+pub struct ZipPacker {
+        zip: ZipWriter<File>,
+        options: SimpleFileOptions,
+}
+
+impl ZipPacker {
+        pub fn create(path: &Path) -> Result<Self, AppError> {
+                if let Some(parent) = path.parent() {
+                        std::fs::create_dir_all(parent)?;
+                }
+                Ok(Self {
+                        zip: ZipWriter::new(File::create(path)?),
+                        options: FileOptions::default()
+                                .compression_method(CompressionMethod::Deflated)
+                                .large_file(true),
+                })
+        }
+
+        /// 写入一段内存数据
+        pub fn add_bytes(
+                &mut self,
+                name: &str,
+                data: &[u8],
+        ) -> Result<(), AppError> {
+                self.zip.start_file(name, self.options)
+                        .map_err(|e| AppError::Fs(e.to_string()))?;
+                self.zip.write_all(data)?;
+                Ok(())
+        }
+
+        /// 以流的方式写入磁盘上的文件
+        pub fn add_file(
+                &mut self,
+                name: &str,
+                path: &Path,
+        ) -> Result<(), AppError> {
+                let mut file = File::open(path)?;
+                self.zip.start_file(name, self.options)
+                        .map_err(|e| AppError::Fs(e.to_string()))?;
+                std::io::copy(&mut file, &mut self.zip)?;
+                Ok(())
+        }
+
+        pub fn finish(self) -> Result<(), AppError> {
+                self.zip.finish().map_err(|e| AppError::Fs(e.to_string()))?;
+                Ok(())
+        }
+}
+
 // ── 解压 ──────────────────────────────────────────────────────────────────────
 
 /// 解压 ZIP 到目标目录，返回解压后的第一级根目录路径
@@ -144,54 +226,3 @@ pub fn extract_rar(
                 | None => extract_to.to_path_buf(),
         })
 }
-
-// ── 打包 ──────────────────────────────────────────────────────────────────────
-
-/// 将目录打包成 ZIP 文件
-/// 安全检查：内容小于 1 KB 时拒绝，避免覆盖旧备份
-pub fn zip_dir(
-        src: &Path,
-        dst: &Path,
-) -> Result<(), AppError> {
-        // 大小安全检查
-        let total: u64 = WalkDir::new(src)
-                .into_iter()
-                .filter_map(|e| e.ok())
-                .filter(|e| e.path().is_file())
-                .filter_map(|e| e.metadata().ok())
-                .map(|m| m.len())
-                .sum();
-
-        if total <= 1024 {
-                return Err(AppError::Fs(format!(
-                        "存档内容过小 ({:.2} KB)，已拦截备份以保护旧数据",
-                        total as f64 / 1024.0
-                )));
-        }
-
-        let file = File::create(dst)?;
-        let mut zip = ZipWriter::new(file);
-        let opts: FileOptions<()> =
-                FileOptions::default().compression_method(zip::CompressionMethod::Deflated);
-
-        for entry in WalkDir::new(src).into_iter().filter_map(|e| e.ok()) {
-                let path = entry.path();
-                let name = path
-                        .strip_prefix(src)
-                        .map_err(|e| AppError::Fs(e.to_string()))?;
-
-                if path.is_file() {
-                        zip.start_file(name.to_string_lossy(), opts)
-                                .map_err(|e| AppError::Fs(e.to_string()))?;
-                        let mut buf = Vec::new();
-                        File::open(path)?.read_to_end(&mut buf)?;
-                        zip.write_all(&buf)?;
-                } else if !name.as_os_str().is_empty() {
-                        zip.add_directory(name.to_string_lossy(), opts)
-                                .map_err(|e| AppError::Fs(e.to_string()))?;
-                }
-        }
-
-        zip.finish().map_err(|e| AppError::Fs(e.to_string()))?;
-        Ok(())
-}
@@ -0,0 +1,298 @@
+//! 启动程序排序
+//!
+//! 对游戏目录（及两层以内的子目录）中的每个 `.exe` 打分并给出理由：
+//! 文件名、与文件夹 / Unity 数据目录是否同名、PE 版本信息、文件大小、是否为控制台程序、所在层级。
+//! 同时按名称和版本信息判断用途，区分游戏本体、设置程序、语言启动器、安装程序和无关工具。
+
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::{
+        error::AppError,
+        infra::pe::{self, VersionInfo},
+};
+
+/// 最多返回的候选数
+const MAX_CANDIDATES: usize = 10;
+/// 向下查找的子目录层数
+const MAX_DEPTH: usize = 2;
+/// 小于此大小的程序多半是引导程序
+const SMALL_EXE: u64 = 100 * 1024;
+/// 不会放游戏本体的子目录
+const SKIPPED_DIRS: &[&str] = &[
+        "redist",
+        "_commonredist",
+        "directx",
+        "dotnet",
+        "vcredist",
+        "support",
+        "crashpad",
+        "manual",
+        "__macosx",
+];
+
+/// 无关工具：崩溃报告、运行库安装、卸载程序等
+const TOOL_KEYWORDS: &[&str] = &[
+        "uninst",
+        "unins0",
+        "crash",
+        "redist",
+        "dxsetup",
+        "dotnet",
+        "helper",
+        "updater",
+        "benchmark",
+];
+const INSTALLER_KEYWORDS: &[&str] = &["setup", "install"];
+const CONFIG_KEYWORDS: &[&str] = &[
+        "config", "setting", "option", "cfg", "設定", "设定", "设置", "環境",
+];
+const LAUNCHER_KEYWORDS: &[&str] = &[
+        "launcher",
+        "language",
+        "langselect",
+        "selector",
+        "ランチャー",
+];
+
+/// 可执行文件的用途
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExeRole {
+        Game,
+        /// 设置程序（画面、音量等）
+        Config,
+        /// 启动器（选择语言、版本后再启动游戏）
+        Launcher,
+        Installer,
+        /// 与游戏无关的工具
+        Tool,
+}
+
+/// 一个可执行文件候选
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExeCandidate {
+        /// 以 `/` 分隔的完整路径
+        pub path: String,
+        pub role: ExeRole,
+        pub score: i32,
+        pub size: u64,
+        /// 图形界面程序；无法解析 PE 头时为空
+        pub gui: Option<bool>,
+        pub version: Option<VersionInfo>,
+        /// 加减分的理由
+        pub reasons: Vec<String>,
+}
+
+/// 对目录中的可执行文件打分，按得分从高到低返回
+pub fn rank(dir: &Path) -> Result<Vec<ExeCandidate>, AppError> {
+        if !dir.is_dir() {
+                return Err(AppError::Resolve(
+                        dir.to_string_lossy().into_owned(),
+                        "路径不存在".into(),
+                ));
+        }
+        let folder = normalize(&dir.file_name().unwrap_or_default().to_string_lossy());
+
+        let mut exes = Vec::new();
+        collect(dir, 0, &mut exes);
+        let largest = exes.iter().map(|(_, _, size)| *size).max().unwrap_or(0);
+        let second = exes
+                .iter()
+                .map(|(_, _, size)| *size)
+                .filter(|s| *s < largest)
+                .max()
+                .unwrap_or(0);
+
+        let mut candidates: Vec<ExeCandidate> = exes
+                .into_iter()
+                .map(|(path, depth, size)| {
+                        score(
+                                &path,
+                                depth,
+                                size,
+                                &folder,
+                                largest > second && size == largest,
+                        )
+                })
+                .collect();
+        candidates.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.path.cmp(&b.path)));
+        candidates.truncate(MAX_CANDIDATES);
+        Ok(candidates)
+}
+
+/// 最适合作为主启动程序的候选：优先游戏本体与启动器，其次是除无关工具外得分最高的
+pub fn best_game(candidates: &[ExeCandidate]) -> Option<&ExeCandidate> {
+        candidates
+                .iter()
+                .find(|c| matches!(c.role, ExeRole::Game | ExeRole::Launcher))
+                .or_else(|| candidates.iter().find(|c| c.role != ExeRole::Tool))
+}
+
+/// 得分最高的设置程序
+pub fn best_config(candidates: &[ExeCandidate]) -> Option<&ExeCandidate> {
+        candidates.iter().find(|c| c.role == ExeRole::Config)
+}
+
+/// 收集目录中的 `.exe`，附带所在层级与大小
+fn collect(
+        dir: &Path,
+        depth: usize,
+        out: &mut Vec<(PathBuf, usize, u64)>,
+) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+                return;
+        };
+        for entry in entries.flatten() {
+                let path = entry.path();
+                let Ok(meta) = entry.metadata() else {
+                        continue;
+                };
+                let name = entry.file_name().to_string_lossy().to_lowercase();
+                if meta.is_dir() {
+                        if depth < MAX_DEPTH
+                                && !name.starts_with('.')
+                                && !SKIPPED_DIRS.contains(&name.as_str())
+                        {
+                                collect(&path, depth + 1, out);
+                        }
+                } else if name.ends_with(".exe") {
+                        out.push((path, depth, meta.len()));
+                }
+        }
+}
+
+fn score(
+        path: &Path,
+        depth: usize,
+        size: u64,
+        folder: &str,
+        largest: bool,
+) -> ExeCandidate {
+        let file_name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        let stem = path
+                .file_stem()
+                .map(|s| s.to_string_lossy().to_lowercase())
+                .unwrap_or_default();
+        let info = pe::read(path);
+        let version = info.as_ref().and_then(|i| i.version.clone());
+
+        let mut score = 0;
+        let mut reasons = Vec::new();
+        let mut add = |points: i32, reason: String| {
+                score += points;
+                reasons.push(format!("{:+} {}", points, reason));
+        };
+
+        // 用途：文件名优先，其次是版本信息中的说明
+        let texts: Vec<String> = version
+                .iter()
+                .flat_map(|v| v.texts())
+                .map(str::to_lowercase)
+                .collect();
+        let keyword = |keywords: &[&str]| -> Option<String> {
+                keywords.iter().find_map(|k| {
+                        if stem.contains(k) {
+                                Some(format!("文件名含「{}」", k))
+                        } else {
+                                texts.iter()
+                                        .any(|t| t.contains(k))
+                                        .then(|| format!("版本信息含「{}」", k))
+                        }
+                })
+        };
+        let role = if let Some(why) = keyword(TOOL_KEYWORDS) {
+                add(-80, format!("{}，是无关工具", why));
+                ExeRole::Tool
+        } else if let Some(why) = keyword(INSTALLER_KEYWORDS) {
+                add(-60, format!("{}，是安装程序", why));
+                ExeRole::Installer
+        } else if let Some(why) = keyword(CONFIG_KEYWORDS) {
+                add(-40, format!("{}，是设置程序", why));
+                ExeRole::Config
+        } else if let Some(why) = keyword(LAUNCHER_KEYWORDS) {
+                add(-15, format!("{}，是启动器", why));
+                ExeRole::Launcher
+        } else {
+                ExeRole::Game
+        };
+
+        // 名称
+        let norm_stem = normalize(&stem);
+        if stem.contains("game") {
+                add(15, "文件名含「game」".into());
+        }
+        if !folder.is_empty() && norm_stem == folder {
+                add(25, "与游戏文件夹同名".into());
+        } else if norm_stem.len() >= 3
+                && folder.len() >= 3
+                && (folder.contains(&norm_stem) || norm_stem.contains(folder))
+        {
+                add(10, "与游戏文件夹名相近".into());
+        }
+        if let Some(parent) = path.parent()
+                && parent.join(format!("{}_Data", stem_of(path))).is_dir()
+        {
+                add(
+                        25,
+                        format!("有同名的 Unity 数据目录 {}_Data", stem_of(path)),
+                );
+        }
+        if !folder.is_empty() && texts.iter().any(|t| normalize(t).contains(folder)) {
+                add(10, "版本信息与游戏文件夹名匹配".into());
+        }
+
+        // 文件本身
+        if largest {
+                add(10, "体积最大".into());
+        }
+        if size < SMALL_EXE {
+                add(-10, "体积很小，可能是引导程序".into());
+        }
+        match &info {
+                | Some(i) if !i.gui => add(-15, "控制台程序".into()),
+                | None => add(-5, "无法读取 PE 文件头".into()),
+                | _ => {},
+        }
+        if depth > 0 {
+                let dir = path
+                        .parent()
+                        .and_then(Path::file_name)
+                        .map(|n| n.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                add(-8 * depth as i32, format!("位于子目录 {}", dir));
+        }
+
+        if reasons.is_empty() {
+                reasons.push(format!("{} 没有明显特征", file_name));
+        }
+        ExeCandidate {
+                path: path.to_string_lossy().replace('\\', "/"),
+                role,
+                score,
+                size,
+                gui: info.as_ref().map(|i| i.gui),
+                version,
+                reasons,
+        }
+}
+
+/// 保留原始大小写的文件名主干，用于拼接同名目录
+fn stem_of(path: &Path) -> String {
+        path.file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+}
+
+/// 只保留字母和数字并转小写
+fn normalize(s: &str) -> String {
+        s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .flat_map(char::to_lowercase)
+                .collect()
+}
@@ -1,10 +1,10 @@
 //! 文件系统通用工具
 
-use std::path::{Path, PathBuf};
+use std::path::Path;
 
 use walkdir::WalkDir;
 
-use crate::error::AppError;
+use crate::{error::AppError, infra::exe};
 
 /// 递归复制目录（异步）
 pub async fn copy_dir(
@@ -42,63 +42,18 @@ pub fn dir_size(path: &Path) -> u64 {
 
 /// 在父目录中推断游戏主启动程序路径
 ///
-/// 规则：
-/// 1. 只看当前目录（不递归），找所有 `.exe`
-/// 2. 过滤掉 `uninst` / `crashpad` 等非主程序
-/// 3. 优先选名字含 `game` 或与文件夹同名的 exe；否则取第一个
+/// 按 [`exe::rank`] 的得分取最适合的一个；设置程序、安装程序和无关工具只在没有其他选择时才会被选中
 pub fn detect_game_exe(parent_path: &str) -> Result<String, AppError> {
-        let root = Path::new(parent_path);
-        if !root.is_dir() {
-                return Err(AppError::Resolve(
-                        parent_path.to_string(),
-                        "路径不存在".into(),
-                ));
-        }
-
-        let folder_name = root
-                .file_name()
-                .unwrap_or_default()
-                .to_string_lossy()
-                .to_lowercase();
-
-        let exes: Vec<PathBuf> = std::fs::read_dir(root)
-                .map_err(|e| AppError::Resolve(parent_path.to_string(), e.to_string()))?
-                .flatten()
-                .map(|e| e.path())
-                .filter(|p| {
-                        p.is_file()
-                                && p.extension()
-                                        .map(|ext| ext.eq_ignore_ascii_case("exe"))
-                                        .unwrap_or(false)
+        let candidates = exe::rank(Path::new(parent_path))?;
+        exe::best_game(&candidates)
+                .map(|c| c.path.clone())
+                .ok_or_else(|| {
+                        AppError::Resolve(parent_path.to_string(), "未找到游戏启动程序".into())
                 })
-                .filter(|p| {
-                        let name = p
-                                .file_name()
-                                .unwrap_or_default()
-                                .to_string_lossy()
-                                .to_lowercase();
-                        !name.contains("uninst") && !name.contains("crashpad")
-                })
-                .collect();
-
-        if exes.is_empty() {
-                return Err(AppError::Resolve(
-                        parent_path.to_string(),
-                        "未找到游戏启动程序".into(),
-                ));
-        }
-
-        let best = exes
-                .iter()
-                .find(|p| {
-                        let name = p
-                                .file_name()
-                                .unwrap_or_default()
-                                .to_string_lossy()
-                                .to_lowercase();
-                        name.contains("game") || name.contains(&folder_name)
-                })
-                .unwrap_or(&exes[0]);
+}
 
-        Ok(best.to_string_lossy().replace('\\', "/"))
+/// 在父目录中推断设置程序路径
+pub fn detect_config_exe(parent_path: &str) -> Option<String> {
+        let candidates = exe::rank(Path::new(parent_path)).ok()?;
+        exe::best_config(&candidates).map(|c| c.path.clone())
 }
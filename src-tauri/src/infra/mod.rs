@@ -1,4 +1,10 @@
 //! 基础设施层 —— 与业务无关的通用工具
+pub mod activity;
 pub mod archive;
+pub mod exe;
 pub mod fs;
+pub mod pe;
 pub mod process;
+
+#[cfg(test)]
+pub mod tempdir;
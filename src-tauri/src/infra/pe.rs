@@ -0,0 +1,250 @@
+//! PE 文件（Windows 可执行文件）信息读取
+//!
+//! 只解析识别启动程序需要的部分：子系统（图形界面 / 控制台）、位数，
+//! 以及版本资源中的字符串（文件说明、产品名称等）。
+//! 只读取文件头和资源节，不会把整个可执行文件载入内存。
+
+use std::{
+        collections::HashMap,
+        fs::File,
+        io::{Read, Seek, SeekFrom},
+        path::Path,
+};
+
+use serde::Serialize;
+
+/// 文件头最多读取的字节数（节表总在这个范围内）
+const HEADER_LIMIT: u64 = 64 * 1024;
+/// 资源节最多读取的字节数，超出时放弃解析版本信息
+const RESOURCE_LIMIT: u32 = 32 * 1024 * 1024;
+const RT_VERSION: u32 = 16;
+const SUBSYSTEM_WINDOWS_GUI: u16 = 2;
+
+/// PE 文件的基本信息
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PeInfo {
+        /// 图形界面程序（否则为控制台程序）
+        pub gui: bool,
+        pub is_64bit: bool,
+        pub version: Option<VersionInfo>,
+}
+
+/// 版本资源中的字符串
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VersionInfo {
+        pub file_description: Option<String>,
+        pub product_name: Option<String>,
+        pub company_name: Option<String>,
+        pub original_filename: Option<String>,
+        pub internal_name: Option<String>,
+        pub file_version: Option<String>,
+}
+
+impl VersionInfo {
+        /// 所有非空字符串，用于关键词匹配
+        pub fn texts(&self) -> impl Iterator<Item = &str> {
+                [
+                        &self.file_description,
+                        &self.product_name,
+                        &self.original_filename,
+                        &self.internal_name,
+                ]
+                .into_iter()
+                .filter_map(|s| s.as_deref())
+        }
+}
+
+struct Section {
+        virtual_address: u32,
+        virtual_size: u32,
+        raw_size: u32,
+        raw_offset: u32,
+}
+
+/// 读取 PE 文件信息；不是有效的 PE 文件时返回 `None`
+pub fn read(path: &Path) -> Option<PeInfo> {
+        let mut file = File::open(path).ok()?;
+        let mut header = Vec::new();
+        (&mut file)
+                .take(HEADER_LIMIT)
+                .read_to_end(&mut header)
+                .ok()?;
+
+        if header.get(..2)? != b"MZ" {
+                return None;
+        }
+        let pe = u32_at(&header, 0x3C)? as usize;
+        if header.get(pe..pe + 4)? != b"PE\0\0" {
+                return None;
+        }
+        let section_count = u16_at(&header, pe + 6)? as usize;
+        let optional_size = u16_at(&header, pe + 20)? as usize;
+        let optional = pe + 24;
+
+        let is_64bit = match u16_at(&header, optional)? {
+                | 0x10b => false,
+                | 0x20b => true,
+                | _ => return None,
+        };
+        let subsystem = u16_at(&header, optional + 68)?;
+        let directories = optional + if is_64bit { 112 } else { 96 };
+        let directory_count = u32_at(&header, optional + if is_64bit { 108 } else { 92 })?;
+
+        let sections: Vec<Section> = (0..section_count)
+                .filter_map(|i| {
+                        let at = optional + optional_size + i * 40;
+                        Some(Section {
+                                virtual_size: u32_at(&header, at + 8)?,
+                                virtual_address: u32_at(&header, at + 12)?,
+                                raw_size: u32_at(&header, at + 16)?,
+                                raw_offset: u32_at(&header, at + 20)?,
+                        })
+                })
+                .collect();
+
+        let version = (directory_count > 2)
+                .then(|| u32_at(&header, directories + 2 * 8))
+                .flatten()
+                .filter(|rva| *rva != 0)
+                .and_then(|rva| read_version(&mut file, &sections, rva));
+
+        Some(PeInfo {
+                gui: subsystem == SUBSYSTEM_WINDOWS_GUI,
+                is_64bit,
+                version,
+        })
+}
+
+/// 在资源目录中找到版本资源并解析
+fn read_version(
+        file: &mut File,
+        sections: &[Section],
+        resource_rva: u32,
+) -> Option<VersionInfo> {
+        let section = sections.iter().find(|s| {
+                let size = s.virtual_size.max(s.raw_size);
+                resource_rva >= s.virtual_address
+                        && s.virtual_address
+                                .checked_add(size)
+                                .is_some_and(|end| resource_rva < end)
+        })?;
+        if section.raw_size > RESOURCE_LIMIT {
+                return None;
+        }
+        let mut data = vec![0; section.raw_size as usize];
+        file.seek(SeekFrom::Start(section.raw_offset as u64)).ok()?;
+        file.read_exact(&mut data).ok()?;
+
+        // 资源树：类型 → 名称 → 语言，取第一个版本资源
+        let root = (resource_rva - section.virtual_address) as usize;
+        let entry = |dir: usize, id: Option<u32>| -> Option<u32> {
+                let named = u16_at(&data, dir + 12)? as usize;
+                let ids = u16_at(&data, dir + 14)? as usize;
+                (0..named + ids).find_map(|i| {
+                        let at = dir + 16 + i * 8;
+                        let name = u32_at(&data, at)?;
+                        id.is_none_or(|id| name == id)
+                                .then(|| u32_at(&data, at + 4))
+                                .flatten()
+                })
+        };
+        let subdir = |offset: u32| {
+                (offset & 0x8000_0000 != 0).then_some(root + (offset & 0x7FFF_FFFF) as usize)
+        };
+
+        let names = subdir(entry(root, Some(RT_VERSION))?)?;
+        let languages = subdir(entry(names, None)?)?;
+        let leaf = entry(languages, None)?;
+        if leaf & 0x8000_0000 != 0 {
+                return None;
+        }
+        let leaf = root + leaf as usize;
+        let data_rva = u32_at(&data, leaf)?;
+        let data_size = u32_at(&data, leaf + 4)? as usize;
+        let start = data_rva.checked_sub(section.virtual_address)? as usize;
+        let block = data.get(start..start.checked_add(data_size)?)?;
+
+        let mut strings = HashMap::new();
+        walk_version(block, 0, block.len(), 0, &mut strings);
+        let mut take = |key: &str| strings.remove(key).filter(|s: &String| !s.is_empty());
+        Some(VersionInfo {
+                file_description: take("FileDescription"),
+                product_name: take("ProductName"),
+                company_name: take("CompanyName"),
+                original_filename: take("OriginalFilename"),
+                internal_name: take("InternalName"),
+                file_version: take("FileVersion"),
+        })
+}
+
+/// 遍历 `VS_VERSIONINFO` 结构：根 → `StringFileInfo` → 语言表 → 字符串
+fn walk_version(
+        buf: &[u8],
+        start: usize,
+        end: usize,
+        depth: u32,
+        out: &mut HashMap<String, String>,
+) {
+        let mut pos = start;
+        while pos + 6 <= end {
+                let Some(len) = u16_at(buf, pos).map(usize::from).filter(|l| *l >= 6) else {
+                        return;
+                };
+                let node_end = (pos + len).min(end);
+                let value_len = u16_at(buf, pos + 2).unwrap_or(0) as usize;
+                let is_text = u16_at(buf, pos + 4) == Some(1);
+                let (key, after_key) = utf16z(buf, pos + 6, node_end);
+                let value_start = align4(after_key);
+                let value_end = (value_start + if is_text { value_len * 2 } else { value_len })
+                        .min(node_end);
+
+                match depth {
+                        | 3 => {
+                                let (value, _) = utf16z(buf, value_start, value_end);
+                                out.entry(key).or_insert_with(|| value.trim().to_string());
+                        },
+                        | 1 if key != "StringFileInfo" => {},
+                        | _ => walk_version(buf, align4(value_end), node_end, depth + 1, out),
+                }
+                pos = align4(node_end);
+        }
+}
+
+/// 读取以 0 结尾的 UTF-16LE 字符串，返回字符串与结尾之后的位置
+fn utf16z(
+        buf: &[u8],
+        start: usize,
+        end: usize,
+) -> (String, usize) {
+        let mut units = Vec::new();
+        let mut pos = start;
+        while pos + 2 <= end {
+                let unit = u16::from_le_bytes([buf[pos], buf[pos + 1]]);
+                pos += 2;
+                if unit == 0 {
+                        break;
+                }
+                units.push(unit);
+        }
+        (String::from_utf16_lossy(&units), pos)
+}
+
+fn align4(pos: usize) -> usize {
+        (pos + 3) & !3
+}
+
+fn u16_at(
+        buf: &[u8],
+        at: usize,
+) -> Option<u16> {
+        Some(u16::from_le_bytes(buf.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn u32_at(
+        buf: &[u8],
+        at: usize,
+) -> Option<u32> {
+        Some(u32::from_le_bytes(buf.get(at..at + 4)?.try_into().ok()?))
+}
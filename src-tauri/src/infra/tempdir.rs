@@ -0,0 +1,35 @@
+//! 测试用临时目录：以随机名称建在系统临时目录下，离开作用域时删除
+
+use std::path::{Path, PathBuf};
+
+use uuid::Uuid;
+
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+        pub fn new() -> Self {
+                Self::with_files(&[])
+        }
+
+        /// 按相对路径创建空文件（连同上级目录）
+        pub fn with_files(files: &[&str]) -> Self {
+                let dir = std::env::temp_dir().join(format!("yumihub-test-{}", Uuid::new_v4()));
+                std::fs::create_dir_all(&dir).unwrap();
+                for file in files {
+                        let path = dir.join(file);
+                        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                        std::fs::write(path, b"").unwrap();
+                }
+                Self(dir)
+        }
+
+        pub fn path(&self) -> &Path {
+                &self.0
+        }
+}
+
+impl Drop for TempDir {
+        fn drop(&mut self) {
+                let _ = std::fs::remove_dir_all(&self.0);
+        }
+}
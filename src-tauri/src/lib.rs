@@ -8,12 +8,16 @@
 //! ├── message/        消息总线（GAME_HUB, CONFIG_HUB）
 //! ├── config/         配置读写与变更分发
 //! ├── game/           游戏实体与启动逻辑
+//! ├── engine/         游戏引擎识别
 //! ├── companion/      连携程序管理
 //! ├── screenshot/     截图
 //! ├── shortcut/       快捷键
+//! ├── stats/          游玩统计
 //! ├── backup/         存档备份
+//! ├── library/        游戏库导入导出
+//! ├── scanner/        游戏根目录扫描与监听
 //! ├── resource/       资源下载
-//! ├── user/           用户实体
+//! ├── user/           用户信息与用户档案
 //! ├── theme.rs        主题加载
 //! ├── sys.rs          系统监控
 //! ├── tray.rs         系统托盘
@@ -33,14 +37,18 @@ mod commands;
 mod companion;
 mod config;
 mod db;
+mod engine;
 mod error;
 mod game;
 mod infra;
+mod library;
 mod life_cycle;
 mod message;
 mod resource;
+mod scanner;
 mod screenshot;
 mod shortcut;
+mod stats;
 mod sys;
 mod theme;
 mod tray;
@@ -82,6 +90,10 @@ pub fn run() {
                         // ── 用户 ──────────────────────────────────
                         commands::get_user_info,
                         commands::update_user_info,
+                        commands::get_user_profiles,
+                        commands::create_user_profile,
+                        commands::delete_user_profile,
+                        commands::switch_user_profile,
                         // ── 游戏 ──────────────────────────────────
                         commands::get_game_meta_list,
                         commands::get_game_meta_by_id,
@@ -91,8 +103,35 @@ pub fn run() {
                         commands::delete_game_by_id,
                         commands::delete_all_games,
                         commands::start_game,
+                        commands::get_launch_profiles,
+                        commands::save_launch_profile,
+                        commands::delete_launch_profile,
+                        commands::set_default_launch_profile,
+                        commands::get_runner_presets,
+                        commands::get_game_hooks,
+                        commands::update_game_hooks,
                         commands::get_sessions,
                         commands::get_sessions_by_year,
+                        commands::create_session,
+                        commands::update_session,
+                        commands::split_session,
+                        commands::merge_sessions,
+                        commands::delete_session,
+                        commands::get_session_edits,
+                        commands::scan_game_library,
+                        // ── 游玩统计 ──────────────────────────────
+                        commands::get_play_totals,
+                        commands::get_game_play_totals,
+                        commands::get_play_records,
+                        commands::get_play_heatmap,
+                        commands::get_developer_completion,
+                        commands::get_year_review,
+                        commands::export_year_review,
+                        // ── 游戏引擎 ──────────────────────────────
+                        commands::get_engine_profiles,
+                        commands::detect_game_engine,
+                        commands::detect_all_game_engines,
+                        commands::set_game_engine,
                         // ── 压缩包 ────────────────────────────────
                         commands::get_archive_list,
                         commands::extract_archive,
@@ -111,6 +150,19 @@ pub fn run() {
                         commands::backup_archive_by_id,
                         commands::restore_archive_by_id,
                         commands::restore_all_archives,
+                        commands::get_backup_snapshots,
+                        commands::restore_archive_snapshot,
+                        commands::get_backup_policy,
+                        commands::update_backup_policy,
+                        commands::verify_backups,
+                        commands::get_backup_runs,
+                        commands::discover_save_paths,
+                        // ── 游戏库 ────────────────────────────────
+                        commands::export_library,
+                        commands::import_library,
+                        commands::relocate_library,
+                        commands::check_library_health,
+                        commands::fix_library_health,
                         // ── 连携程序 ──────────────────────────────
                         commands::get_companions,
                         commands::update_companions,
@@ -124,6 +176,7 @@ pub fn run() {
                         commands::remove_game_from_collection,
                         // ── 系统工具 ──────────────────────────────
                         commands::get_start_up_path,
+                        commands::rank_start_up_paths,
                         commands::get_path_kinds,
                         commands::get_system_fonts,
                         commands::get_game_size,
@@ -0,0 +1,194 @@
+//! 游戏库导出包的读写
+//!
+//! ```
+//! library.zip
+//! ├── manifest.json          格式版本、导出时的路径、各表记录数
+//! ├── library.json           各表记录与配置
+//! ├── images/<game_id>/…     本地封面与背景（可选）
+//! └── backups/…              存档备份仓库的完整副本（可选）
+//! ```
+
+use std::{
+        collections::{HashMap, HashSet},
+        fs::File,
+        io::Read,
+        path::{Path, PathBuf},
+};
+
+use walkdir::WalkDir;
+
+use crate::{
+        backup::store,
+        error::AppError,
+        infra::archive::{ZipPacker, read_zip_files},
+        library::entity::{BUNDLE_VERSION, BundleManifest, LibraryData},
+};
+
+const MANIFEST_FILE: &str = "manifest.json";
+const LIBRARY_FILE: &str = "library.json";
+const IMAGE_DIR: &str = "images";
+const BACKUP_DIR: &str = "backups";
+
+/// 读取导出包得到的内容（图片和备份只记录条目名，导入确认后再解压）
+pub struct BundleContents {
+        pub manifest: BundleManifest,
+        pub data: LibraryData,
+        pub images: HashSet<String>,
+}
+
+/// 本地图片在导出包中的条目名
+pub fn image_entry(
+        game_id: &str,
+        path: &Path,
+) -> Option<String> {
+        let name = path.file_name()?.to_string_lossy();
+        Some(format!("{}/{}/{}", IMAGE_DIR, game_id, name))
+}
+
+/// 图片条目解压到资源目录后的路径
+pub fn image_target(
+        meta_dir: &Path,
+        entry: &str,
+) -> Option<PathBuf> {
+        let name = entry.rsplit('/').next().filter(|n| !n.is_empty())?;
+        store::resolve_manifest_path(meta_dir, name).ok()
+}
+
+/// 写出导出包，返回打包的备份文件数
+///
+/// 先写临时文件，完成后再改名，避免中断时留下不完整的导出包
+pub fn write(
+        path: &Path,
+        manifest: &BundleManifest,
+        data: &LibraryData,
+        images: &[(String, PathBuf)],
+        backup_root: Option<&Path>,
+) -> Result<usize, AppError> {
+        let tmp = path.with_extension("tmp");
+        let mut zip = ZipPacker::create(&tmp)?;
+
+        zip.add_bytes(MANIFEST_FILE, &to_json(manifest)?)?;
+        zip.add_bytes(LIBRARY_FILE, &to_json(data)?)?;
+        for (entry, file) in images {
+                zip.add_file(entry, file)?;
+        }
+
+        let mut backup_files = 0;
+        if let Some(root) = backup_root.filter(|r| r.is_dir()) {
+                // 打包期间不允许回收或写入快照，保证清单与 blob 一致
+                let _guard = store::lock()?;
+                for entry in WalkDir::new(root).into_iter().filter_map(|e| e.ok()) {
+                        if !entry.file_type().is_file()
+                                || entry.path().extension().is_some_and(|ext| ext == "tmp")
+                        {
+                                continue;
+                        }
+                        let Ok(rel) = entry.path().strip_prefix(root) else {
+                                continue;
+                        };
+                        let name = format!("{}/{}", BACKUP_DIR, store::to_manifest_path(rel));
+                        zip.add_file(&name, entry.path())?;
+                        backup_files += 1;
+                }
+        }
+
+        zip.finish()?;
+        std::fs::rename(&tmp, path)?;
+        Ok(backup_files)
+}
+
+/// 读取导出包的清单和记录，版本高于当前支持的导出包直接拒绝
+pub fn read(path: &Path) -> Result<BundleContents, AppError> {
+        let mut manifest: Option<BundleManifest> = None;
+        let mut library = None;
+        let mut images = HashSet::new();
+
+        read_zip_files(path, |entry, reader| {
+                match entry.name.as_str() {
+                        | MANIFEST_FILE => manifest = Some(from_json(reader, MANIFEST_FILE)?),
+                        | LIBRARY_FILE => {
+                                let mut buf = Vec::new();
+                                reader.read_to_end(&mut buf)?;
+                                library = Some(buf);
+                        },
+                        | name if name.starts_with(IMAGE_DIR) => {
+                                images.insert(name.to_string());
+                        },
+                        | _ => {},
+                }
+                Ok(())
+        })?;
+
+        let missing = |name: &str| AppError::Fs(format!("不是有效的游戏库导出包：缺少 {}", name));
+        let manifest = manifest.ok_or_else(|| missing(MANIFEST_FILE))?;
+        if manifest.version > BUNDLE_VERSION {
+                return Err(AppError::Fs(format!(
+                        "导出包版本 {} 高于当前支持的 {}，请先升级程序",
+                        manifest.version, BUNDLE_VERSION
+                )));
+        }
+        let library = library.ok_or_else(|| missing(LIBRARY_FILE))?;
+
+        Ok(BundleContents {
+                manifest,
+                data: from_json(&mut library.as_slice(), LIBRARY_FILE)?,
+                images,
+        })
+}
+
+/// 解压选中的图片，并把备份仓库合并到本地（已存在的文件保持不变）
+///
+/// 返回写入的图片数和备份文件数
+pub fn extract(
+        path: &Path,
+        images: &HashMap<String, PathBuf>,
+        backup_root: Option<&Path>,
+) -> Result<(usize, usize), AppError> {
+        let _guard = backup_root.map(|_| store::lock()).transpose()?;
+        let backup_prefix = format!("{}/", BACKUP_DIR);
+        let (mut image_count, mut backup_count) = (0, 0);
+
+        read_zip_files(path, |entry, reader| {
+                if let Some(target) = images.get(&entry.name) {
+                        write_file(reader, target)?;
+                        image_count += 1;
+                } else if let (Some(root), Some(rel)) =
+                        (backup_root, entry.name.strip_prefix(&backup_prefix))
+                {
+                        // blob 以内容命名、快照以时间命名，同名文件内容相同，无需覆盖
+                        let target = store::resolve_manifest_path(root, rel)?;
+                        if !target.exists() {
+                                write_file(reader, &target)?;
+                                backup_count += 1;
+                        }
+                }
+                Ok(())
+        })?;
+
+        Ok((image_count, backup_count))
+}
+
+fn write_file(
+        reader: &mut dyn Read,
+        target: &Path,
+) -> Result<(), AppError> {
+        if let Some(parent) = target.parent() {
+                std::fs::create_dir_all(parent)?;
+        }
+        let tmp = target.with_extension("tmp");
+        std::io::copy(reader, &mut File::create(&tmp)?)?;
+        std::fs::rename(&tmp, target)?;
+        Ok(())
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<Vec<u8>, AppError> {
+        serde_json::to_vec_pretty(value).map_err(|e| AppError::Generic(e.to_string()))
+}
+
+fn from_json<T: serde::de::DeserializeOwned>(
+        reader: &mut dyn Read,
+        name: &str,
+) -> Result<T, AppError> {
+        serde_json::from_reader(reader)
+                .map_err(|e| AppError::Fs(format!("{} 解析失败: {}", name, e)))
+}
@@ -0,0 +1,463 @@
+use std::{
+        collections::{BTreeMap, HashMap},
+        path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use serde_json::{Map, Value};
+use sqlx::SqlitePool;
+use tauri::async_runtime;
+use tauri_plugin_log::log::{info, warn};
+
+use crate::{
+        config::{entity::Storage, publish_changes, read_config},
+        error::AppError,
+        game::entity::SaveLocation,
+        library::{
+                bundle,
+                entity::{
+                        BUNDLE_VERSION, BundleManifest, ConflictResolution, ExportOptions,
+                        ExportReport, ImportConflict, ImportMode, ImportOptions, ImportReport,
+                        LibraryData, TableImport, TableRows, UnresolvedPath,
+                },
+                paths::PathRemap,
+                rows::{self, TABLES, TableSpec},
+        },
+        user::profile::active_id,
+};
+
+/// 存放绝对路径、导入时需要重映射的列：(表, 列, 重映射后是否检查本机上存在)
+const PATH_COLUMNS: &[(&str, &str, bool)] = &[
+        ("games", "abs_path", true),
+        ("games", "save_data_path", false),
+        ("games", "backup_data_path", false),
+        ("game_launch_profiles", "exe_path", true),
+        ("game_launch_profiles", "working_dir", false),
+        ("game_hooks", "working_dir", false),
+        ("game_screenshots", "file_path", false),
+        ("companions", "path", true),
+];
+/// 指向资源目录中本地图片的列
+const IMAGE_COLUMNS: &[&str] = &["local_cover", "local_background"];
+/// 按用户档案存放的表，旧版导出包中没有 `account_id` 列
+const PROFILE_TABLES: &[&str] = &[
+        "game_progress",
+        "game_play_sessions",
+        "game_session_edits",
+        "collections",
+        "shortcut",
+];
+/// 旧版导出包中存放在 `games` 表的进度列
+const PROGRESS_COLUMNS: &[&str] = &["is_passed", "passed_at", "play_time", "last_played_at"];
+
+// ── 导出 ──────────────────────────────────────────────────────────────────────
+
+/// 把整个游戏库写入一个导出包
+pub async fn export_library(
+        pool: &SqlitePool,
+        path: PathBuf,
+        options: ExportOptions,
+) -> Result<ExportReport, AppError> {
+        let mut config = read_config()?.clone();
+        // 账号授权信息不随导出包外流
+        config.auth = Default::default();
+        let storage = config.storage.clone();
+
+        let mut tables = BTreeMap::new();
+        for spec in TABLES {
+                tables.insert(
+                        spec.name.to_string(),
+                        rows::fetch_all(pool, spec.name).await?,
+                );
+        }
+        // 档案保存的设置同样不带授权信息
+        for account in tables.get_mut("account").into_iter().flatten() {
+                let overrides = str_of(account, "config_overrides")
+                        .and_then(|text| serde_json::from_str::<Map<String, Value>>(text).ok());
+                if let Some(mut overrides) = overrides {
+                        overrides.remove("auth");
+                        account.insert(
+                                "config_overrides".into(),
+                                Value::String(Value::Object(overrides).to_string()),
+                        );
+                }
+        }
+
+        let images: Vec<(String, PathBuf)> = if options.include_images {
+                tables.get("games")
+                        .into_iter()
+                        .flatten()
+                        .flat_map(|game| {
+                                let id = str_of(game, "id").unwrap_or_default().to_string();
+                                IMAGE_COLUMNS
+                                        .iter()
+                                        .filter_map(|col| str_of(game, col))
+                                        .map(PathBuf::from)
+                                        .filter(|p| p.is_file())
+                                        .filter_map(move |p| {
+                                                Some((bundle::image_entry(&id, &p)?, p))
+                                        })
+                                        .collect::<Vec<_>>()
+                        })
+                        .collect()
+        } else {
+                Vec::new()
+        };
+
+        let manifest = BundleManifest {
+                version: BUNDLE_VERSION,
+                app_version: env!("CARGO_PKG_VERSION").to_string(),
+                exported_at: Local::now(),
+                gal_root_dir: storage.gal_root_dir.to_string_lossy().into_owned(),
+                screenshot_path: storage.screenshot_path.to_string_lossy().into_owned(),
+                includes_images: options.include_images,
+                includes_backups: options.include_backups,
+                tables: tables
+                        .iter()
+                        .map(|(name, rows)| (name.clone(), rows.len()))
+                        .collect(),
+        };
+        let data = LibraryData { tables, config };
+        let backup_root = options.include_backups.then_some(storage.backup_save_path);
+
+        let image_count = images.len();
+        let (backup_file_count, manifest, path) = async_runtime::spawn_blocking(move || {
+                let count =
+                        bundle::write(&path, &manifest, &data, &images, backup_root.as_deref())?;
+                Ok::<_, AppError>((count, manifest, path))
+        })
+        .await
+        .map_err(|e| AppError::Fs(e.to_string()))??;
+
+        let size = std::fs::metadata(&path)?.len();
+        info!(
+                "游戏库已导出到 {:?}（{:.2} MB）",
+                path,
+                size as f64 / 1024.0 / 1024.0
+        );
+
+        Ok(ExportReport {
+                path: path.to_string_lossy().into_owned(),
+                manifest,
+                image_count,
+                backup_file_count,
+                size,
+        })
+}
+
+// ── 导入 ──────────────────────────────────────────────────────────────────────
+
+/// 从导出包导入游戏库
+///
+/// 所有记录在一个事务中写入；`dry_run` 时事务回滚，只返回报告
+pub async fn import_library(
+        pool: &SqlitePool,
+        path: PathBuf,
+        options: ImportOptions,
+) -> Result<ImportReport, AppError> {
+        let read_path = path.clone();
+        let contents = async_runtime::spawn_blocking(move || bundle::read(&read_path))
+                .await
+                .map_err(|e| AppError::Fs(e.to_string()))??;
+        let manifest = contents.manifest;
+        let mut data = contents.data;
+        assign_profiles(&mut data.tables, &active_id()?);
+
+        let local = read_config()?.clone();
+        let root = options
+                .gal_root_dir
+                .clone()
+                .filter(|r| !r.trim().is_empty())
+                .map(PathBuf::from)
+                .unwrap_or_else(|| local.storage.gal_root_dir.clone());
+        let remaps: Vec<PathRemap> = [
+                PathRemap::new(&manifest.gal_root_dir, &root),
+                PathRemap::new(&manifest.screenshot_path, &local.storage.screenshot_path),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        let mut report = ImportReport {
+                dry_run: options.dry_run,
+                manifest: manifest.clone(),
+                tables: Vec::new(),
+                conflicts: Vec::new(),
+                remapped_paths: 0,
+                unresolved_paths: Vec::new(),
+                image_count: 0,
+                backup_file_count: 0,
+                config_applied: false,
+        };
+
+        // 游戏 ID → 该游戏需要解压的图片（条目名 → 本机路径），只解压实际写入的游戏
+        let mut game_images: HashMap<String, Vec<(String, PathBuf)>> = HashMap::new();
+        let mut images = HashMap::new();
+
+        let mut tx = pool.begin().await?;
+        for spec in TABLES {
+                let Some(mut table) = data.tables.remove(spec.name) else {
+                        continue;
+                };
+                let columns = rows::columns(&mut tx, spec.name).await?;
+                let mut stats = TableImport {
+                        table: spec.name.to_string(),
+                        ..Default::default()
+                };
+
+                for row in &mut table {
+                        let key = spec.key_of(row);
+                        let remapped = remap_row(spec, &key, row, &remaps, &mut report);
+                        report.remapped_paths += remapped;
+                        if spec.name == "games" {
+                                let found = rewrite_images(
+                                        row,
+                                        &key,
+                                        &contents.images,
+                                        &local.storage.meta_save_path,
+                                );
+                                game_images.insert(key.clone(), found);
+                        }
+
+                        let written = match rows::find(&mut tx, spec, row).await? {
+                                | None => {
+                                        stats.added += 1;
+                                        true
+                                },
+                                | Some(existing) => {
+                                        let mut changed = rows::changed_columns(row, &existing);
+                                        changed.retain(|c| columns.contains(c));
+                                        if changed.is_empty() {
+                                                stats.unchanged += 1;
+                                                continue;
+                                        }
+                                        let resolution = match options.mode {
+                                                | ImportMode::Merge => {
+                                                        stats.kept += 1;
+                                                        ConflictResolution::KeptLocal
+                                                },
+                                                | ImportMode::Replace => {
+                                                        stats.replaced += 1;
+                                                        ConflictResolution::Replaced
+                                                },
+                                        };
+                                        report.conflicts.push(ImportConflict {
+                                                table: spec.name.to_string(),
+                                                key: key.clone(),
+                                                label: spec.label_of(&existing),
+                                                columns: changed,
+                                                resolution,
+                                        });
+                                        resolution == ConflictResolution::Replaced
+                                },
+                        };
+                        if written {
+                                rows::upsert(&mut tx, spec, row, &columns).await?;
+                                if let Some(found) = game_images.remove(&key) {
+                                        images.extend(found);
+                                }
+                        }
+                }
+                report.tables.push(stats);
+        }
+
+        if options.dry_run {
+                tx.rollback().await?;
+                report.image_count = images.len();
+                return Ok(report);
+        }
+
+        // 先解压文件再提交，解压失败时数据库保持原样
+        let backup_root = manifest
+                .includes_backups
+                .then(|| local.storage.backup_save_path.clone());
+        let (image_count, backup_file_count) = async_runtime::spawn_blocking(move || {
+                bundle::extract(&path, &images, backup_root.as_deref())
+        })
+        .await
+        .map_err(|e| AppError::Fs(e.to_string()))??;
+        tx.commit().await?;
+
+        report.image_count = image_count;
+        report.backup_file_count = backup_file_count;
+
+        if options.include_config {
+                let mut config = data.config;
+                config.storage = Storage {
+                        gal_root_dir: root,
+                        allow_downloading_resources: config.storage.allow_downloading_resources,
+                        auto_backup: config.storage.auto_backup,
+                        backup_retention: config.storage.backup_retention,
+                        backup_schedule: config.storage.backup_schedule,
+                        scan_depth: config.storage.scan_depth,
+                        watch_gal_root: config.storage.watch_gal_root,
+                        ..local.storage
+                };
+                config.auth = local.auth;
+                // 背景图是导出机器上的文件，保留本机设置
+                config.interface.global_background.path = local.interface.global_background.path;
+                config.basic.game_display_order = merge_order(
+                        local.basic.game_display_order,
+                        config.basic.game_display_order,
+                        options.mode,
+                );
+                publish_changes(config)?;
+                report.config_applied = true;
+        }
+
+        info!(
+                "游戏库导入完成：{} 处冲突，{} 个路径已重映射，{} 个路径在本机不存在",
+                report.conflicts.len(),
+                report.remapped_paths,
+                report.unresolved_paths.len()
+        );
+        Ok(report)
+}
+
+/// 让导出包中的档案数据落到本机的档案上
+///
+/// 导入的档案不改变本机的当前档案；旧版导出包没有档案，其中的游玩记录、收藏夹、快捷键
+/// 归到当前档案，`games` 中的进度列转为当前档案的 `game_progress`
+fn assign_profiles(
+        tables: &mut BTreeMap<String, TableRows>,
+        account_id: &str,
+) {
+        for account in tables.get_mut("account").into_iter().flatten() {
+                account.remove("is_active");
+        }
+
+        if !tables.contains_key("game_progress") {
+                let progress = tables
+                        .get("games")
+                        .into_iter()
+                        .flatten()
+                        .filter(|game| PROGRESS_COLUMNS.iter().any(|c| game.contains_key(*c)))
+                        .map(|game| {
+                                let mut row = Map::new();
+                                row.insert(
+                                        "game_id".into(),
+                                        game.get("id").cloned().unwrap_or_default(),
+                                );
+                                for column in PROGRESS_COLUMNS {
+                                        if let Some(value) = game.get(*column) {
+                                                row.insert(column.to_string(), value.clone());
+                                        }
+                                }
+                                row
+                        })
+                        .collect::<Vec<_>>();
+                if !progress.is_empty() {
+                        tables.insert("game_progress".into(), progress);
+                }
+        }
+
+        for table in PROFILE_TABLES {
+                for row in tables.get_mut(*table).into_iter().flatten() {
+                        if row.get("account_id").is_none_or(Value::is_null) {
+                                row.insert("account_id".into(), Value::String(account_id.into()));
+                        }
+                }
+        }
+}
+
+/// 按新的根目录改写记录中的绝对路径，返回改写的个数；改写后仍不存在的路径记入报告
+fn remap_row(
+        spec: &TableSpec,
+        key: &str,
+        row: &mut Map<String, Value>,
+        remaps: &[PathRemap],
+        report: &mut ImportReport,
+) -> usize {
+        let mut count = 0;
+        for (_, column, check) in PATH_COLUMNS.iter().filter(|(t, ..)| *t == spec.name) {
+                let Some(old) = str_of(row, column).filter(|p| !p.is_empty()) else {
+                        continue;
+                };
+                let new = PathRemap::apply_any(remaps, old);
+                let path = new.clone().unwrap_or_else(|| old.to_string());
+                if *check && !Path::new(&path).exists() {
+                        report.unresolved_paths.push(UnresolvedPath {
+                                table: spec.name.to_string(),
+                                key: key.to_string(),
+                                column: column.to_string(),
+                                path: path.clone(),
+                        });
+                }
+                if new.is_some() {
+                        row.insert(column.to_string(), Value::String(path));
+                        count += 1;
+                }
+        }
+
+        // 存档位置以 JSON 文本保存，逐个改写其中的绝对路径模板
+        if spec.name == "games"
+                && let Some(text) = str_of(row, "save_locations")
+        {
+                match serde_json::from_str::<Vec<SaveLocation>>(text) {
+                        | Ok(mut locations) => {
+                                let n = PathRemap::apply_locations(remaps, &mut locations);
+                                if n > 0 && let Ok(text) = serde_json::to_string(&locations) {
+                                        row.insert("save_locations".into(), Value::String(text));
+                                        count += n;
+                                }
+                        },
+                        | Err(e) => warn!("游戏 {} 的存档位置解析失败: {}", key, e),
+                }
+        }
+        count
+}
+
+/// 把本地图片列指向解压后的位置；导出包里没有对应图片、本机也不存在时清空，
+/// 界面会退回使用网络封面
+fn rewrite_images(
+        row: &mut Map<String, Value>,
+        game_id: &str,
+        available: &std::collections::HashSet<String>,
+        meta_dir: &Path,
+) -> Vec<(String, PathBuf)> {
+        let mut found = Vec::new();
+        for column in IMAGE_COLUMNS {
+                let Some(old) = str_of(row, column).map(PathBuf::from) else {
+                        continue;
+                };
+                let target = bundle::image_entry(game_id, &old)
+                        .filter(|entry| available.contains(entry))
+                        .and_then(|entry| Some((bundle::image_target(meta_dir, &entry)?, entry)));
+                let value = match target {
+                        | Some((target, entry)) => {
+                                let value = Value::String(target.to_string_lossy().into_owned());
+                                found.push((entry, target));
+                                value
+                        },
+                        | None if old.is_file() => continue,
+                        | None => Value::Null,
+                };
+                row.insert(column.to_string(), value);
+        }
+        found
+}
+
+/// 合并首页展示顺序：以保留的一方为准，另一方独有的游戏追加在后
+fn merge_order(
+        local: Vec<String>,
+        incoming: Vec<String>,
+        mode: ImportMode,
+) -> Vec<String> {
+        let (mut first, second) = match mode {
+                | ImportMode::Merge => (local, incoming),
+                | ImportMode::Replace => (incoming, local),
+        };
+        for id in second {
+                if !first.contains(&id) {
+                        first.push(id);
+                }
+        }
+        first
+}
+
+fn str_of<'a>(
+        row: &'a Map<String, Value>,
+        column: &str,
+) -> Option<&'a str> {
+        row.get(column).and_then(Value::as_str)
+}
@@ -0,0 +1,287 @@
+//! 游戏库维护相关数据结构（导入导出、路径迁移、体检）
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::config::Config;
+
+/// 导出包的格式版本，结构不兼容地变化时递增
+pub const BUNDLE_VERSION: u32 = 1;
+
+/// 一张表的全部记录，每条记录为「列名 → 值」
+pub type TableRows = Vec<Map<String, Value>>;
+
+/// 导出包的 `manifest.json`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BundleManifest {
+        pub version: u32,
+        /// 导出时的程序版本
+        pub app_version: String,
+        pub exported_at: DateTime<Local>,
+        /// 导出时的游戏根目录，导入时据此重映射游戏路径
+        pub gal_root_dir: String,
+        /// 导出时的截图目录
+        pub screenshot_path: String,
+        pub includes_images: bool,
+        pub includes_backups: bool,
+        /// 各表的记录数
+        pub tables: BTreeMap<String, usize>,
+}
+
+/// 导出包的 `library.json`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LibraryData {
+        pub tables: BTreeMap<String, TableRows>,
+        /// 导出时的配置（不含账号授权信息）
+        pub config: Config,
+}
+
+/// 导出选项
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ExportOptions {
+        /// 打包本地封面与背景图
+        pub include_images: bool,
+        /// 打包存档备份仓库
+        pub include_backups: bool,
+}
+
+/// 导出结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportReport {
+        pub path: String,
+        pub manifest: BundleManifest,
+        pub image_count: usize,
+        pub backup_file_count: usize,
+        /// 导出包大小（字节）
+        pub size: u64,
+}
+
+/// 本地已有同一条记录时的处理方式
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+        /// 保留本地记录，只导入本地没有的记录
+        #[default]
+        Merge,
+        /// 用导出包中的记录覆盖本地记录（本地独有的记录不受影响）
+        Replace,
+}
+
+/// 导入选项
+#[derive(Debug, Clone, Default)]
+pub struct ImportOptions {
+        /// 本机的游戏根目录，为空时使用当前配置
+        pub gal_root_dir: Option<String>,
+        pub mode: ImportMode,
+        /// 同时导入界面、系统等设置（本机的存储路径与账号授权不会被覆盖）
+        pub include_config: bool,
+        /// 只生成报告，不写入任何数据
+        pub dry_run: bool,
+}
+
+/// 单张表的导入统计
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TableImport {
+        pub table: String,
+        /// 本地没有、新增的记录
+        pub added: usize,
+        /// 覆盖了本地记录
+        pub replaced: usize,
+        /// 与本地记录冲突、保留了本地记录
+        pub kept: usize,
+        /// 与本地记录完全相同
+        pub unchanged: usize,
+}
+
+/// 冲突的处理结果
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictResolution {
+        KeptLocal,
+        Replaced,
+}
+
+/// 导出包与本地记录的一处冲突
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportConflict {
+        pub table: String,
+        /// 主键，多列主键以 `/` 连接
+        pub key: String,
+        /// 便于辨认的名称（游戏名、收藏夹名等）
+        pub label: Option<String>,
+        /// 取值不同的列
+        pub columns: Vec<String>,
+        pub resolution: ConflictResolution,
+}
+
+/// 重映射后在本机仍不存在的路径
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UnresolvedPath {
+        pub table: String,
+        pub key: String,
+        pub column: String,
+        pub path: String,
+}
+
+/// 导入结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportReport {
+        pub dry_run: bool,
+        pub manifest: BundleManifest,
+        pub tables: Vec<TableImport>,
+        pub conflicts: Vec<ImportConflict>,
+        /// 按新游戏根目录改写的路径数
+        pub remapped_paths: usize,
+        pub unresolved_paths: Vec<UnresolvedPath>,
+        pub image_count: usize,
+        /// 新写入的备份文件数（已存在的文件会跳过）
+        pub backup_file_count: usize,
+        pub config_applied: bool,
+}
+
+/// 一处路径改写
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PathChange {
+        /// 列名；存档位置模板为 `save_locations[序号]`，
+        /// 启动配置中的路径为 `launch_profiles[配置名].列名`
+        pub column: String,
+        pub old: String,
+        pub new: String,
+        /// 新路径在本机上是否存在
+        pub exists: bool,
+}
+
+/// 受路径迁移影响的游戏
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocatedGame {
+        pub game_id: String,
+        pub name: String,
+        pub changes: Vec<PathChange>,
+}
+
+/// 路径迁移结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocationReport {
+        pub old_prefix: String,
+        pub new_prefix: String,
+        /// 是否已写入；预览或存在缺失的目标路径时为 false
+        pub applied: bool,
+        pub games: Vec<RelocatedGame>,
+        /// 新路径不存在的个数
+        pub missing: usize,
+        /// 重新授权的路径
+        pub scopes: Vec<String>,
+        /// 游戏根目录也在旧前缀之下时，迁移后的新根目录
+        pub gal_root_dir: Option<String>,
+}
+
+/// 体检发现的问题类型
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum HealthIssueKind {
+        /// 启动程序不存在，但游戏目录还在
+        MissingExecutable,
+        /// 游戏目录不存在
+        MissingDirectory,
+        /// 存档位置无法解析（模板或过滤规则写错）
+        InvalidSaveLocation,
+        /// 存档目录不存在
+        MissingSavePath,
+        MissingCover,
+        MissingBackground,
+        /// 游戏目录不在已授权的路径中，界面无法读取其中的文件
+        Unauthorized,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+pub enum HealthSeverity {
+        /// 游戏无法启动或数据丢失
+        Error,
+        /// 不影响启动，但部分功能失效
+        Warning,
+}
+
+/// 本地图片的种类
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ImageKind {
+        Cover,
+        Background,
+}
+
+/// 修复建议
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum HealthFix {
+        /// 改用在游戏目录中重新识别到的启动程序
+        DetectExecutable { path: String },
+        /// 重新下载图片
+        RedownloadImage { image: ImageKind },
+        /// 清除失效的本地图片路径，界面改用网络图片
+        ClearLocalImage { image: ImageKind },
+        /// 授权访问游戏目录
+        Authorize { path: String },
+        /// 无法自动修复，需要手动处理
+        Manual { hint: String },
+}
+
+impl HealthFix {
+        /// 可以不经确认批量执行的修复
+        pub fn is_safe(&self) -> bool {
+                !matches!(self, HealthFix::Manual { .. })
+        }
+}
+
+/// 体检发现的一个问题
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthIssue {
+        pub game_id: String,
+        pub name: String,
+        pub kind: HealthIssueKind,
+        pub severity: HealthSeverity,
+        /// 出问题的路径
+        pub path: Option<String>,
+        pub detail: String,
+        pub fix: HealthFix,
+        pub safe: bool,
+}
+
+/// 体检报告
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthReport {
+        pub checked_at: DateTime<Local>,
+        pub game_count: usize,
+        /// 没有任何问题的游戏数
+        pub healthy_count: usize,
+        pub issues: Vec<HealthIssue>,
+}
+
+/// 执行失败的修复
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedFix {
+        pub issue: HealthIssue,
+        pub error: String,
+}
+
+/// 批量修复结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthFixReport {
+        pub applied: Vec<HealthIssue>,
+        pub failed: Vec<FailedFix>,
+        /// 需要手动处理、未执行的问题数
+        pub skipped: usize,
+}
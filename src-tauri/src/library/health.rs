@@ -0,0 +1,347 @@
+//! 游戏库体检
+//!
+//! 逐个检查游戏的启动程序、游戏目录、存档位置、本地图片和访问授权，
+//! 为每个问题给出修复建议。不需要用户判断的修复（重新识别启动程序、重新下载图片、
+//! 清除失效的图片路径、授权游戏目录）可以批量执行，其余只给出处理提示。
+
+use std::{
+        collections::{HashMap, HashSet},
+        path::{Path, PathBuf},
+};
+
+use chrono::Local;
+use sqlx::SqlitePool;
+use tauri::{AppHandle, async_runtime};
+use tauri_plugin_log::log::info;
+use uuid::Uuid;
+
+use crate::{
+        backup::location::SaveSet,
+        config::read_config,
+        error::AppError,
+        game::{
+                entity::{GameEvent, GameMeta, ResourceTarget},
+                progress,
+        },
+        infra::fs::detect_game_exe,
+        library::entity::{
+                FailedFix, HealthFix, HealthFixReport, HealthIssue, HealthIssueKind, HealthReport,
+                HealthSeverity, ImageKind,
+        },
+        message::{GAME_HUB, traits::MessageHub},
+        sys,
+        user::profile::active_id,
+};
+
+/// 检查整个游戏库
+pub async fn check(pool: &SqlitePool) -> Result<HealthReport, AppError> {
+        let games = progress::list(pool, &active_id()?).await?;
+        // 与启动时恢复权限的方式一致：记录的路径授权其所在目录
+        let allowed: Vec<PathBuf> = sqlx::query_scalar("SELECT path FROM authorized_scopes")
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|p: String| {
+                        let path = PathBuf::from(p);
+                        path.parent().map(Path::to_path_buf).unwrap_or(path)
+                })
+                .collect();
+        let allow_download = read_config()?.storage.allow_downloading_resources;
+
+        let game_count = games.len();
+        let issues: Vec<HealthIssue> = async_runtime::spawn_blocking(move || {
+                games.iter()
+                        .flat_map(|game| check_game(game, &allowed, allow_download))
+                        .collect()
+        })
+        .await
+        .map_err(|e| AppError::Fs(e.to_string()))?;
+
+        let unhealthy: HashSet<&str> = issues.iter().map(|i| i.game_id.as_str()).collect();
+        Ok(HealthReport {
+                checked_at: Local::now(),
+                game_count,
+                healthy_count: game_count - unhealthy.len(),
+                issues,
+        })
+}
+
+fn check_game(
+        game: &GameMeta,
+        allowed: &[PathBuf],
+        allow_download: bool,
+) -> Vec<HealthIssue> {
+        let exe = Path::new(&game.abs_path);
+        let game_dir = exe.parent().unwrap_or(exe);
+        let mut issues = Vec::new();
+        let mut push = |kind, severity, path: Option<&Path>, detail: &str, fix: HealthFix| {
+                issues.push(HealthIssue {
+                        game_id: game.id.clone(),
+                        name: game.name.clone(),
+                        kind,
+                        severity,
+                        path: path.map(|p| p.to_string_lossy().into_owned()),
+                        detail: detail.to_string(),
+                        safe: fix.is_safe(),
+                        fix,
+                })
+        };
+
+        // 启动程序与游戏目录
+        let dir_exists = game_dir.is_dir();
+        if !dir_exists {
+                push(
+                        HealthIssueKind::MissingDirectory,
+                        HealthSeverity::Error,
+                        Some(game_dir),
+                        "游戏目录不存在",
+                        HealthFix::Manual {
+                                hint: "游戏目录整体移动过时可以用路径迁移批量改写，否则请重新选择游戏目录"
+                                        .into(),
+                        },
+                );
+        } else if !exe.is_file() {
+                let fix = match detect_game_exe(&game_dir.to_string_lossy()) {
+                        | Ok(path) => HealthFix::DetectExecutable { path },
+                        | Err(e) => HealthFix::Manual {
+                                hint: format!("{}，请手动选择启动程序", e),
+                        },
+                };
+                push(
+                        HealthIssueKind::MissingExecutable,
+                        HealthSeverity::Error,
+                        Some(exe),
+                        "启动程序不存在",
+                        fix,
+                );
+        }
+
+        // 存档位置；游戏目录已经不在时，目录下的存档位置不再重复报告
+        match SaveSet::resolve(
+                &game.save_locations,
+                game.save_data_path.as_deref(),
+                game_dir,
+        ) {
+                | Ok(set) => {
+                        for root in &set.roots {
+                                if root.dir.is_dir()
+                                        || (!dir_exists && root.dir.starts_with(game_dir))
+                                {
+                                        continue;
+                                }
+                                push(
+                                        HealthIssueKind::MissingSavePath,
+                                        HealthSeverity::Warning,
+                                        Some(&root.dir),
+                                        "存档目录不存在",
+                                        HealthFix::Manual {
+                                                hint: "可以用存档目录发现重新查找存档位置".into(),
+                                        },
+                                );
+                        }
+                },
+                | Err(e) => push(
+                        HealthIssueKind::InvalidSaveLocation,
+                        HealthSeverity::Warning,
+                        None,
+                        &e.to_string(),
+                        HealthFix::Manual {
+                                hint: "请修改存档位置设置".into(),
+                        },
+                ),
+        }
+
+        // 本地图片
+        let images = [
+                (ImageKind::Cover, &game.local_cover, &game.cover),
+                (
+                        ImageKind::Background,
+                        &game.local_background,
+                        &game.background,
+                ),
+        ];
+        for (image, local, url) in images {
+                let Some(local) = local.as_deref().filter(|p| !p.is_empty()) else {
+                        continue;
+                };
+                if Path::new(local).is_file() {
+                        continue;
+                }
+                let (kind, detail) = match image {
+                        | ImageKind::Cover => (HealthIssueKind::MissingCover, "本地封面不存在"),
+                        | ImageKind::Background => {
+                                (HealthIssueKind::MissingBackground, "本地背景图不存在")
+                        },
+                };
+                let fix = if allow_download && url.starts_with("http") {
+                        HealthFix::RedownloadImage { image }
+                } else {
+                        HealthFix::ClearLocalImage { image }
+                };
+                push(
+                        kind,
+                        HealthSeverity::Warning,
+                        Some(Path::new(local)),
+                        detail,
+                        fix,
+                );
+        }
+
+        // 访问授权
+        if dir_exists && !allowed.iter().any(|dir| game_dir.starts_with(dir)) {
+                push(
+                        HealthIssueKind::Unauthorized,
+                        HealthSeverity::Warning,
+                        Some(game_dir),
+                        "游戏目录未授权访问",
+                        HealthFix::Authorize {
+                                path: game.abs_path.clone(),
+                        },
+                );
+        }
+
+        issues
+}
+
+// ── 批量修复 ──────────────────────────────────────────────────────────────────
+
+/// 重新体检并执行所有可以安全自动执行的修复；`game_ids` 为空时处理全部游戏
+pub async fn apply_safe_fixes(
+        handle: &AppHandle,
+        pool: &SqlitePool,
+        game_ids: Option<Vec<String>>,
+) -> Result<HealthFixReport, AppError> {
+        let report = check(pool).await?;
+        let wanted: Option<HashSet<String>> = game_ids.map(|ids| ids.into_iter().collect());
+
+        let mut result = HealthFixReport {
+                applied: Vec::new(),
+                failed: Vec::new(),
+                skipped: 0,
+        };
+        // 同一个游戏的封面和背景合并成一次下载，避免两个任务互相覆盖
+        let mut downloads: HashMap<String, Vec<HealthIssue>> = HashMap::new();
+
+        for issue in report.issues {
+                if wanted
+                        .as_ref()
+                        .is_some_and(|ids| !ids.contains(&issue.game_id))
+                {
+                        continue;
+                }
+                if !issue.safe {
+                        result.skipped += 1;
+                        continue;
+                }
+                let applied = match &issue.fix {
+                        | HealthFix::RedownloadImage { .. } => {
+                                downloads
+                                        .entry(issue.game_id.clone())
+                                        .or_default()
+                                        .push(issue);
+                                continue;
+                        },
+                        | HealthFix::DetectExecutable { path } => {
+                                sqlx::query("UPDATE games SET abs_path = ? WHERE id = ?")
+                                        .bind(path)
+                                        .bind(&issue.game_id)
+                                        .execute(pool)
+                                        .await
+                                        .map(|_| ())
+                                        .map_err(AppError::from)
+                        },
+                        | HealthFix::ClearLocalImage { image } => {
+                                let column = match image {
+                                        | ImageKind::Cover => "local_cover",
+                                        | ImageKind::Background => "local_background",
+                                };
+                                sqlx::query(&format!(
+                                        "UPDATE games SET {} = NULL WHERE id = ?",
+                                        column
+                                ))
+                                .bind(&issue.game_id)
+                                .execute(pool)
+                                .await
+                                .map(|_| ())
+                                .map_err(AppError::from)
+                        },
+                        | HealthFix::Authorize { path } => authorize(handle, pool, path).await,
+                        | HealthFix::Manual { .. } => continue,
+                };
+                match applied {
+                        | Ok(()) => result.applied.push(issue),
+                        | Err(e) => result.failed.push(FailedFix {
+                                issue,
+                                error: e.to_string(),
+                        }),
+                }
+        }
+
+        for (game_id, issues) in downloads {
+                match queue_download(pool, &game_id, &issues).await {
+                        | Ok(()) => result.applied.extend(issues),
+                        | Err(e) => {
+                                result.failed
+                                        .extend(issues.into_iter().map(|issue| FailedFix {
+                                                issue,
+                                                error: e.to_string(),
+                                        }))
+                        },
+                }
+        }
+
+        info!(
+                "游戏库修复：完成 {} · 失败 {} · 需手动处理 {}",
+                result.applied.len(),
+                result.failed.len(),
+                result.skipped
+        );
+        Ok(result)
+}
+
+/// 与 `authorize_path_access` 一致：记入授权列表，并立即授予所在目录的访问权限
+async fn authorize(
+        handle: &AppHandle,
+        pool: &SqlitePool,
+        path: &str,
+) -> Result<(), AppError> {
+        sqlx::query("INSERT OR IGNORE INTO authorized_scopes (id, path) VALUES (?, ?)")
+                .bind(Uuid::new_v4().to_string())
+                .bind(path)
+                .execute(pool)
+                .await?;
+        sys::allow_scope(handle, Path::new(path));
+        Ok(())
+}
+
+/// 为缺失的图片重新排队下载
+async fn queue_download(
+        pool: &SqlitePool,
+        game_id: &str,
+        issues: &[HealthIssue],
+) -> Result<(), AppError> {
+        let images: HashSet<ImageKind> = issues
+                .iter()
+                .filter_map(|i| match i.fix {
+                        | HealthFix::RedownloadImage { image } => Some(image),
+                        | _ => None,
+                })
+                .collect();
+        let target = match (
+                images.contains(&ImageKind::Cover),
+                images.contains(&ImageKind::Background),
+        ) {
+                | (true, false) => ResourceTarget::CoverOnly,
+                | (false, true) => ResourceTarget::BackgroundOnly,
+                | _ => ResourceTarget::All,
+        };
+
+        let meta = progress::find(pool, &active_id()?, game_id)
+                .await?
+                .ok_or_else(|| AppError::Resolve(game_id.to_string(), "游戏不存在".into()))?;
+        GAME_HUB.publish(GameEvent::GameResourceTask {
+                meta: Box::new(meta),
+                target,
+        });
+        Ok(())
+}
@@ -0,0 +1,13 @@
+//! 游戏库维护
+//!
+//! - 把整个游戏库（记录、配置、本地图片、存档备份）导出为一个可移植的导出包，
+//!   并在另一台机器上按新的游戏根目录重映射路径后导入
+//! - 游戏目录在本机搬家后，按新旧前缀批量迁移路径
+//! - 体检：找出启动程序、目录、存档、本地图片失效或未授权的游戏，并给出修复建议
+pub mod bundle;
+pub mod commands;
+pub mod entity;
+pub mod health;
+pub mod paths;
+pub mod relocate;
+pub mod rows;
@@ -0,0 +1,93 @@
+//! 路径前缀重映射
+//!
+//! 游戏库换了机器或盘符后，数据库里的绝对路径需要把旧的根目录换成新的根目录。
+//! 比较以路径组件为单位（`D:\Games` 不会匹配 `D:\Games2`），`/` 与 `\` 视为同一种分隔符，
+//! 因此 Windows 上导出的路径在其他系统上也能正确匹配。
+
+use std::path::{Path, PathBuf};
+
+use crate::game::entity::SaveLocation;
+
+/// 一条「旧前缀 → 新前缀」的映射
+#[derive(Debug, Clone)]
+pub struct PathRemap {
+        pub from: String,
+        pub to: PathBuf,
+}
+
+impl PathRemap {
+        /// 新旧前缀都非空且不同时才需要映射
+        pub fn new(
+                from: &str,
+                to: &Path,
+        ) -> Option<Self> {
+                let unchanged = components(from) == components(&to.to_string_lossy());
+                (!from.trim().is_empty() && !to.as_os_str().is_empty() && !unchanged).then(|| {
+                        Self {
+                                from: from.to_string(),
+                                to: to.to_path_buf(),
+                        }
+                })
+        }
+
+        /// 路径位于旧前缀之下时返回替换后的路径，否则返回 `None`
+        pub fn apply(
+                &self,
+                path: &str,
+        ) -> Option<String> {
+                let prefix = components(&self.from);
+                let parts = components(path);
+                if prefix.is_empty() || parts.len() < prefix.len() {
+                        return None;
+                }
+                let matched = prefix.iter().zip(&parts).all(|(a, b)| same_component(a, b));
+                if !matched {
+                        return None;
+                }
+
+                let mut out = self.to.clone();
+                out.extend(&parts[prefix.len()..]);
+                Some(out.to_string_lossy().into_owned())
+        }
+
+        /// 依次尝试多条映射，返回第一条命中的结果
+        pub fn apply_any(
+                remaps: &[PathRemap],
+                path: &str,
+        ) -> Option<String> {
+                remaps.iter().find_map(|r| r.apply(path))
+        }
+
+        /// 改写存档位置模板中的绝对路径（含占位符的模板不受影响），返回改写的个数
+        pub fn apply_locations(
+                remaps: &[PathRemap],
+                locations: &mut [SaveLocation],
+        ) -> usize {
+                let mut count = 0;
+                for loc in locations {
+                        if let Some(new) = Self::apply_any(remaps, &loc.template) {
+                                loc.template = new;
+                                count += 1;
+                        }
+                }
+                count
+        }
+}
+
+/// 按 `/` 和 `\` 拆分路径，忽略空组件
+fn components(path: &str) -> Vec<&str> {
+        path.split(['/', '\\']).filter(|c| !c.is_empty()).collect()
+}
+
+/// 盘符不区分大小写（`d:` 与 `D:`），其余组件精确比较
+fn same_component(
+        a: &str,
+        b: &str,
+) -> bool {
+        let is_drive = |s: &str| s.len() == 2 && s.ends_with(':');
+        if is_drive(a) && is_drive(b) {
+                a.eq_ignore_ascii_case(b)
+        } else {
+                a == b
+        }
+}
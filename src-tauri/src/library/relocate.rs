@@ -0,0 +1,257 @@
+//! 路径迁移
+//!
+//! 游戏目录整体搬家（换盘符、整理 `gal_root_dir`）后，把数据库中旧前缀下的路径批量改成新前缀：
+//! 先预览受影响的游戏并检查新路径是否存在，确认后在一个事务中改写，
+//! 同时把 `authorized_scopes` 中的旧路径换成新路径，重新授权文件访问。
+
+use std::{collections::HashMap, path::Path};
+
+use sqlx::{Row, SqlitePool, types::Json};
+use tauri_plugin_log::log::{info, warn};
+use uuid::Uuid;
+
+use crate::{
+        config::{publish_changes, read_config},
+        error::AppError,
+        game::entity::SaveLocation,
+        library::{
+                entity::{PathChange, RelocatedGame, RelocationReport},
+                paths::PathRemap,
+        },
+};
+
+/// 需要迁移的路径列
+const COLUMNS: &[&str] = &[
+        "abs_path",
+        "save_data_path",
+        "local_cover",
+        "local_background",
+        "config_path",
+];
+/// 启动配置中需要迁移的路径列
+const PROFILE_COLUMNS: &[&str] = &["exe_path", "working_dir"];
+
+/// 按新前缀改写游戏路径
+///
+/// `dry_run` 时只返回预览；存在新路径缺失时同样不写入，除非 `force`
+pub async fn relocate(
+        pool: &SqlitePool,
+        old_prefix: &str,
+        new_prefix: &str,
+        dry_run: bool,
+        force: bool,
+) -> Result<RelocationReport, AppError> {
+        let remap = PathRemap::new(old_prefix, Path::new(new_prefix)).ok_or_else(|| {
+                AppError::Resolve(
+                        old_prefix.to_string(),
+                        "新旧路径前缀不能为空，且不能相同".into(),
+                )
+        })?;
+
+        let rows = sqlx::query(
+                "SELECT id, name, abs_path, save_data_path, local_cover, local_background, \
+         config_path, save_locations FROM games",
+        )
+        .fetch_all(pool)
+        .await?;
+        let mut profiles: HashMap<String, Vec<_>> = HashMap::new();
+        for row in sqlx::query(
+                "SELECT id, game_id, name, exe_path, working_dir FROM game_launch_profiles",
+        )
+        .fetch_all(pool)
+        .await?
+        {
+                profiles.entry(row.try_get("game_id")?)
+                        .or_default()
+                        .push(row);
+        }
+
+        let mut games = Vec::new();
+        let mut updates = Vec::new();
+        for row in &rows {
+                let id: String = row.try_get("id")?;
+                let mut changes = Vec::new();
+
+                let mut values = Vec::new();
+                for column in COLUMNS {
+                        let old: Option<String> = row.try_get(*column)?;
+                        let new = old.as_deref().and_then(|p| remap.apply(p));
+                        if let (Some(old), Some(new)) = (&old, &new) {
+                                changes.push(change(column, old, new));
+                        }
+                        values.push(new.or(old));
+                }
+
+                let Json(mut locations): Json<Vec<SaveLocation>> = row.try_get("save_locations")?;
+                for (i, loc) in locations.iter_mut().enumerate() {
+                        if let Some(new) = remap.apply(&loc.template) {
+                                changes.push(change(
+                                        &format!("save_locations[{}]", i),
+                                        &loc.template,
+                                        &new,
+                                ));
+                                loc.template = new;
+                        }
+                }
+
+                let mut profile_values = Vec::new();
+                for profile in profiles.get(&id).into_iter().flatten() {
+                        let name: String = profile.try_get("name")?;
+                        let mut values = Vec::new();
+                        let mut changed = false;
+                        for column in PROFILE_COLUMNS {
+                                let old: Option<String> = profile.try_get(*column)?;
+                                let new = old.as_deref().and_then(|p| remap.apply(p));
+                                if let (Some(old), Some(new)) = (&old, &new) {
+                                        changes.push(change(
+                                                &format!("launch_profiles[{}].{}", name, column),
+                                                old,
+                                                new,
+                                        ));
+                                        changed = true;
+                                }
+                                values.push(new.or(old));
+                        }
+                        if changed {
+                                profile_values.push((profile.try_get::<String, _>("id")?, values));
+                        }
+                }
+
+                if changes.is_empty() {
+                        continue;
+                }
+                updates.push((id.clone(), values, locations, profile_values));
+                games.push(RelocatedGame {
+                        game_id: id,
+                        name: row.try_get("name")?,
+                        changes,
+                });
+        }
+
+        let gal_root_dir = read_config()?
+                .storage
+                .gal_root_dir
+                .to_str()
+                .and_then(|root| remap.apply(root));
+
+        let mut report = RelocationReport {
+                old_prefix: old_prefix.to_string(),
+                new_prefix: new_prefix.to_string(),
+                applied: false,
+                missing: games
+                        .iter()
+                        .flat_map(|g| &g.changes)
+                        .filter(|c| !c.exists)
+                        .count(),
+                games,
+                scopes: Vec::new(),
+                gal_root_dir,
+        };
+
+        if dry_run {
+                return Ok(report);
+        }
+        if report.missing > 0 && !force {
+                warn!(
+                        "路径迁移 {} → {}：{} 个新路径不存在，未写入",
+                        old_prefix, new_prefix, report.missing
+                );
+                return Ok(report);
+        }
+
+        let mut tx = pool.begin().await?;
+        for (id, values, locations, profile_values) in &updates {
+                sqlx::query(
+                        "UPDATE games SET abs_path=?, save_data_path=?, local_cover=?, local_background=?, \
+         config_path=?, save_locations=? WHERE id=?",
+                )
+                .bind(&values[0])
+                .bind(&values[1])
+                .bind(&values[2])
+                .bind(&values[3])
+                .bind(&values[4])
+                .bind(Json(locations))
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+                for (profile_id, values) in profile_values {
+                        sqlx::query(
+                                "UPDATE game_launch_profiles SET exe_path=?, working_dir=? WHERE id=?",
+                        )
+                        .bind(&values[0])
+                        .bind(&values[1])
+                        .bind(profile_id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+        }
+
+        // 已授权的旧路径换成新路径；新路径已有记录时删除旧记录
+        let scopes: Vec<(String, String)> =
+                sqlx::query_as("SELECT id, path FROM authorized_scopes")
+                        .fetch_all(&mut *tx)
+                        .await?;
+        for (id, path) in scopes {
+                let Some(new) = remap.apply(&path) else {
+                        continue;
+                };
+                sqlx::query(
+                        "UPDATE OR IGNORE authorized_scopes \
+         SET path=?, authorized_at=CURRENT_TIMESTAMP WHERE id=?",
+                )
+                .bind(&new)
+                .bind(&id)
+                .execute(&mut *tx)
+                .await?;
+                sqlx::query("DELETE FROM authorized_scopes WHERE id = ? AND path = ?")
+                        .bind(&id)
+                        .bind(&path)
+                        .execute(&mut *tx)
+                        .await?;
+                report.scopes.push(new);
+        }
+
+        // 迁移后的游戏程序路径也记入授权列表（授权其所在目录），下次启动时自动恢复
+        for (_, values, ..) in &updates {
+                let Some(exe) = &values[0] else {
+                        continue;
+                };
+                sqlx::query("INSERT OR IGNORE INTO authorized_scopes (id, path) VALUES (?, ?)")
+                        .bind(Uuid::new_v4().to_string())
+                        .bind(exe)
+                        .execute(&mut *tx)
+                        .await?;
+                if !report.scopes.contains(exe) {
+                        report.scopes.push(exe.clone());
+                }
+        }
+        tx.commit().await?;
+
+        if let Some(root) = &report.gal_root_dir {
+                let mut config = read_config()?.clone();
+                config.storage.gal_root_dir = root.into();
+                publish_changes(config)?;
+        }
+
+        report.applied = true;
+        info!(
+                "路径迁移 {} → {}：{} 个游戏",
+                old_prefix,
+                new_prefix,
+                report.games.len()
+        );
+        Ok(report)
+}
+
+fn change(
+        column: &str,
+        old: &str,
+        new: &str,
+) -> PathChange {
+        PathChange {
+                column: column.to_string(),
+                old: old.to_string(),
+                new: new.to_string(),
+                exists: Path::new(new).exists(),
+        }
+}
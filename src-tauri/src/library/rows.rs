@@ -0,0 +1,260 @@
+//! 按表读写记录
+//!
+//! 导出包以「列名 → 值」的形式保存记录，不依赖具体实体结构：
+//! 导入时只写入本地表中存在的列，缺少的列使用表的默认值，
+//! 因此不同版本之间的导出包可以互相导入。
+
+use std::collections::HashSet;
+
+use serde_json::{Map, Value};
+use sqlx::{
+        Column, Row, SqliteConnection, SqlitePool, TypeInfo, ValueRef,
+        query::Query,
+        sqlite::{Sqlite, SqliteArguments, SqliteRow},
+};
+
+use crate::{error::AppError, library::entity::TableRows};
+
+/// 参与导入导出的表
+pub struct TableSpec {
+        pub name: &'static str,
+        /// 主键列
+        pub key: &'static [&'static str],
+        /// 冲突报告中用于辨认记录的列
+        pub label: Option<&'static str>,
+}
+
+/// 按外键依赖排序：被引用的表在前
+pub const TABLES: &[TableSpec] = &[
+        TableSpec {
+                name: "account",
+                key: &["id"],
+                label: Some("user_name"),
+        },
+        TableSpec {
+                name: "games",
+                key: &["id"],
+                label: Some("name"),
+        },
+        TableSpec {
+                name: "game_progress",
+                key: &["account_id", "game_id"],
+                label: None,
+        },
+        TableSpec {
+                name: "game_launch_profiles",
+                key: &["id"],
+                label: Some("name"),
+        },
+        TableSpec {
+                name: "game_hooks",
+                key: &["id"],
+                label: Some("name"),
+        },
+        TableSpec {
+                name: "game_play_sessions",
+                key: &["id"],
+                label: Some("play_date"),
+        },
+        TableSpec {
+                name: "game_session_edits",
+                key: &["id"],
+                label: Some("created_at"),
+        },
+        TableSpec {
+                name: "game_screenshots",
+                key: &["id"],
+                label: Some("file_path"),
+        },
+        TableSpec {
+                name: "game_backup_policies",
+                key: &["game_id"],
+                label: None,
+        },
+        TableSpec {
+                name: "collections",
+                key: &["id"],
+                label: Some("name"),
+        },
+        TableSpec {
+                name: "collection_games",
+                key: &["collection_id", "game_id"],
+                label: None,
+        },
+        TableSpec {
+                name: "companions",
+                key: &["id"],
+                label: Some("name"),
+        },
+        TableSpec {
+                name: "shortcut",
+                key: &["account_id", "id"],
+                label: Some("key_combo"),
+        },
+];
+
+impl TableSpec {
+        /// 记录的主键，多列主键以 `/` 连接
+        pub fn key_of(
+                &self,
+                row: &Map<String, Value>,
+        ) -> String {
+                self.key.iter()
+                        .map(|k| display(row.get(*k).unwrap_or(&Value::Null)))
+                        .collect::<Vec<_>>()
+                        .join("/")
+        }
+
+        pub fn label_of(
+                &self,
+                row: &Map<String, Value>,
+        ) -> Option<String> {
+                self.label.and_then(|col| row.get(col)).map(display)
+        }
+}
+
+/// 读取整张表
+pub async fn fetch_all(
+        pool: &SqlitePool,
+        table: &str,
+) -> Result<TableRows, AppError> {
+        let rows = sqlx::query(&format!("SELECT * FROM \"{}\"", table))
+                .fetch_all(pool)
+                .await?;
+        Ok(rows.iter().map(to_map).collect())
+}
+
+/// 本地表的全部列名
+pub async fn columns(
+        conn: &mut SqliteConnection,
+        table: &str,
+) -> Result<HashSet<String>, AppError> {
+        let names: Vec<String> =
+                sqlx::query_scalar(&format!("SELECT name FROM pragma_table_info('{}')", table))
+                        .fetch_all(conn)
+                        .await?;
+        Ok(names.into_iter().collect())
+}
+
+/// 按主键查找本地记录
+pub async fn find(
+        conn: &mut SqliteConnection,
+        spec: &TableSpec,
+        row: &Map<String, Value>,
+) -> Result<Option<Map<String, Value>>, AppError> {
+        let filter =
+                spec.key.iter()
+                        .map(|k| format!("\"{}\" = ?", k))
+                        .collect::<Vec<_>>()
+                        .join(" AND ");
+        let sql = format!("SELECT * FROM \"{}\" WHERE {}", spec.name, filter);
+
+        let mut query = sqlx::query(&sql);
+        for k in spec.key {
+                query = bind(query, row.get(*k).unwrap_or(&Value::Null));
+        }
+        Ok(query.fetch_optional(conn).await?.as_ref().map(to_map))
+}
+
+/// 插入记录，主键已存在时覆盖；只写入本地表中存在的列
+pub async fn upsert(
+        conn: &mut SqliteConnection,
+        spec: &TableSpec,
+        row: &Map<String, Value>,
+        columns: &HashSet<String>,
+) -> Result<(), AppError> {
+        let cols: Vec<&String> = row.keys().filter(|c| columns.contains(*c)).collect();
+        let updates: Vec<String> = cols
+                .iter()
+                .filter(|c| !spec.key.contains(&c.as_str()))
+                .map(|c| format!("\"{0}\" = excluded.\"{0}\"", c))
+                .collect();
+        let on_conflict = if updates.is_empty() {
+                "DO NOTHING".to_string()
+        } else {
+                format!("DO UPDATE SET {}", updates.join(", "))
+        };
+
+        // 不用 INSERT OR REPLACE：它会先删除旧记录，级联删掉关联的游玩记录和截图
+        let sql = format!(
+                "INSERT INTO \"{}\" ({}) VALUES ({}) ON CONFLICT ({}) {}",
+                spec.name,
+                cols.iter()
+                        .map(|c| format!("\"{}\"", c))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                vec!["?"; cols.len()].join(", "),
+                spec.key.iter()
+                        .map(|k| format!("\"{}\"", k))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                on_conflict,
+        );
+
+        let mut query = sqlx::query(&sql);
+        for col in &cols {
+                query = bind(query, &row[col.as_str()]);
+        }
+        query.execute(conn).await?;
+        Ok(())
+}
+
+/// 两条记录中取值不同的列（只比较双方都有的列）
+pub fn changed_columns(
+        incoming: &Map<String, Value>,
+        local: &Map<String, Value>,
+) -> Vec<String> {
+        incoming.iter()
+                .filter(|(col, value)| local.get(*col).is_some_and(|v| v != *value))
+                .map(|(col, _)| col.clone())
+                .collect()
+}
+
+fn to_map(row: &SqliteRow) -> Map<String, Value> {
+        row.columns()
+                .iter()
+                .map(|col| (col.name().to_string(), value_at(row, col.ordinal())))
+                .collect()
+}
+
+/// 按实际存储类型取值（SQLite 的列类型只是建议，以值本身为准）
+fn value_at(
+        row: &SqliteRow,
+        index: usize,
+) -> Value {
+        let Ok(raw) = row.try_get_raw(index) else {
+                return Value::Null;
+        };
+        if raw.is_null() {
+                return Value::Null;
+        }
+        let value = match raw.type_info().name() {
+                | "INTEGER" => row.try_get_unchecked::<i64, _>(index).map(Value::from),
+                | "REAL" => row.try_get_unchecked::<f64, _>(index).map(Value::from),
+                | _ => row.try_get_unchecked::<String, _>(index).map(Value::from),
+        };
+        value.unwrap_or(Value::Null)
+}
+
+fn bind<'q>(
+        query: Query<'q, Sqlite, SqliteArguments<'q>>,
+        value: &Value,
+) -> Query<'q, Sqlite, SqliteArguments<'q>> {
+        match value {
+                | Value::Null => query.bind(None::<String>),
+                | Value::Bool(b) => query.bind(*b),
+                | Value::Number(n) => match n.as_i64() {
+                        | Some(i) => query.bind(i),
+                        | None => query.bind(n.as_f64()),
+                },
+                | Value::String(s) => query.bind(s.clone()),
+                | other => query.bind(other.to_string()),
+        }
+}
+
+fn display(value: &Value) -> String {
+        match value {
+                | Value::String(s) => s.clone(),
+                | other => other.to_string(),
+        }
+}
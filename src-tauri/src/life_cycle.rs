@@ -15,7 +15,8 @@ use window_vibrancy::{NSVisualEffectMaterial, apply_vibrancy};
 use window_vibrancy::apply_acrylic;
 
 use crate::{
-        companion, config, db, error::AppError, resource, screenshot, shortcut, sys, theme, tray,
+        backup, companion, config, db, error::AppError, game, resource, scanner, screenshot,
+        shortcut, sys, theme, tray, user,
 };
 
 /// 程序启动初始化（在 Tauri setup 回调中调用）
@@ -39,12 +40,16 @@ pub fn init(app: &mut App) -> Result<(), Box<dyn Error>> {
         db::init(handle)?; // 1. 数据库（其他模块依赖 Pool）
         sys::init(handle); // 2. 系统监控 + 权限恢复
         config::init(handle)?; // 3. 配置（依赖 Pool）
-        tray::init(handle)?; // 4. 托盘
-        companion::init(handle); // 5. 连携程序
-        shortcut::init(handle); // 6. 快捷键
-        screenshot::init(handle)?; // 7. 截图目录
-        resource::init(handle); // 8. 资源下载监听
-        theme::init(handle)?; // 9. 主题
+        user::profile::init(handle)?; // 4. 当前用户档案（依赖 Pool）
+        tray::init(handle)?; // 5. 托盘
+        companion::init(handle); // 6. 连携程序
+        shortcut::init(handle); // 7. 快捷键（依赖用户档案）
+        screenshot::init(handle)?; // 8. 截图目录
+        resource::init(handle); // 9. 资源下载监听
+        theme::init(handle)?; // 10. 主题
+        backup::init(handle); // 11. 定时备份（依赖 Pool 与配置）
+        scanner::init(handle); // 12. 游戏根目录监听（依赖 Pool 与配置）
+        game::init(handle); // 13. 遗留会话恢复与外部启动识别（依赖 Pool、配置与用户档案）
 
         log::info!("所有模块初始化完成");
         Ok(())
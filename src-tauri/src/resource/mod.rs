@@ -47,7 +47,7 @@ fn start(handle: &AppHandle) {
                                                         | Ok(_permit) => {
                                                                 if let Err(e) =
                                                                         download_game_assets(
-                                                                                &handle, meta,
+                                                                                &handle, *meta,
                                                                                 target,
                                                                         )
                                                                         .await
@@ -69,7 +69,7 @@ fn start(handle: &AppHandle) {
                                         let handle = handle.clone();
                                         tauri::async_runtime::spawn(async move {
                                                 if let Err(e) =
-                                                        download_user_avatar(&handle, meta).await
+                                                        download_user_avatar(&handle, *meta).await
                                                 {
                                                         error!("用户头像下载失败: {}", e);
                                                 }
@@ -156,25 +156,12 @@ async fn download_user_avatar(
         let local_path = download_file(&user.avatar, &save_path).await?;
 
         let pool = handle.state::<Pool<Sqlite>>();
-        sqlx::query(
-                "INSERT OR REPLACE INTO account \
-         (id, user_name, avatar, games_count, favorite_game, total_play_time, \
-          games_completed_number, selected_disk, last_play_at, created_at) \
-         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
-        )
-        .bind(&user.id)
-        .bind(&user.user_name)
-        .bind(&local_path)
-        .bind(user.games_count)
-        .bind(&user.favorite_game)
-        .bind(user.total_play_time)
-        .bind(user.games_completed_number)
-        .bind(&user.selected_disk)
-        .bind(user.last_play_at)
-        .bind(user.created_at)
-        .execute(&*pool)
-        .await
-        .map_err(AppError::from)?;
+        sqlx::query("UPDATE account SET avatar = ? WHERE id = ?")
+                .bind(&local_path)
+                .bind(&user.id)
+                .execute(&*pool)
+                .await
+                .map_err(AppError::from)?;
 
         info!("用户头像已更新");
         Ok(())
@@ -0,0 +1,54 @@
+use std::{collections::HashSet, path::Path};
+
+use sqlx::SqlitePool;
+use tauri::async_runtime;
+use tauri_plugin_log::log::info;
+
+use crate::{
+        config::read_config,
+        error::AppError,
+        scanner::{entity::ScanReport, scan},
+};
+
+/// 扫描游戏根目录，`depth` 为空时使用配置中的扫描层数
+pub async fn scan_library(
+        pool: &SqlitePool,
+        depth: Option<u32>,
+) -> Result<ScanReport, AppError> {
+        let (root, default_depth) = {
+                let config = read_config()?;
+                (
+                        config.storage.gal_root_dir.clone(),
+                        config.storage.scan_depth,
+                )
+        };
+        if root.as_os_str().is_empty() || !root.is_dir() {
+                return Err(AppError::Resolve(
+                        root.to_string_lossy().into_owned(),
+                        "游戏根目录未设置或不存在".into(),
+                ));
+        }
+        let depth = depth.unwrap_or(default_depth).max(1);
+
+        let known: HashSet<String> = sqlx::query_scalar("SELECT abs_path FROM games")
+                .fetch_all(pool)
+                .await?
+                .into_iter()
+                .map(|p: String| {
+                        let path = Path::new(&p);
+                        scan::normalize(path.parent().unwrap_or(path))
+                })
+                .collect();
+
+        let report = async_runtime::spawn_blocking(move || scan::scan(&root, depth, &known))
+                .await
+                .map_err(|e| AppError::Fs(e.to_string()))?;
+
+        info!(
+                "扫描游戏根目录 {}：检查 {} 个目录，发现 {} 个候选",
+                report.root,
+                report.scanned_dirs,
+                report.candidates.len()
+        );
+        Ok(report)
+}
@@ -0,0 +1,56 @@
+//! 游戏库扫描相关数据结构
+
+use serde::Serialize;
+
+use crate::{engine::entity::Engine, game::entity::GameMeta};
+
+/// 扫描发现的候选游戏文件夹
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GameCandidate {
+        pub dir: String,
+        /// 推断出的启动程序；没有 `.exe` 时为空，导入前需要手动选择
+        pub exe: Option<String>,
+        pub engine: Option<Engine>,
+        /// 置信度（0-100）
+        pub score: u32,
+        /// 判断依据
+        pub signals: Vec<String>,
+        /// 预填好的游戏记录，可直接交给 `add_new_game_list` 批量导入
+        pub meta: GameMeta,
+}
+
+/// 扫描结果
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanReport {
+        pub root: String,
+        pub depth: u32,
+        /// 检查过的目录数
+        pub scanned_dirs: usize,
+        /// 已在游戏库中、被跳过的目录数
+        pub known_dirs: usize,
+        /// 按置信度从高到低排列
+        pub candidates: Vec<GameCandidate>,
+}
+
+/// 监听到游戏目录被删除（`library-removed` 事件）
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RemovedGame {
+        pub game_id: String,
+        pub name: String,
+        pub dir: String,
+        /// 记录中的启动程序
+        pub exe: String,
+}
+
+/// 监听到游戏目录改名或移动，游戏路径已自动改写（`library-renamed` 事件）
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamedFolder {
+        pub from: String,
+        pub to: String,
+        /// 路径被改写的游戏
+        pub game_ids: Vec<String>,
+}
@@ -0,0 +1,15 @@
+//! 游戏库扫描
+//!
+//! 在游戏根目录（`Storage::gal_root_dir`）下查找尚未加入游戏库的游戏文件夹，
+//! 返回带置信度的候选，供前端确认后批量导入；
+//! 并在后台监听根目录，实时同步新增、删除和改名的游戏文件夹。
+pub mod commands;
+pub mod entity;
+pub mod scan;
+pub mod watcher;
+
+use tauri::AppHandle;
+
+pub fn init(handle: &AppHandle) {
+        watcher::start(handle);
+}
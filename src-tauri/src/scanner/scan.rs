@@ -0,0 +1,249 @@
+//! 游戏文件夹识别
+//!
+//! 从游戏根目录逐层向下查找，每个目录只看其直接内容：
+//! - 目录中直接有可作为启动程序的 `.exe`（见 [`crate::infra::exe::rank`]）
+//! - 能按特征文件识别出游戏引擎（见 [`crate::engine::detect`]）
+//!
+//! 被识别为游戏的目录不再向下查找，避免把游戏内部的工具目录当成另一个游戏。
+
+use std::{
+        collections::HashSet,
+        path::{Path, PathBuf},
+};
+
+use uuid::Uuid;
+
+use crate::{
+        engine::{detect, entity::Engine},
+        game::entity::GameMeta,
+        infra::exe::{self, ExeRole},
+        scanner::entity::{GameCandidate, ScanReport},
+};
+
+/// 低于此分数的目录不作为候选
+const MIN_SCORE: u32 = 30;
+
+/// 游戏目录中常见的资源子目录
+const RESOURCE_DIRS: &[&str] = &[
+        "data", "save", "savedata", "movie", "movies", "bgm", "voice", "sound", "system", "plugin",
+];
+/// 不会包含游戏的目录
+const SKIPPED_DIRS: &[&str] = &["$recycle.bin", "system volume information", "__macosx"];
+
+/// 一个目录的游戏证据
+struct Evidence {
+        exe: Option<String>,
+        engine: Option<Engine>,
+        score: u32,
+        signals: Vec<String>,
+}
+
+/// 扫描游戏根目录，`known` 为已在游戏库中的目录（经 [`normalize`] 处理）
+pub fn scan(
+        root: &Path,
+        depth: u32,
+        known: &HashSet<String>,
+) -> ScanReport {
+        let mut report = ScanReport {
+                root: root.to_string_lossy().into_owned(),
+                depth,
+                scanned_dirs: 0,
+                known_dirs: 0,
+                candidates: Vec::new(),
+        };
+        walk(root, 1, depth, known, &mut report);
+        report.candidates
+                .sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.dir.cmp(&b.dir)));
+        report
+}
+
+fn walk(
+        dir: &Path,
+        level: u32,
+        depth: u32,
+        known: &HashSet<String>,
+        report: &mut ScanReport,
+) {
+        for sub in subdirs(dir) {
+                report.scanned_dirs += 1;
+                if known.contains(&normalize(&sub)) {
+                        report.known_dirs += 1;
+                        continue;
+                }
+                if let Some(candidate) = inspect(&sub) {
+                        report.candidates.push(candidate);
+                        continue;
+                }
+                if level < depth {
+                        walk(&sub, level + 1, depth, known, report);
+                }
+        }
+}
+
+/// 检查单个目录：本身是游戏文件夹时直接返回，否则在 `levels` 层内向下查找
+pub fn candidates_in(
+        dir: &Path,
+        levels: u32,
+        known: &HashSet<String>,
+) -> Vec<GameCandidate> {
+        if known.contains(&normalize(dir)) {
+                return Vec::new();
+        }
+        if let Some(candidate) = inspect(dir) {
+                return vec![candidate];
+        }
+        let mut report = ScanReport {
+                root: dir.to_string_lossy().into_owned(),
+                depth: levels,
+                scanned_dirs: 0,
+                known_dirs: 0,
+                candidates: Vec::new(),
+        };
+        if levels > 0 {
+                walk(dir, 1, levels, known, &mut report);
+        }
+        report.candidates
+}
+
+/// 判断一个目录是不是游戏文件夹，是则返回候选
+pub fn inspect(dir: &Path) -> Option<GameCandidate> {
+        let evidence = evidence(dir)?;
+        let name = dir
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+        let dir_str = dir.to_string_lossy().replace('\\', "/");
+
+        Some(GameCandidate {
+                meta: GameMeta {
+                        id: Uuid::new_v4().to_string(),
+                        name,
+                        abs_path: evidence.exe.clone().unwrap_or_else(|| dir_str.clone()),
+                        is_displayed: true,
+                        engine: evidence.engine,
+                        ..Default::default()
+                },
+                dir: dir_str,
+                exe: evidence.exe,
+                engine: evidence.engine,
+                score: evidence.score,
+                signals: evidence.signals,
+        })
+}
+
+fn evidence(dir: &Path) -> Option<Evidence> {
+        let entries: Vec<PathBuf> = std::fs::read_dir(dir)
+                .ok()?
+                .flatten()
+                .map(|e| e.path())
+                .collect();
+        let lower_name = |p: &Path| {
+                p.file_name()
+                        .map(|n| n.to_string_lossy().to_lowercase())
+                        .unwrap_or_default()
+        };
+        let dirs: Vec<String> = entries
+                .iter()
+                .filter(|p| p.is_dir())
+                .map(|p| lower_name(p))
+                .collect();
+
+        // 只看目录本身的程序，子目录里的程序可能属于更深一层的游戏
+        let ranked: Vec<_> = exe::rank(dir)
+                .unwrap_or_default()
+                .into_iter()
+                .filter(|c| Path::new(&c.path).parent() == Some(dir))
+                .collect();
+        let best = exe::best_game(&ranked);
+        let exe = best.map(|c| c.path.clone());
+        let engine = detect::detect(dir);
+        let mut score = 0;
+        let mut signals = Vec::new();
+
+        if let Some(best) = best {
+                let exe_name = lower_name(Path::new(&best.path));
+                let folder = normalize_name(&lower_name(dir));
+                let stem = normalize_name(exe_name.trim_end_matches(".exe"));
+
+                match best.role {
+                        | ExeRole::Game | ExeRole::Launcher => {
+                                score += 40;
+                                signals.push(format!("启动程序 {}", exe_name));
+                        },
+                        | ExeRole::Config => {
+                                score += 15;
+                                signals.push(format!("只有设置程序 {}", exe_name));
+                        },
+                        | ExeRole::Installer | ExeRole::Tool => {
+                                score += 15;
+                                signals.push(format!("只有安装程序 {}", exe_name));
+                        },
+                }
+                if stem.contains("game") || (!folder.is_empty() && stem == folder) {
+                        score += 10;
+                        signals.push("启动程序名与游戏匹配".into());
+                }
+        }
+        if let Some(m) = &engine {
+                score += 45;
+                signals.push(format!(
+                        "{} 引擎：{}",
+                        m.engine.label(),
+                        m.evidence.join("、")
+                ));
+        }
+        let resources: Vec<&String> = dirs
+                .iter()
+                .filter(|d| RESOURCE_DIRS.contains(&d.as_str()))
+                .collect();
+        if !resources.is_empty() {
+                score += 5;
+                signals.push(format!(
+                        "资源目录 {}",
+                        resources
+                                .iter()
+                                .map(|s| s.as_str())
+                                .collect::<Vec<_>>()
+                                .join(", ")
+                ));
+        }
+
+        let score = score.min(100);
+        (score >= MIN_SCORE).then_some(Evidence {
+                exe,
+                engine: engine.map(|m| m.engine),
+                score,
+                signals,
+        })
+}
+
+/// 直接子目录，跳过隐藏目录与系统目录
+fn subdirs(dir: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+                return Vec::new();
+        };
+        let mut dirs: Vec<PathBuf> = entries
+                .flatten()
+                .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+                .filter(|e| {
+                        let name = e.file_name().to_string_lossy().to_lowercase();
+                        !name.starts_with('.') && !SKIPPED_DIRS.contains(&name.as_str())
+                })
+                .map(|e| e.path())
+                .collect();
+        dirs.sort();
+        dirs
+}
+
+/// 统一分隔符与大小写，用于比较目录是否相同
+pub fn normalize(path: &Path) -> String {
+        path.to_string_lossy()
+                .replace('\\', "/")
+                .trim_end_matches('/')
+                .to_lowercase()
+}
+
+/// 只保留字母和数字，用于比较启动程序与文件夹名
+fn normalize_name(s: &str) -> String {
+        s.chars().filter(|c| c.is_alphanumeric()).collect()
+}
@@ -0,0 +1,410 @@
+//! 游戏根目录监听
+//!
+//! 在后台监听 `Storage::gal_root_dir`，文件变动平息 [`DEBOUNCE`] 后按根目录下的一级目录整理：
+//! - 游戏目录改名或移动：自动改写游戏路径（与路径迁移相同），推送 `library-renamed`
+//! - 游戏目录被删除：推送 `library-removed`
+//! - 出现新的游戏文件夹：推送 `library-candidate`
+//!
+//! 复制游戏、解压安装包会在短时间内产生大量事件，去抖后只整理一次。
+//! 根目录与开关（`Storage::watch_gal_root`）修改后无需重启即生效。
+
+use std::{
+        collections::HashSet,
+        path::{Path, PathBuf},
+        time::{Duration, Instant},
+};
+
+use notify::{
+        Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+        event::{ModifyKind, RenameMode},
+};
+use sqlx::SqlitePool;
+use tauri::{AppHandle, Emitter, Manager, async_runtime};
+use tauri_plugin_log::log::{error, info, warn};
+use tokio::sync::mpsc::{self, UnboundedSender};
+
+use crate::{
+        config::read_config,
+        error::AppError,
+        library::relocate,
+        scanner::{
+                entity::{GameCandidate, RemovedGame, RenamedFolder},
+                scan,
+        },
+        sys,
+};
+
+/// 最后一次变动之后等待多久再整理
+const DEBOUNCE: Duration = Duration::from_secs(5);
+/// 没有事件时检查配置与去抖的间隔
+const TICK: Duration = Duration::from_secs(1);
+
+pub fn start(handle: &AppHandle) {
+        let Some(pool) = handle.try_state::<SqlitePool>() else {
+                error!("未能获取 SqlitePool，游戏根目录监听未启动");
+                return;
+        };
+        let pool = pool.inner().clone();
+        let handle = handle.clone();
+        let (tx, mut rx) = mpsc::unbounded_channel::<Event>();
+
+        async_runtime::spawn(async move {
+                let mut watch = Watch::default();
+                let mut pending = Pending::default();
+                let mut emitted = Emitted::default();
+
+                loop {
+                        if let Ok(Some(event)) = tokio::time::timeout(TICK, rx.recv()).await {
+                                pending.push(event);
+                        }
+
+                        let (root, depth) = watch.sync(&tx);
+                        let Some(root) = root else {
+                                pending = Pending::default();
+                                continue;
+                        };
+                        if !pending.settled() {
+                                continue;
+                        }
+
+                        let batch = std::mem::take(&mut pending);
+                        if let Err(e) =
+                                flush(&handle, &pool, &root, depth, batch, &mut emitted).await
+                        {
+                                error!("整理游戏根目录变动失败: {}", e);
+                        }
+                }
+        });
+
+        info!("游戏根目录监听已启动");
+}
+
+// ── 监听器 ────────────────────────────────────────────────────────────────────
+
+/// 当前的监听状态，随配置重建
+#[derive(Default)]
+struct Watch {
+        /// 上次按配置处理过的根目录（关闭监听时为空）
+        target: Option<PathBuf>,
+        watcher: Option<RecommendedWatcher>,
+}
+
+impl Watch {
+        /// 按当前配置开启、关闭或切换监听，返回正在监听的根目录与扫描层数
+        fn sync(
+                &mut self,
+                tx: &UnboundedSender<Event>,
+        ) -> (Option<PathBuf>, u32) {
+                let (target, depth) = match read_config() {
+                        | Ok(cfg) => (
+                                (cfg.storage.watch_gal_root
+                                        && !cfg.storage.gal_root_dir.as_os_str().is_empty())
+                                .then(|| cfg.storage.gal_root_dir.clone()),
+                                cfg.storage.scan_depth.max(1),
+                        ),
+                        | Err(e) => {
+                                error!("读取游戏根目录配置失败: {}", e);
+                                return (self.watching(), 1);
+                        },
+                };
+
+                if target != self.target {
+                        self.watcher = None;
+                        if let Some(root) = &target {
+                                match watch(root, tx.clone()) {
+                                        | Ok(watcher) => {
+                                                info!("开始监听游戏根目录 {}", root.display());
+                                                self.watcher = Some(watcher);
+                                        },
+                                        | Err(e) => warn!(
+                                                "无法监听游戏根目录 {}: {}",
+                                                root.display(),
+                                                e
+                                        ),
+                                }
+                        }
+                        self.target = target;
+                }
+                (self.watching(), depth)
+        }
+
+        fn watching(&self) -> Option<PathBuf> {
+                self.watcher.as_ref().and(self.target.clone())
+        }
+}
+
+fn watch(
+        root: &Path,
+        tx: UnboundedSender<Event>,
+) -> Result<RecommendedWatcher, AppError> {
+        let mut watcher =
+                notify::recommended_watcher(move |res: notify::Result<Event>| match res {
+                        | Ok(event) => {
+                                let _ = tx.send(event);
+                        },
+                        | Err(e) => warn!("游戏根目录监听出错: {}", e),
+                })
+                .map_err(|e| AppError::Fs(e.to_string()))?;
+        watcher.watch(root, RecursiveMode::Recursive)
+                .map_err(|e| AppError::Fs(e.to_string()))?;
+        Ok(watcher)
+}
+
+// ── 去抖 ──────────────────────────────────────────────────────────────────────
+
+/// 尚未整理的变动
+#[derive(Default)]
+struct Pending {
+        paths: HashSet<PathBuf>,
+        /// 改名 / 移动（旧路径 → 新路径）
+        renames: Vec<(PathBuf, PathBuf)>,
+        /// 只收到了旧路径、等待新路径的改名
+        rename_from: Option<PathBuf>,
+        last: Option<Instant>,
+}
+
+impl Pending {
+        fn push(
+                &mut self,
+                event: Event,
+        ) {
+                match event.kind {
+                        | EventKind::Access(_) => return,
+                        | EventKind::Modify(ModifyKind::Name(RenameMode::Both)) => {
+                                if let [from, to] = &event.paths[..] {
+                                        self.rename(from.clone(), to.clone());
+                                }
+                        },
+                        | EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                                self.rename_from = event.paths.first().cloned();
+                        },
+                        | EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                                if let (Some(from), Some(to)) =
+                                        (self.rename_from.take(), event.paths.first())
+                                {
+                                        self.rename(from, to.clone());
+                                }
+                        },
+                        | _ => {},
+                }
+                self.paths.extend(event.paths);
+                self.last = Some(Instant::now());
+        }
+
+        fn rename(
+                &mut self,
+                from: PathBuf,
+                to: PathBuf,
+        ) {
+                // 部分平台会同时发出分开的和成对的改名事件
+                if !self.renames.iter().any(|(f, t)| *f == from && *t == to) {
+                        self.renames.push((from, to));
+                }
+        }
+
+        fn settled(&self) -> bool {
+                self.last.is_some_and(|last| last.elapsed() >= DEBOUNCE)
+        }
+}
+
+/// 已经推送过的事件，避免同一个目录反复提示
+#[derive(Default)]
+struct Emitted {
+        /// 候选目录（经 [`scan::normalize`] 处理）
+        candidates: HashSet<String>,
+        /// 已被删除的游戏目录（同上）
+        removed: HashSet<String>,
+}
+
+// ── 整理 ──────────────────────────────────────────────────────────────────────
+
+struct Changes {
+        removed: Vec<RemovedGame>,
+        candidates: Vec<GameCandidate>,
+}
+
+async fn flush(
+        handle: &AppHandle,
+        pool: &SqlitePool,
+        root: &Path,
+        depth: u32,
+        batch: Pending,
+        emitted: &mut Emitted,
+) -> Result<(), AppError> {
+        // 1. 目录改名：游戏路径跟着改
+        for (from, to) in &batch.renames {
+                if to.is_dir() {
+                        rename(handle, pool, from, to).await?;
+                }
+        }
+
+        // 2. 按根目录下的一级目录检查删除与新增
+        let units: HashSet<PathBuf> = batch
+                .paths
+                .iter()
+                .filter_map(|p| unit_of(root, p))
+                .collect();
+        if units.is_empty() {
+                return Ok(());
+        }
+        let games: Vec<(String, String, String)> =
+                sqlx::query_as("SELECT id, name, abs_path FROM games")
+                        .fetch_all(pool)
+                        .await?;
+
+        let Changes {
+                mut removed,
+                mut candidates,
+        } = async_runtime::spawn_blocking(move || inspect(&units, depth, &games))
+                .await
+                .map_err(|e| AppError::Fs(e.to_string()))?;
+
+        // 3. 改名被拆成「删除 + 新增」上报时，按启动程序文件名配对
+        let mut i = 0;
+        while i < removed.len() {
+                let paired = candidates.iter().position(|c| same_exe(&removed[i], c));
+                match paired {
+                        | Some(j) => {
+                                let game = removed.remove(i);
+                                let candidate = candidates.remove(j);
+                                rename(
+                                        handle,
+                                        pool,
+                                        Path::new(&game.dir),
+                                        Path::new(&candidate.dir),
+                                )
+                                .await?;
+                        },
+                        | None => i += 1,
+                }
+        }
+
+        // 4. 推送
+        emitted.candidates.retain(|dir| Path::new(dir).is_dir());
+        emitted.removed.retain(|dir| !Path::new(dir).is_dir());
+        for candidate in candidates {
+                if emitted
+                        .candidates
+                        .insert(scan::normalize(Path::new(&candidate.dir)))
+                {
+                        info!("发现新的游戏文件夹 {}", candidate.dir);
+                        let _ = handle.emit("library-candidate", &candidate);
+                }
+        }
+        for game in removed {
+                if emitted
+                        .removed
+                        .insert(scan::normalize(Path::new(&game.dir)))
+                {
+                        info!("游戏 {} 的目录已被删除：{}", game.name, game.dir);
+                        let _ = handle.emit("library-removed", &game);
+                }
+        }
+        Ok(())
+}
+
+/// 改写旧目录下所有游戏的路径并重新授权
+async fn rename(
+        handle: &AppHandle,
+        pool: &SqlitePool,
+        from: &Path,
+        to: &Path,
+) -> Result<(), AppError> {
+        let report = relocate::relocate(
+                pool,
+                &from.to_string_lossy(),
+                &to.to_string_lossy(),
+                false,
+                false,
+        )
+        .await?;
+        if !report.applied || report.games.is_empty() {
+                return Ok(());
+        }
+        for scope in &report.scopes {
+                sys::allow_scope(handle, Path::new(scope));
+        }
+
+        info!(
+                "游戏目录 {} 改名为 {}，已改写 {} 个游戏的路径",
+                from.display(),
+                to.display(),
+                report.games.len()
+        );
+        let _ = handle.emit(
+                "library-renamed",
+                RenamedFolder {
+                        from: report.old_prefix,
+                        to: report.new_prefix,
+                        game_ids: report.games.into_iter().map(|g| g.game_id).collect(),
+                },
+        );
+        Ok(())
+}
+
+/// 检查变动过的一级目录：其中目录已不存在的游戏，以及尚未加入游戏库的游戏文件夹
+fn inspect(
+        units: &HashSet<PathBuf>,
+        depth: u32,
+        games: &[(String, String, String)],
+) -> Changes {
+        let dir_of = |abs_path: &str| {
+                let path = Path::new(abs_path);
+                path.parent().unwrap_or(path).to_path_buf()
+        };
+        let known: HashSet<String> = games
+                .iter()
+                .map(|(_, _, abs_path)| scan::normalize(&dir_of(abs_path)))
+                .collect();
+
+        let removed = games
+                .iter()
+                .filter_map(|(id, name, abs_path)| {
+                        let dir = dir_of(abs_path);
+                        (units.iter().any(|u| dir.starts_with(u)) && !dir.is_dir()).then(|| {
+                                RemovedGame {
+                                        game_id: id.clone(),
+                                        name: name.clone(),
+                                        dir: dir.to_string_lossy().into_owned(),
+                                        exe: abs_path.clone(),
+                                }
+                        })
+                })
+                .collect();
+        let candidates = units
+                .iter()
+                .filter(|u| u.is_dir())
+                .flat_map(|u| scan::candidates_in(u, depth - 1, &known))
+                .collect();
+
+        Changes {
+                removed,
+                candidates,
+        }
+}
+
+/// 路径在根目录下的一级目录；根目录本身与根目录外的路径返回 `None`
+fn unit_of(
+        root: &Path,
+        path: &Path,
+) -> Option<PathBuf> {
+        let first = path.strip_prefix(root).ok()?.components().next()?;
+        Some(root.join(first))
+}
+
+/// 被删除的游戏与新候选的启动程序文件名相同
+fn same_exe(
+        game: &RemovedGame,
+        candidate: &GameCandidate,
+) -> bool {
+        let name = |p: &str| {
+                Path::new(p)
+                        .file_name()
+                        .map(|n| n.to_string_lossy().to_lowercase())
+        };
+        candidate
+                .exe
+                .as_deref()
+                .and_then(name)
+                .is_some_and(|exe| name(&game.exe).is_some_and(|old| old == exe))
+}
@@ -11,13 +11,14 @@ use crate::{
         companion,
         config::read_config,
         error::AppError,
-        game::{RUNNING_GAMES, entity::GameMeta},
+        game::{RUNNING_GAMES, progress},
         infra::process::{kill_by_pid, toggle_windows_by_pids},
         screenshot,
         shortcut::entity::ShortcutSetting,
+        user::profile::active_id,
 };
 
-/// 从数据库重新加载并注册所有全局快捷键
+/// 从数据库重新加载并注册当前档案的所有全局快捷键
 pub async fn refresh_shortcuts<R: Runtime>(handle: &AppHandle<R>) -> Result<(), AppError> {
         let gs = handle.global_shortcut();
 
@@ -35,8 +36,9 @@ pub async fn refresh_shortcuts<R: Runtime>(handle: &AppHandle<R>) -> Result<(),
         let pool = handle.state::<SqlitePool>();
         let shortcuts = sqlx::query_as::<_, ShortcutSetting>(
                 "SELECT id, key_combo, is_global FROM shortcut \
-         WHERE is_global = 1 AND key_combo IS NOT NULL",
+         WHERE account_id = ? AND is_global = 1 AND key_combo IS NOT NULL",
         )
+        .bind(active_id()?)
         .fetch_all(&*pool)
         .await
         .map_err(AppError::from)?;
@@ -81,15 +83,12 @@ async fn dispatch<R: Runtime>(
         match id {
                 // 启动上次游玩的游戏
                 | "launch_last" => {
-                        let game = sqlx::query_as::<_, GameMeta>(
-                                "SELECT * FROM games ORDER BY last_played_at DESC LIMIT 1",
-                        )
-                        .fetch_optional(&*pool)
-                        .await
-                        .map_err(AppError::from)?;
+                        let game = progress::last_played(&pool, &active_id()?).await?;
 
                         if let Some(g) = game {
-                                crate::game::commands::launch((*pool).clone(), g).await?;
+                                let profile =
+                                        crate::game::profile::resolve(&pool, &g, None).await?;
+                                crate::game::commands::launch((*pool).clone(), g, profile).await?;
                         }
                 },
 
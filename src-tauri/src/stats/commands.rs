@@ -0,0 +1,190 @@
+//! 游玩统计查询，均按用户档案统计
+
+use chrono::{Local, NaiveDate};
+use sqlx::SqlitePool;
+
+use crate::{
+        error::AppError,
+        stats::entity::{
+                DateRange, DeveloperCompletion, GameTotal, Granularity, HeatmapCell,
+                LongestSession, PeriodTotal, PlayHeatmap, PlayRecords, PlayStreak,
+        },
+};
+
+/// 档案下已结束且在范围内的会话；`play_date` 与日期字符串比较即按本地日期筛选，可以走索引
+pub const IN_RANGE: &str = "account_id = ? AND is_open = 0 AND play_date >= ? AND play_date < ?";
+
+/// 有游玩记录的日期按连续段分组：日期减去序号相同的即为同一段
+const STREAKS_SQL: &str = "WITH days AS ( \
+         SELECT DISTINCT substr(play_date, 1, 10) AS day FROM game_play_sessions \
+         WHERE account_id = ? AND is_open = 0 AND duration_minutes > 0 \
+     ), islands AS ( \
+         SELECT day, julianday(day) - ROW_NUMBER() OVER (ORDER BY day) AS island FROM days \
+     ) \
+     SELECT MIN(day) AS start, MAX(day) AS \"end\", COUNT(*) AS days FROM islands GROUP BY island";
+
+/// 按日 / 周 / 月汇总游玩时长，没有记录的时段不返回
+pub async fn period_totals(
+        pool: &SqlitePool,
+        account_id: &str,
+        range: DateRange,
+        granularity: Granularity,
+) -> Result<Vec<PeriodTotal>, AppError> {
+        let period = match granularity {
+                | Granularity::Day => "substr(play_date, 1, 10)",
+                | Granularity::Week => "date(substr(play_date, 1, 10), '-6 days', 'weekday 1')",
+                | Granularity::Month => "substr(play_date, 1, 7) || '-01'",
+        };
+        let (start, end) = bounds(range)?;
+        sqlx::query_as(&format!(
+                "SELECT {} AS period_start, SUM(duration_minutes) AS minutes, COUNT(*) AS sessions, \
+                 COUNT(DISTINCT game_id) AS games FROM game_play_sessions WHERE {} \
+                 GROUP BY period_start ORDER BY period_start",
+                period, IN_RANGE
+        ))
+        .bind(account_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// 各游戏在范围内的游玩时长，按时长从多到少
+pub async fn game_totals(
+        pool: &SqlitePool,
+        account_id: &str,
+        range: DateRange,
+) -> Result<Vec<GameTotal>, AppError> {
+        let (start, end) = bounds(range)?;
+        sqlx::query_as(
+                "SELECT s.game_id, g.name, SUM(s.duration_minutes) AS minutes, COUNT(*) AS sessions, \
+         MIN(s.play_date) AS first_played_at, \
+         MAX(COALESCE(s.last_played_at, s.play_date)) AS last_played_at \
+         FROM game_play_sessions s JOIN games g ON g.id = s.game_id \
+         WHERE s.account_id = ? AND s.is_open = 0 AND s.play_date >= ? AND s.play_date < ? \
+         GROUP BY s.game_id ORDER BY minutes DESC, g.name",
+        )
+        .bind(account_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// 最长的一次会话，以及当前与历史最长的连续游玩天数
+pub async fn records(
+        pool: &SqlitePool,
+        account_id: &str,
+) -> Result<PlayRecords, AppError> {
+        let longest_session: Option<LongestSession> = sqlx::query_as(
+                "SELECT s.id AS session_id, s.game_id, g.name, s.play_date, s.duration_minutes \
+         FROM game_play_sessions s JOIN games g ON g.id = s.game_id \
+         WHERE s.account_id = ? AND s.is_open = 0 \
+         ORDER BY s.duration_minutes DESC, s.play_date DESC LIMIT 1",
+        )
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await?;
+
+        let longest_streak: Option<PlayStreak> = sqlx::query_as(&format!(
+                "{} ORDER BY days DESC, \"end\" DESC LIMIT 1",
+                STREAKS_SQL
+        ))
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await?;
+
+        // 今天还没玩时，截至昨天的连续天数仍然算数
+        let yesterday = Local::now()
+                .date_naive()
+                .pred_opt()
+                .unwrap_or(NaiveDate::MIN);
+        let current_streak: Option<PlayStreak> = sqlx::query_as(&format!(
+                "{} HAVING MAX(day) >= ? ORDER BY \"end\" DESC LIMIT 1",
+                STREAKS_SQL
+        ))
+        .bind(account_id)
+        .bind(yesterday)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(PlayRecords {
+                longest_session,
+                current_streak,
+                longest_streak,
+        })
+}
+
+/// 按星期与小时统计范围内的游玩时长
+pub async fn heatmap(
+        pool: &SqlitePool,
+        account_id: &str,
+        range: DateRange,
+) -> Result<PlayHeatmap, AppError> {
+        let (start, end) = bounds(range)?;
+        let cells: Vec<HeatmapCell> = sqlx::query_as(&format!(
+                "SELECT CAST(strftime('%w', substr(play_date, 1, 10)) AS INTEGER) AS weekday, \
+                 CAST(substr(play_date, 12, 2) AS INTEGER) AS hour, \
+                 SUM(duration_minutes) AS minutes, COUNT(*) AS sessions \
+                 FROM game_play_sessions WHERE {} GROUP BY weekday, hour ORDER BY weekday, hour",
+                IN_RANGE
+        ))
+        .bind(account_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+
+        let mut by_hour = vec![0; 24];
+        let mut by_weekday = vec![0; 7];
+        for cell in &cells {
+                if let Some(total) = by_hour.get_mut(cell.hour as usize) {
+                        *total += cell.minutes;
+                }
+                if let Some(total) = by_weekday.get_mut(cell.weekday as usize) {
+                        *total += cell.minutes;
+                }
+        }
+        Ok(PlayHeatmap {
+                cells,
+                by_hour,
+                by_weekday,
+        })
+}
+
+/// 各开发商在档案下的通关率，未填写开发商的游戏不计
+pub async fn developer_completion(
+        pool: &SqlitePool,
+        account_id: &str,
+) -> Result<Vec<DeveloperCompletion>, AppError> {
+        sqlx::query_as(
+                "SELECT TRIM(g.developer) AS developer, COUNT(*) AS games, \
+         COALESCE(SUM(p.is_passed != 0), 0) AS passed, \
+         CAST(COALESCE(SUM(p.is_passed != 0), 0) AS REAL) / COUNT(*) AS completion_rate, \
+         COALESCE(SUM(p.play_time), 0) AS play_time FROM games g \
+         LEFT JOIN game_progress p ON p.game_id = g.id AND p.account_id = ? \
+         WHERE TRIM(g.developer) != '' \
+         GROUP BY TRIM(g.developer) ORDER BY games DESC, developer",
+        )
+        .bind(account_id)
+        .fetch_all(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// 范围的起止：结束日期取次日零点，与 `play_date` 按字符串比较
+pub fn bounds(range: DateRange) -> Result<(NaiveDate, NaiveDate), AppError> {
+        if range.start > range.end {
+                return Err(AppError::Generic(format!(
+                        "开始日期 {} 晚于结束日期 {}",
+                        range.start, range.end
+                )));
+        }
+        let end = range
+                .end
+                .succ_opt()
+                .ok_or_else(|| AppError::Generic(format!("无效的结束日期 {}", range.end)))?;
+        Ok((range.start, end))
+}
@@ -0,0 +1,185 @@
+//! 游玩统计数据结构
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::prelude::FromRow;
+
+/// 统计的日期范围（本地日期，首尾都包含）
+#[derive(Debug, Deserialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct DateRange {
+        pub start: NaiveDate,
+        pub end: NaiveDate,
+}
+
+/// 汇总的时间粒度
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Granularity {
+        Day,
+        /// 周一开始的自然周
+        Week,
+        Month,
+}
+
+/// 某一天 / 周 / 月的游玩汇总
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodTotal {
+        /// 这一段的第一天
+        pub period_start: NaiveDate,
+        pub minutes: i64,
+        pub sessions: i64,
+        /// 玩过的游戏数
+        pub games: i64,
+}
+
+/// 单个游戏在某段时间内的游玩汇总
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct GameTotal {
+        pub game_id: String,
+        pub name: String,
+        pub minutes: i64,
+        pub sessions: i64,
+        pub first_played_at: DateTime<Local>,
+        pub last_played_at: DateTime<Local>,
+}
+
+/// 最长的一次会话
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct LongestSession {
+        pub session_id: String,
+        pub game_id: String,
+        pub name: String,
+        pub play_date: DateTime<Local>,
+        pub duration_minutes: i64,
+}
+
+/// 连续有游玩记录的天数
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayStreak {
+        pub start: NaiveDate,
+        pub end: NaiveDate,
+        pub days: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayRecords {
+        pub longest_session: Option<LongestSession>,
+        /// 截至今天（或昨天，今天还没玩时）的连续天数
+        pub current_streak: Option<PlayStreak>,
+        pub longest_streak: Option<PlayStreak>,
+}
+
+/// 热力图的一格：会话按开始时间计入
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct HeatmapCell {
+        /// 0 为周日
+        pub weekday: i64,
+        pub hour: i64,
+        pub minutes: i64,
+        pub sessions: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlayHeatmap {
+        /// 星期 × 小时，只包含有记录的格子
+        pub cells: Vec<HeatmapCell>,
+        /// 各小时（0–23）的游玩分钟数
+        pub by_hour: Vec<i64>,
+        /// 各星期（0 为周日）的游玩分钟数
+        pub by_weekday: Vec<i64>,
+}
+
+/// 某个开发商的通关情况
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct DeveloperCompletion {
+        pub developer: String,
+        pub games: i64,
+        pub passed: i64,
+        /// 通关数 / 游戏数
+        pub completion_rate: f64,
+        /// 这些游戏的总游玩分钟数
+        pub play_time: i64,
+}
+
+// ── 年度回顾 ──────────────────────────────────────────────────────────────────
+
+/// 某一年的游玩回顾
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearReview {
+        pub year: i32,
+        pub generated_at: DateTime<Local>,
+        pub total_minutes: i64,
+        pub sessions: i64,
+        pub games_played: i64,
+        pub days_played: i64,
+        /// 游玩时长最多的游戏
+        pub top_games: Vec<ReviewGame>,
+        /// 这一年第一次玩的游戏，按首次游玩时间排序
+        pub new_games: Vec<ReviewGame>,
+        /// 这一年通关的游戏，按通关时间排序
+        pub completed: Vec<CompletedGame>,
+        /// 1–12 月，没有记录的月份为 0
+        pub monthly: Vec<MonthTotal>,
+        pub longest_session: Option<LongestSession>,
+        pub screenshots: i64,
+        pub most_screenshotted: Option<ScreenshotGame>,
+}
+
+/// 回顾中的一个游戏：时长为这一年内的统计
+#[derive(Debug, Serialize, Clone, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ReviewGame {
+        pub game_id: String,
+        pub name: String,
+        pub minutes: i64,
+        pub sessions: i64,
+        /// 第一次游玩的时间（不限于这一年）
+        pub first_played_at: DateTime<Local>,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletedGame {
+        pub game_id: String,
+        pub name: String,
+        pub passed_at: DateTime<Local>,
+        /// 累计游玩分钟数
+        pub play_time: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonthTotal {
+        pub month: u32,
+        pub minutes: i64,
+        pub sessions: i64,
+        pub games: i64,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotGame {
+        pub game_id: String,
+        pub name: String,
+        pub screenshots: i64,
+}
+
+/// 年度回顾导出的文件
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct YearReviewExport {
+        pub json_path: PathBuf,
+        pub html_path: PathBuf,
+}
@@ -0,0 +1,8 @@
+//! 游玩统计模块
+//!
+//! 所有统计都在 SQL 中按 `game_play_sessions` 聚合，只统计已结束的会话。
+//! `play_date` 以带时区偏移的本地时间字符串保存，前 10 / 19 个字符即本地日期 / 时间，
+//! 因此按日期分组、按小时统计时直接截取字符串，不经过 SQLite 的 UTC 换算。
+pub mod commands;
+pub mod entity;
+pub mod review;
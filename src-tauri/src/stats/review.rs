@@ -0,0 +1,411 @@
+//! 年度回顾
+//!
+//! 汇总档案一年的会话、通关记录与截图（截图不分档案），生成结构化的回顾数据，
+//! 并可导出为 JSON 与一个自包含的 HTML 页面（图表为内嵌 SVG，不引用任何外部资源）。
+
+use std::{fmt::Write as _, path::Path};
+
+use chrono::{Datelike, Local, NaiveDate, NaiveTime, TimeZone};
+use sqlx::SqlitePool;
+use tauri::async_runtime;
+
+use crate::{
+        error::AppError,
+        stats::{
+                commands::{self as stc, IN_RANGE},
+                entity::{
+                        CompletedGame, DateRange, Granularity, LongestSession, MonthTotal,
+                        ReviewGame, ScreenshotGame, YearReview, YearReviewExport,
+                },
+        },
+};
+
+/// 回顾中列出的游玩时长最多的游戏数
+const TOP_GAMES: usize = 10;
+
+/// 生成档案某一年的回顾
+pub async fn year_review(
+        pool: &SqlitePool,
+        account_id: &str,
+        year: i32,
+) -> Result<YearReview, AppError> {
+        let range = year_range(year)?;
+        let (start, end) = stc::bounds(range)?;
+
+        let (total_minutes, sessions, games_played, days_played): (i64, i64, i64, i64) =
+                sqlx::query_as(&format!(
+                        "SELECT COALESCE(SUM(duration_minutes), 0), COUNT(*), \
+                         COUNT(DISTINCT game_id), COUNT(DISTINCT substr(play_date, 1, 10)) \
+                         FROM game_play_sessions WHERE {}",
+                        IN_RANGE
+                ))
+                .bind(account_id)
+                .bind(start)
+                .bind(end)
+                .fetch_one(pool)
+                .await?;
+
+        // 这一年玩过的游戏，附带第一次游玩的时间
+        let mut games: Vec<ReviewGame> = sqlx::query_as(
+                "SELECT s.game_id, g.name, SUM(s.duration_minutes) AS minutes, COUNT(*) AS sessions, \
+         (SELECT MIN(f.play_date) FROM game_play_sessions f \
+          WHERE f.account_id = s.account_id AND f.is_open = 0 AND f.game_id = s.game_id) \
+         AS first_played_at \
+         FROM game_play_sessions s JOIN games g ON g.id = s.game_id \
+         WHERE s.account_id = ? AND s.is_open = 0 AND s.play_date >= ? AND s.play_date < ? \
+         GROUP BY s.game_id ORDER BY minutes DESC, g.name",
+        )
+        .bind(account_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+        let mut new_games: Vec<ReviewGame> = games
+                .iter()
+                .filter(|g| g.first_played_at.date_naive() >= range.start)
+                .cloned()
+                .collect();
+        new_games.sort_by_key(|g| g.first_played_at);
+        games.truncate(TOP_GAMES);
+
+        let completed: Vec<CompletedGame> = sqlx::query_as(
+                "SELECT p.game_id, g.name, p.passed_at, p.play_time \
+         FROM game_progress p JOIN games g ON g.id = p.game_id \
+         WHERE p.account_id = ? AND p.is_passed = 1 AND p.passed_at >= ? AND p.passed_at < ? \
+         ORDER BY p.passed_at",
+        )
+        .bind(account_id)
+        .bind(start)
+        .bind(end)
+        .fetch_all(pool)
+        .await?;
+
+        let mut monthly: Vec<MonthTotal> = (1..=12)
+                .map(|month| MonthTotal {
+                        month,
+                        minutes: 0,
+                        sessions: 0,
+                        games: 0,
+                })
+                .collect();
+        for total in stc::period_totals(pool, account_id, range, Granularity::Month).await? {
+                if let Some(month) = monthly.get_mut(total.period_start.month0() as usize) {
+                        month.minutes = total.minutes;
+                        month.sessions = total.sessions;
+                        month.games = total.games;
+                }
+        }
+
+        let longest_session: Option<LongestSession> = sqlx::query_as(
+                "SELECT s.id AS session_id, s.game_id, g.name, s.play_date, s.duration_minutes \
+         FROM game_play_sessions s JOIN games g ON g.id = s.game_id \
+         WHERE s.account_id = ? AND s.is_open = 0 AND s.play_date >= ? AND s.play_date < ? \
+         ORDER BY s.duration_minutes DESC, s.play_date DESC LIMIT 1",
+        )
+        .bind(account_id)
+        .bind(start)
+        .bind(end)
+        .fetch_optional(pool)
+        .await?;
+
+        // 截图时间由数据库按 UTC 写入，按本地时间的年份边界换算后比较
+        let (shot_start, shot_end) = (utc_timestamp(range.start)?, utc_timestamp(end)?);
+        let screenshots: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM game_screenshots WHERE created_at >= ? AND created_at < ?",
+        )
+        .bind(&shot_start)
+        .bind(&shot_end)
+        .fetch_one(pool)
+        .await?;
+        let most_screenshotted: Option<ScreenshotGame> = sqlx::query_as(
+                "SELECT s.game_id, g.name, COUNT(*) AS screenshots \
+         FROM game_screenshots s JOIN games g ON g.id = s.game_id \
+         WHERE s.created_at >= ? AND s.created_at < ? \
+         GROUP BY s.game_id ORDER BY screenshots DESC, g.name LIMIT 1",
+        )
+        .bind(&shot_start)
+        .bind(&shot_end)
+        .fetch_optional(pool)
+        .await?;
+
+        Ok(YearReview {
+                year,
+                generated_at: Local::now(),
+                total_minutes,
+                sessions,
+                games_played,
+                days_played,
+                top_games: games,
+                new_games,
+                completed,
+                monthly,
+                longest_session,
+                screenshots,
+                most_screenshotted,
+        })
+}
+
+/// 生成回顾并在目录下写入 `year-in-review-<年份>.json` 与 `.html`
+pub async fn export(
+        pool: &SqlitePool,
+        account_id: &str,
+        year: i32,
+        dir: &Path,
+) -> Result<YearReviewExport, AppError> {
+        let review = year_review(pool, account_id, year).await?;
+        let json =
+                serde_json::to_vec_pretty(&review).map_err(|e| AppError::Generic(e.to_string()))?;
+        let html = render_html(&review);
+        let json_path = dir.join(format!("year-in-review-{}.json", year));
+        let html_path = dir.join(format!("year-in-review-{}.html", year));
+
+        let (dir, json_file, html_file) = (dir.to_path_buf(), json_path.clone(), html_path.clone());
+        async_runtime::spawn_blocking(move || {
+                std::fs::create_dir_all(&dir)?;
+                std::fs::write(&json_file, json)?;
+                std::fs::write(&html_file, html)
+        })
+        .await
+        .map_err(|e| AppError::Fs(e.to_string()))??;
+
+        Ok(YearReviewExport {
+                json_path,
+                html_path,
+        })
+}
+
+/// 本地时间某日零点对应的 UTC 时间，格式与 SQLite 的 `CURRENT_TIMESTAMP` 相同
+fn utc_timestamp(date: NaiveDate) -> Result<String, AppError> {
+        Local.from_local_datetime(&date.and_time(NaiveTime::MIN))
+                .earliest()
+                .map(|time| time.naive_utc().format("%Y-%m-%d %H:%M:%S").to_string())
+                .ok_or_else(|| AppError::Generic(format!("无法换算本地时间 {}", date)))
+}
+
+fn year_range(year: i32) -> Result<DateRange, AppError> {
+        let date = |month, day| {
+                NaiveDate::from_ymd_opt(year, month, day)
+                        .ok_or_else(|| AppError::Generic(format!("无效的年份 {}", year)))
+        };
+        Ok(DateRange {
+                start: date(1, 1)?,
+                end: date(12, 31)?,
+        })
+}
+
+// ── HTML 导出 ─────────────────────────────────────────────────────────────────
+
+const STYLE: &str = "\
+body{margin:0;background:#111827;color:#e5e7eb;font-family:system-ui,-apple-system,'Segoe UI',\
+'PingFang SC','Microsoft YaHei',sans-serif}\
+main{max-width:860px;margin:0 auto;padding:40px 24px}\
+h1{font-size:32px;margin:0 0 4px}h2{font-size:20px;margin:40px 0 12px;color:#6ee7b7}\
+.muted{color:#9ca3af;font-size:13px}\
+.cards{display:grid;grid-template-columns:repeat(auto-fit,minmax(150px,1fr));gap:12px;margin-top:24px}\
+.card{background:#1f2937;border-radius:12px;padding:16px}\
+.card b{display:block;font-size:24px;margin-top:4px;color:#f9fafb}\
+table{width:100%;border-collapse:collapse}td{padding:8px 4px;border-bottom:1px solid #374151}\
+td.num{text-align:right;color:#9ca3af;white-space:nowrap}\
+svg text{fill:#9ca3af;font-size:12px}";
+
+/// 渲染自包含的 HTML 页面
+fn render_html(review: &YearReview) -> String {
+        let mut html = String::new();
+        let _ = write!(
+                html,
+                "<!DOCTYPE html><html lang=\"zh\"><head><meta charset=\"utf-8\">\
+                 <meta name=\"viewport\" content=\"width=device-width,initial-scale=1\">\
+                 <title>{year} 年度游玩回顾</title><style>{style}</style></head><body><main>\
+                 <h1>{year} 年度游玩回顾</h1><div class=\"muted\">生成于 {generated}</div>",
+                year = review.year,
+                style = STYLE,
+                generated = review.generated_at.format("%Y-%m-%d %H:%M"),
+        );
+
+        html.push_str("<div class=\"cards\">");
+        for (label, value) in [
+                ("总游玩时长", duration_text(review.total_minutes)),
+                ("游玩天数", format!("{} 天", review.days_played)),
+                ("玩过的游戏", format!("{} 款", review.games_played)),
+                ("游玩次数", format!("{} 次", review.sessions)),
+                ("通关", format!("{} 款", review.completed.len())),
+                ("截图", format!("{} 张", review.screenshots)),
+        ] {
+                let _ = write!(
+                        html,
+                        "<div class=\"card\"><span class=\"muted\">{}</span><b>{}</b></div>",
+                        label, value
+                );
+        }
+        html.push_str("</div>");
+
+        html.push_str("<h2>每月游玩时长</h2>");
+        html.push_str(&monthly_chart(&review.monthly));
+
+        if !review.top_games.is_empty() {
+                html.push_str("<h2>最常玩的游戏</h2>");
+                html.push_str(&top_games_chart(&review.top_games));
+        }
+
+        if !review.new_games.is_empty() {
+                html.push_str("<h2>今年初次游玩</h2><table>");
+                for game in &review.new_games {
+                        let _ = write!(
+                                html,
+                                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>",
+                                escape(&game.name),
+                                game.first_played_at.format("%m-%d"),
+                                duration_text(game.minutes)
+                        );
+                }
+                html.push_str("</table>");
+        }
+
+        if !review.completed.is_empty() {
+                html.push_str("<h2>今年通关</h2><table>");
+                for game in &review.completed {
+                        let _ = write!(
+                                html,
+                                "<tr><td>{}</td><td class=\"num\">{}</td><td class=\"num\">累计 {}</td></tr>",
+                                escape(&game.name),
+                                game.passed_at.format("%m-%d"),
+                                duration_text(game.play_time)
+                        );
+                }
+                html.push_str("</table>");
+        }
+
+        if review.longest_session.is_some() || review.most_screenshotted.is_some() {
+                html.push_str("<h2>之最</h2><table>");
+                if let Some(session) = &review.longest_session {
+                        let _ = write!(
+                                html,
+                                "<tr><td>最长的一次游玩</td><td>{}</td><td class=\"num\">{} · {}</td></tr>",
+                                escape(&session.name),
+                                session.play_date.format("%m-%d"),
+                                duration_text(session.duration_minutes)
+                        );
+                }
+                if let Some(game) = &review.most_screenshotted {
+                        let _ = write!(
+                                html,
+                                "<tr><td>截图最多</td><td>{}</td><td class=\"num\">{} 张</td></tr>",
+                                escape(&game.name),
+                                game.screenshots
+                        );
+                }
+                html.push_str("</table>");
+        }
+
+        html.push_str("</main></body></html>");
+        html
+}
+
+/// 每月时长的柱状图
+fn monthly_chart(monthly: &[MonthTotal]) -> String {
+        const WIDTH: f64 = 720.0;
+        const HEIGHT: f64 = 200.0;
+        const BAR_AREA: f64 = 160.0;
+        let max = monthly.iter().map(|m| m.minutes).max().unwrap_or(0).max(1) as f64;
+        let slot = WIDTH / monthly.len().max(1) as f64;
+
+        let mut svg = format!(
+                "<svg viewBox=\"0 0 {} {}\" width=\"100%\" role=\"img\">",
+                WIDTH, HEIGHT
+        );
+        for (i, month) in monthly.iter().enumerate() {
+                let height = month.minutes as f64 / max * BAR_AREA;
+                let x = i as f64 * slot + slot * 0.2;
+                let y = BAR_AREA - height + 16.0;
+                let _ = write!(
+                        svg,
+                        "<rect x=\"{x:.1}\" y=\"{y:.1}\" width=\"{w:.1}\" height=\"{height:.1}\" \
+                         rx=\"4\" fill=\"#34d399\"><title>{title}</title></rect>\
+                         <text x=\"{cx:.1}\" y=\"{ly:.1}\" text-anchor=\"middle\">{month}月</text>",
+                        w = slot * 0.6,
+                        title = duration_text(month.minutes),
+                        cx = x + slot * 0.3,
+                        ly = HEIGHT - 4.0,
+                        month = month.month,
+                );
+                if month.minutes > 0 {
+                        let _ = write!(
+                                svg,
+                                "<text x=\"{:.1}\" y=\"{:.1}\" text-anchor=\"middle\">{}</text>",
+                                x + slot * 0.3,
+                                y - 4.0,
+                                (month.minutes + 30) / 60
+                        );
+                }
+        }
+        svg.push_str("</svg><div class=\"muted\">柱顶数字为小时</div>");
+        svg
+}
+
+/// 游戏时长的横向条形图
+fn top_games_chart(games: &[ReviewGame]) -> String {
+        const WIDTH: f64 = 720.0;
+        const ROW: f64 = 32.0;
+        const LABEL: f64 = 220.0;
+        const VALUE: f64 = 110.0;
+        let max = games.iter().map(|g| g.minutes).max().unwrap_or(0).max(1) as f64;
+
+        let mut svg = format!(
+                "<svg viewBox=\"0 0 {} {}\" width=\"100%\" role=\"img\">",
+                WIDTH,
+                ROW * games.len() as f64
+        );
+        for (i, game) in games.iter().enumerate() {
+                let y = i as f64 * ROW;
+                let width = (game.minutes as f64 / max * (WIDTH - LABEL - VALUE)).max(2.0);
+                let _ = write!(
+                        svg,
+                        "<text x=\"0\" y=\"{ty:.1}\">{name}</text>\
+                         <rect x=\"{LABEL}\" y=\"{ry:.1}\" width=\"{width:.1}\" height=\"18\" rx=\"4\" \
+                         fill=\"#60a5fa\"/>\
+                         <text x=\"{vx:.1}\" y=\"{ty:.1}\">{value}</text>",
+                        ty = y + 21.0,
+                        name = escape(&truncate_name(&game.name, 16)),
+                        ry = y + 7.0,
+                        vx = LABEL + width + 8.0,
+                        value = duration_text(game.minutes),
+                );
+        }
+        svg.push_str("</svg>");
+        svg
+}
+
+fn duration_text(minutes: i64) -> String {
+        match (minutes / 60, minutes % 60) {
+                | (0, m) => format!("{} 分钟", m),
+                | (h, 0) => format!("{} 小时", h),
+                | (h, m) => format!("{} 小时 {} 分", h, m),
+        }
+}
+
+fn truncate_name(
+        name: &str,
+        max_chars: usize,
+) -> String {
+        if name.chars().count() <= max_chars {
+                return name.to_string();
+        }
+        let mut short: String = name.chars().take(max_chars - 1).collect();
+        short.push('…');
+        short
+}
+
+fn escape(text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+        for c in text.chars() {
+                match c {
+                        | '&' => out.push_str("&amp;"),
+                        | '<' => out.push_str("&lt;"),
+                        | '>' => out.push_str("&gt;"),
+                        | '"' => out.push_str("&quot;"),
+                        | '\'' => out.push_str("&#39;"),
+                        | _ => out.push(c),
+                }
+        }
+        out
+}
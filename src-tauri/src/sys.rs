@@ -77,20 +77,24 @@ fn restore_permissions(handle: &AppHandle) {
                                 },
                         };
 
-                let fs_scope = handle.fs_scope();
-                let asset_scope = handle.asset_protocol_scope();
-
                 for scope in scopes {
-                        let path = Path::new(&scope.path);
-                        let dir = path.parent().unwrap_or(path);
-                        debug!("恢复路径权限: {:?}", dir);
-
-                        if let Err(e) = fs_scope.allow_directory(dir, true) {
-                                error!("FS 权限恢复失败 {:?}: {}", dir, e);
-                        }
-                        if let Err(e) = asset_scope.allow_directory(dir, true) {
-                                error!("Asset 权限恢复失败 {:?}: {}", dir, e);
-                        }
+                        allow_scope(&handle, Path::new(&scope.path));
                 }
         });
 }
+
+/// 授予已记录路径所在目录的文件与资源访问权限，失败只记录日志
+pub fn allow_scope(
+        handle: &AppHandle,
+        path: &Path,
+) {
+        let dir = path.parent().unwrap_or(path);
+        debug!("恢复路径权限: {:?}", dir);
+
+        if let Err(e) = handle.fs_scope().allow_directory(dir, true) {
+                error!("FS 权限恢复失败 {:?}: {}", dir, e);
+        }
+        if let Err(e) = handle.asset_protocol_scope().allow_directory(dir, true) {
+                error!("Asset 权限恢复失败 {:?}: {}", dir, e);
+        }
+}
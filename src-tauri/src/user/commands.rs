@@ -0,0 +1,111 @@
+//! 用户信息
+//!
+//! 游戏数、累计时长、通关数与喜爱的游戏按需由 `games` / `game_progress` / `game_play_sessions`
+//! 计算，`account` 表只保存这几项的手动覆盖值。
+
+use chrono::{Duration, Local};
+use sqlx::SqlitePool;
+
+use crate::{
+        config::{entity::FavoriteGameRule, read_config},
+        error::AppError,
+        user::entity::User,
+};
+
+/// 读取档案的用户信息，统计项填入计算值或覆盖值
+pub async fn load(
+        pool: &SqlitePool,
+        account_id: &str,
+) -> Result<User, AppError> {
+        let mut user = sqlx::query_as::<_, User>("SELECT * FROM account WHERE id = ?")
+                .bind(account_id)
+                .fetch_optional(pool)
+                .await?
+                .ok_or_else(|| {
+                        AppError::Resolve(account_id.to_string(), "用户档案不存在".into())
+                })?;
+
+        let (games_count, total_play_time, games_completed_number): (i64, i64, i64) =
+                sqlx::query_as(
+                        "SELECT (SELECT COUNT(*) FROM games), COALESCE(SUM(play_time), 0), \
+                 COALESCE(SUM(is_passed != 0), 0) FROM game_progress WHERE account_id = ?",
+                )
+                .bind(account_id)
+                .fetch_one(pool)
+                .await?;
+        let overrides = &user.overrides;
+        user.games_count = overrides.games_count.unwrap_or(games_count);
+        user.total_play_time = overrides.total_play_time.unwrap_or(total_play_time);
+        user.games_completed_number = overrides
+                .games_completed_number
+                .unwrap_or(games_completed_number);
+        user.favorite_game = match overrides
+                .favorite_game
+                .clone()
+                .filter(|name| !name.trim().is_empty())
+        {
+                | Some(name) => name,
+                | None => favorite_game(pool, account_id).await?.unwrap_or_default(),
+        };
+        Ok(user)
+}
+
+/// 按配置的规则选出档案喜爱的游戏（游戏名）
+pub async fn favorite_game(
+        pool: &SqlitePool,
+        account_id: &str,
+) -> Result<Option<String>, AppError> {
+        let rule = read_config()
+                .map(|cfg| cfg.system.favorite_game_rule)
+                .unwrap_or_default();
+        let found: Option<String> = match rule {
+                | FavoriteGameRule::RecentMostPlayed { days } => {
+                        most_recent(pool, account_id, days, "SUM(s.duration_minutes)").await?
+                },
+                | FavoriteGameRule::RecentMostSessions { days } => {
+                        most_recent(pool, account_id, days, "COUNT(*)").await?
+                },
+                | FavoriteGameRule::MostPlayed => None,
+                | FavoriteGameRule::LastPlayed => sqlx::query_scalar(
+                        "SELECT g.name FROM game_progress p JOIN games g ON g.id = p.game_id \
+                         WHERE p.account_id = ? AND p.last_played_at IS NOT NULL \
+                         ORDER BY p.last_played_at DESC LIMIT 1",
+                )
+                .bind(account_id)
+                .fetch_optional(pool)
+                .await?,
+        };
+        if found.is_some() {
+                return Ok(found);
+        }
+        sqlx::query_scalar(
+                "SELECT g.name FROM game_progress p JOIN games g ON g.id = p.game_id \
+         WHERE p.account_id = ? AND p.play_time > 0 \
+         ORDER BY p.play_time DESC, p.last_played_at DESC LIMIT 1",
+        )
+        .bind(account_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::from)
+}
+
+/// 最近 `days` 天内按 `metric` 排名第一的游戏
+async fn most_recent(
+        pool: &SqlitePool,
+        account_id: &str,
+        days: u32,
+        metric: &str,
+) -> Result<Option<String>, AppError> {
+        let since = (Local::now() - Duration::days(days.into())).date_naive();
+        sqlx::query_scalar(&format!(
+                "SELECT g.name FROM game_play_sessions s JOIN games g ON g.id = s.game_id \
+         WHERE s.account_id = ? AND s.is_open = 0 AND s.play_date >= ? GROUP BY s.game_id \
+         ORDER BY {} DESC, MAX(s.play_date) DESC LIMIT 1",
+                metric
+        ))
+        .bind(account_id)
+        .bind(since)
+        .fetch_optional(pool)
+        .await
+        .map_err(AppError::from)
+}
@@ -1,17 +1,89 @@
 use chrono::{DateTime, Local};
 use serde::{Deserialize, Serialize};
 
+use crate::config::entity::{Authorization, Config, Interface};
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
 #[serde(rename_all = "camelCase")]
 pub struct User {
         pub id: String,
         pub user_name: String,
         pub avatar: String,
+        /// 以下四项由游戏库计算；设置了覆盖值时为覆盖值，保存时忽略
+        #[sqlx(skip)]
         pub games_count: i64,
+        #[sqlx(skip)]
         pub favorite_game: String,
+        #[sqlx(skip)]
         pub total_play_time: i64,
+        #[sqlx(skip)]
         pub games_completed_number: i64,
         pub selected_disk: Option<String>,
         pub last_play_at: Option<DateTime<Local>>,
         pub created_at: Option<DateTime<Local>>,
+        /// 是否为当前档案，保存时忽略
+        #[serde(default)]
+        #[sqlx(default)]
+        pub is_active: bool,
+        /// 手动覆盖的统计值
+        #[serde(default)]
+        #[sqlx(flatten)]
+        pub overrides: UserOverrides,
+}
+
+/// 统计值的手动覆盖，`None` 表示使用计算值
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize, sqlx::FromRow)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOverrides {
+        pub games_count: Option<i64>,
+        pub favorite_game: Option<String>,
+        pub total_play_time: Option<i64>,
+        pub games_completed_number: Option<i64>,
+}
+
+/// 随档案切换的设置：切换走时从当前配置中保存，切换回来时恢复，
+/// 没有保存过的项沿用切换前的配置
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigOverrides {
+        #[serde(default)]
+        pub language: Option<String>,
+        /// 首页游戏展示顺序
+        #[serde(default)]
+        pub game_display_order: Option<Vec<String>>,
+        #[serde(default)]
+        pub interface: Option<Interface>,
+        #[serde(default)]
+        pub auth: Option<Authorization>,
+}
+
+impl ConfigOverrides {
+        /// 取出配置中随档案切换的部分
+        pub fn capture(config: &Config) -> Self {
+                Self {
+                        language: Some(config.basic.language.clone()),
+                        game_display_order: Some(config.basic.game_display_order.clone()),
+                        interface: Some(config.interface.clone()),
+                        auth: Some(config.auth.clone()),
+                }
+        }
+
+        /// 把保存的设置写入配置
+        pub fn apply(
+                self,
+                config: &mut Config,
+        ) {
+                if let Some(language) = self.language {
+                        config.basic.language = language;
+                }
+                if let Some(order) = self.game_display_order {
+                        config.basic.game_display_order = order;
+                }
+                if let Some(interface) = self.interface {
+                        config.interface = interface;
+                }
+                if let Some(auth) = self.auth {
+                        config.auth = auth;
+                }
+        }
 }
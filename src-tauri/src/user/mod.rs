@@ -1 +1,5 @@
+//! 用户信息与用户档案
+
+pub mod commands;
 pub mod entity;
+pub mod profile;
@@ -0,0 +1,207 @@
+//! 用户档案
+//!
+//! 同一台机器上的多个档案共用已安装的游戏，各自有游玩记录、游戏进度（`game_progress`）、
+//! 收藏夹、快捷键与一部分设置（见 [`ConfigOverrides`]）。
+//! 当前档案以 `account.is_active` 标记，启动时读入内存，各模块通过 [`active_id`] 取得。
+//! 切换档案不会中断正在进行的会话，它们仍记在启动时的档案下。
+
+use std::sync::RwLock;
+
+use chrono::Local;
+use lazy_static::lazy_static;
+use sqlx::{SqlitePool, types::Json};
+use tauri::{AppHandle, Manager};
+use tauri_plugin_log::log::info;
+use uuid::Uuid;
+
+use crate::{
+        config::{publish_changes, read_config, write_config},
+        error::AppError,
+        shortcut::commands::refresh_shortcuts,
+        user::{
+                commands::load,
+                entity::{ConfigOverrides, User},
+        },
+};
+
+lazy_static! {
+    /// 当前档案的 ID
+    static ref ACTIVE_ID: RwLock<String> = RwLock::new(String::new());
+}
+
+/// 当前档案的 ID
+pub fn active_id() -> Result<String, AppError> {
+        ACTIVE_ID
+                .read()
+                .map(|id| id.clone())
+                .map_err(|e| AppError::Lock(e.to_string()))
+}
+
+fn set_active_id(id: String) -> Result<(), AppError> {
+        *ACTIVE_ID
+                .write()
+                .map_err(|e| AppError::Lock(e.to_string()))? = id;
+        Ok(())
+}
+
+/// 读入当前档案，在 life_cycle::init 中、其他依赖档案的模块之前调用
+pub fn init(handle: &AppHandle) -> Result<(), AppError> {
+        let pool = handle.state::<SqlitePool>();
+        let id = tauri::async_runtime::block_on(ensure_active(&pool))?;
+        info!("当前用户档案: {}", id);
+        set_active_id(id)
+}
+
+/// 当前档案的 ID；没有标记时取最早创建的档案，一个档案都没有时新建默认档案
+async fn ensure_active(pool: &SqlitePool) -> Result<String, AppError> {
+        let active: Option<String> =
+                sqlx::query_scalar("SELECT id FROM account WHERE is_active = 1")
+                        .fetch_optional(pool)
+                        .await?;
+        if let Some(id) = active {
+                return Ok(id);
+        }
+
+        let first: Option<String> =
+                sqlx::query_scalar("SELECT id FROM account ORDER BY created_at, id LIMIT 1")
+                        .fetch_optional(pool)
+                        .await?;
+        let id = match first {
+                | Some(id) => id,
+                | None => {
+                        sqlx::query(
+                                "INSERT INTO account (id, user_name, created_at) VALUES ('default', 'user', ?)",
+                        )
+                        .bind(Local::now())
+                        .execute(pool)
+                        .await?;
+                        "default".to_string()
+                },
+        };
+        sqlx::query("UPDATE account SET is_active = 1 WHERE id = ?")
+                .bind(&id)
+                .execute(pool)
+                .await?;
+        Ok(id)
+}
+
+/// 全部档案，按创建时间
+pub async fn list(pool: &SqlitePool) -> Result<Vec<User>, AppError> {
+        let ids: Vec<String> = sqlx::query_scalar("SELECT id FROM account ORDER BY created_at, id")
+                .fetch_all(pool)
+                .await?;
+        let mut users = Vec::with_capacity(ids.len());
+        for id in ids {
+                users.push(load(pool, &id).await?);
+        }
+        Ok(users)
+}
+
+/// 新建档案，快捷键沿用当前档案的设置
+pub async fn create(
+        pool: &SqlitePool,
+        user_name: &str,
+) -> Result<User, AppError> {
+        let user_name = user_name.trim();
+        if user_name.is_empty() {
+                return Err(AppError::Generic("档案名称不能为空".into()));
+        }
+        let id = Uuid::new_v4().to_string();
+
+        let mut tx = pool.begin().await?;
+        sqlx::query("INSERT INTO account (id, user_name, created_at) VALUES (?, ?, ?)")
+                .bind(&id)
+                .bind(user_name)
+                .bind(Local::now())
+                .execute(&mut *tx)
+                .await?;
+        sqlx::query(
+                "INSERT INTO shortcut (account_id, id, key_combo, is_global) \
+         SELECT ?, id, key_combo, is_global FROM shortcut WHERE account_id = ?",
+        )
+        .bind(&id)
+        .bind(active_id()?)
+        .execute(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        info!("已新建用户档案: {}", user_name);
+        load(pool, &id).await
+}
+
+/// 删除档案及其游玩记录、进度、收藏夹与快捷键；不能删除当前档案
+pub async fn delete(
+        pool: &SqlitePool,
+        id: &str,
+) -> Result<(), AppError> {
+        if id == active_id()? {
+                return Err(AppError::Resolve(
+                        id.to_string(),
+                        "不能删除当前档案，请先切换到其他档案".into(),
+                ));
+        }
+        let deleted = sqlx::query("DELETE FROM account WHERE id = ?")
+                .bind(id)
+                .execute(pool)
+                .await?;
+        if deleted.rows_affected() == 0 {
+                return Err(AppError::Resolve(id.to_string(), "用户档案不存在".into()));
+        }
+        info!("已删除用户档案: {}", id);
+        Ok(())
+}
+
+/// 切换当前档案：保存当前档案的设置，恢复目标档案的设置，并重新注册快捷键
+pub async fn switch(
+        handle: &AppHandle,
+        pool: &SqlitePool,
+        id: &str,
+) -> Result<User, AppError> {
+        let previous = active_id()?;
+        if id == previous {
+                return load(pool, id).await;
+        }
+        let current = read_config()?.clone();
+
+        let mut tx = pool.begin().await?;
+        let Some(Json(overrides)) = sqlx::query_scalar::<_, Json<ConfigOverrides>>(
+                "SELECT config_overrides FROM account WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?
+        else {
+                return Err(AppError::Resolve(id.to_string(), "用户档案不存在".into()));
+        };
+        sqlx::query("UPDATE account SET config_overrides = ? WHERE id = ?")
+                .bind(Json(ConfigOverrides::capture(&current)))
+                .bind(&previous)
+                .execute(&mut *tx)
+                .await?;
+        // 先清除旧标记再设置新标记，否则会违反「只有一个当前档案」的唯一索引
+        sqlx::query("UPDATE account SET is_active = 0 WHERE is_active = 1")
+                .execute(&mut *tx)
+                .await?;
+        sqlx::query("UPDATE account SET is_active = 1 WHERE id = ?")
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        tx.commit().await?;
+        set_active_id(id.to_string())?;
+
+        // 事件只负责副作用，配置由监听循环异步写入；这里同步写入，切换后立即读取配置也是新档案的
+        let mut config = current;
+        overrides.apply(&mut config);
+        publish_changes(config.clone())?;
+        {
+                let mut cfg = write_config()?;
+                cfg.basic.language = config.basic.language;
+                cfg.basic.game_display_order = config.basic.game_display_order;
+                cfg.interface = config.interface;
+                cfg.auth = config.auth;
+        }
+
+        refresh_shortcuts(handle).await?;
+        info!("已切换到用户档案: {}", id);
+        load(pool, id).await
+}
@@ -17,6 +17,7 @@ export enum Cmds {
   START_GAME = "start_game",
   GET_SESSIONS = "get_sessions",
   GET_SESSIONS_BY_YEAR = "get_sessions_by_year",
+  CREATE_SESSION = "create_session",
 
   // 压缩包
   GET_ARCHIVE_LIST = "get_archive_list",
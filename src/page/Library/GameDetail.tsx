@@ -34,7 +34,7 @@ const itemVariants: Variants = {
 
 export default function GameDetail() {
   const { id } = useParams<{ id: string }>()
-  const { getGameMetaById, setGameMeta } = useGameStore()
+  const { getGameMetaById, setGameMeta, addPlayTime } = useGameStore()
   const { updateConfig } = useConfigStore()
   const navigate = useNavigate()
 
@@ -44,6 +44,7 @@ export default function GameDetail() {
   const [syncMode, setSyncMode] = useState<'bangumi' | 'vndb'>('bangumi')
   const [inputId, setInputId] = useState('')
   const [collectionOpen, setCollectionOpen] = useState(false)
+  const [playTimeDraft, setPlayTimeDraft] = useState<number | null>(null)
 
   useEffect(() => {
     async function getGame() {
@@ -121,6 +122,24 @@ export default function GameDetail() {
     }
   }
 
+  // 游玩时长由会话累计：增加的部分录入为一次手动会话，减少需要在会话列表中修改或删除会话
+  const commitPlayTime = async () => {
+    if (playTimeDraft === null) return
+    const delta = playTimeDraft - game.playTime
+    setPlayTimeDraft(null)
+    if (delta === 0) return
+    if (delta < 0) {
+      toast.error(t`减少游玩时长请在游玩记录中修改或删除对应的会话`)
+      return
+    }
+    try {
+      setGame(await addPlayTime(game.id, delta))
+      toast.success(t`已补记 ${delta} 分钟游玩时长`)
+    } catch (err: any) {
+      toast.error(t`补记失败: ` + (err?.details || err?.message || String(err)))
+    }
+  }
+
   const updateField = async <K extends keyof GameMeta>(field: K, value: GameMeta[K]) => {
     const previousGame = game
     const updatedGame = { ...game, [field]: value }
@@ -286,8 +305,10 @@ export default function GameDetail() {
                 {/* 这里的 input 已经通过全局 style 去除了小箭头 */}
                 <input
                   type="number"
-                  value={game.playTime}
-                  onChange={(e) => updateField('playTime', parseInt(e.target.value) || 0)}
+                  value={playTimeDraft ?? game.playTime}
+                  onChange={(e) => setPlayTimeDraft(parseInt(e.target.value) || 0)}
+                  onBlur={commitPlayTime}
+                  onKeyDown={(e) => e.key === 'Enter' && e.currentTarget.blur()}
                   className="bg-transparent border-none focus:ring-0 p-0 text-4xl! font-[1000] font-mono text-custom-500 w-32 outline-none appearance-none m-0"
                 />
               </div>
@@ -26,7 +26,7 @@ interface EditUserInfoDialogProps {
 }
 
 const EditUserInfoDialog: React.FC<EditUserInfoDialogProps> = ({ isOpen, onClose }) => {
-  const { user, setUser } = useUserStore()
+  const { user, setUser, refreshUser } = useUserStore()
   const [formData, setFormData] = useState<User | null>(null)
 
   useEffect(() => {
@@ -70,7 +70,8 @@ const EditUserInfoDialog: React.FC<EditUserInfoDialogProps> = ({ isOpen, onClose
   const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault()
     if (formData) {
-      setUser(formData)
+      // 保存后重新读取，显示按覆盖值计算后的统计项
+      setUser(formData).then(refreshUser)
       toast.success(t`用户信息同步成功`)
       onClose()
     }
@@ -154,8 +155,12 @@ const EditUserInfoDialog: React.FC<EditUserInfoDialogProps> = ({ isOpen, onClose
                 <Sparkles size={12} className="text-zinc-300" /><Trans> 最喜欢的游戏</Trans>
               </Label>
               <Input
-                value={formData.favoriteGame}
-                onChange={e => setFormData({ ...formData, favoriteGame: e.target.value })}
+                value={formData.overrides?.favoriteGame ?? ""}
+                placeholder={formData.favoriteGame}
+                onChange={e => setFormData({
+                  ...formData,
+                  overrides: { ...formData.overrides, favoriteGame: e.target.value || null },
+                })}
                 className="h-14 rounded-2xl border-none bg-zinc-50 px-5 font-bold text-zinc-700 focus:ring-2 focus:ring-zinc-100"
               />
             </div>
@@ -23,7 +23,7 @@ import { SystemMonitor } from "./SystemMonitor"
 
 // ── 主页面 ────────────────────────────────────────────────────────────────────
 export default function User() {
-  const { user, setUser } = useUserStore()
+  const { user, refreshUser } = useUserStore()
   const { gameMetaList } = useGameStore()
   const bgStyle = usePageBackground()
   const [editOpen, setEditOpen] = useState(false)
@@ -31,12 +31,9 @@ export default function User() {
   const [journeyYear, setJourneyYear] = useState(new Date().getFullYear())
   const [journeyMonth, setJourneyMonth] = useState(new Date().getMonth() + 1)
 
+  // 统计项由后端按游戏库计算，游戏列表变化后重新读取
   useEffect(() => {
-    if (!user) return
-    const count = gameMetaList.filter(g => g.isPassed).length
-    const mins = gameMetaList.reduce((s, g) => s + (g.playTime || 0), 0)
-    if (user.gamesCompletedNumber !== count || user.totalPlayTime !== mins)
-      setUser({ gamesCompletedNumber: count, totalPlayTime: mins })
+    refreshUser()
   }, [gameMetaList])
 
   const avatarSrc = useMemo(() => {
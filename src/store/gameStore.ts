@@ -12,6 +12,7 @@ interface GameStoreParams {
   updateSelectedGame: (game: GameMeta | null) => void,
   setGameMetaList: (gameMetaList: GameMetaList) => void,
   setGameMeta: (game: GameMeta) => Promise<void>,
+  addPlayTime: (id: string, minutes: number) => Promise<GameMeta>,
   discardGame: (id: string) => Promise<void>,
   filterGameMetaListByName: (name: string) => GameMetaList
   getGameMetaById: (id: string) => GameMeta
@@ -81,6 +82,31 @@ const useGameStore = create<GameStoreParams>()(
       }
     },
 
+    /**
+     * 手动补记游玩时长：录入一次手动会话（留有修改记录），再读回游戏的最新时长
+     * @param id - 游戏id
+     * @param minutes - 补记的分钟数
+     */
+    addPlayTime: async (id, minutes) => {
+      const now = new Date()
+      await invoke(Cmds.CREATE_SESSION, {
+        session: {
+          gameId: id,
+          playDate: new Date(now.getTime() - minutes * 60_000).toISOString(),
+          durationMinutes: minutes,
+          lastPlayedAt: now.toISOString(),
+        },
+      })
+      const game = await invoke<GameMeta>(Cmds.GET_GAME_META, { id })
+      set((state) => {
+        state.gameMetaList = state.gameMetaList.map((g) => g.id === id ? game : g)
+        if (state.selectedGame?.id === id) {
+          state.selectedGame = game
+        }
+      })
+      return game
+    },
+
     /**
      * 根据名称过滤游戏列表
      * @param name - 搜索关键词
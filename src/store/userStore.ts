@@ -11,7 +11,12 @@ interface UserStore {
    * 设置/更新并且更新数据库
    * @param user 要更新的用户数据
    */
-  setUser: (fields: Partial<User>) => void
+  setUser: (fields: Partial<User>) => Promise<void>
+
+  /**
+   * 从后端重新读取用户信息（统计项由后端计算），不写回数据库
+   */
+  refreshUser: () => Promise<void>
 }
 
 const useUserStore = create<UserStore>()(
@@ -29,12 +34,24 @@ const useUserStore = create<UserStore>()(
 
       const updatedUser = get().user;
       if (updatedUser) {
-        invoke(Cmds.UPDATE_USER_INFO, {
+        return invoke<void>(Cmds.UPDATE_USER_INFO, {
           account: updatedUser,
         }).catch((err) => {
           console.error("同步用户信息失败:", err);
         });
       }
+      return Promise.resolve();
+    },
+
+    refreshUser: async () => {
+      try {
+        const user = await invoke<User>(Cmds.GET_USER_INFO)
+        set((state) => {
+          state.user = user
+        })
+      } catch (err) {
+        console.error("读取用户信息失败:", err)
+      }
     },
   }))
 )
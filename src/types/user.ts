@@ -2,6 +2,7 @@ export interface User {
   id: string
   userName: string
   avatar: string
+  /** 以下四项由后端按游戏库计算；设置了覆盖值时为覆盖值 */
   gamesCount: number
   favoriteGame: string
   totalPlayTime: number
@@ -9,4 +10,14 @@ export interface User {
   selectedDisk: string | null
   lastPlayAt: string | null
   createdAt: string | null
+  isActive?: boolean
+  /** 手动覆盖的统计值，保存时只写入这一部分，null 表示使用计算值 */
+  overrides: UserOverrides
+}
+
+export interface UserOverrides {
+  gamesCount: number | null
+  favoriteGame: string | null
+  totalPlayTime: number | null
+  gamesCompletedNumber: number | null
 }